@@ -1,7 +1,17 @@
 use criterion::{black_box, criterion_group, criterion_main, Criterion};
-use fatigue::interpolate::{InterpolationStrategyEnum, Linear, NDInterpolation};
+use evalexpr::{
+    build_operator_tree, ContextWithMutableVariables, HashMapContext, Value as ExprValue,
+};
+use fatigue::expression::CompiledExpression;
+use fatigue::interpolate::{
+    Delaunay, Extrapolation, Grid, GridOrder, InterpolationStrategy, InterpolationStrategyEnum,
+    InverseDistance, Linear, NDInterpolation, NearestNeighbor, Rbf, RbfKernel,
+};
 use fatigue::rainflow::rainflow;
+use fatigue::timeseries::ParseConfig;
 use fatigue::timeseries::Point;
+use fatigue::timeseries::{read_cached_stress_tensors, read_stress_tensors_from_file};
+use nalgebra::DMatrix;
 use rand::distributions::{Distribution, Uniform}; // 0.6.5
 
 fn setup_large_dataset(interpolator: &mut NDInterpolation) {
@@ -48,16 +58,429 @@ fn bench_linear_interpolation(c: &mut Criterion) {
     });
 }
 
+/// Unit-load points in 3D with the stress tensors of many nodes, one row per point.
+fn setup_node_tensors(nodes: usize) -> (Vec<Point>, DMatrix<f64>) {
+    let coordinates = [
+        [0.0, 0.0, 0.0],
+        [1.0, 0.0, 0.0],
+        [0.0, 1.0, 0.0],
+        [0.0, 0.0, 1.0],
+    ];
+    let points = coordinates
+        .iter()
+        .map(|c| Point {
+            coordinates: c.to_vec(),
+            file: None,
+        })
+        .collect::<Vec<_>>();
+    let step = Uniform::new(-100.0, 100.0);
+    let mut rng = rand::thread_rng();
+    let values = DMatrix::from_fn(points.len(), 6 * nodes, |_, _| step.sample(&mut rng));
+    (points, values)
+}
+
+fn setup_time_steps(samples: usize) -> Vec<Vec<f64>> {
+    let step = Uniform::new(-1.0, 1.0);
+    let mut rng = rand::thread_rng();
+    (0..samples)
+        .map(|_| (0..3).map(|_| step.sample(&mut rng)).collect())
+        .collect()
+}
+
+fn bench_interpolation_per_node(c: &mut Criterion) {
+    c.bench_function(
+        "linear interpolation fitted per node, 1000 nodes x 2000 steps",
+        |b| {
+            let (points, values) = setup_node_tensors(1000);
+            let target = setup_time_steps(2000);
+            b.iter(|| {
+                for node in 0..1000 {
+                    let tensor = values.columns(6 * node, 6).into_owned();
+                    black_box(
+                        Linear
+                            .interpolate_values(&points, &tensor, &target)
+                            .unwrap(),
+                    );
+                }
+            });
+        },
+    );
+}
+
+fn bench_interpolation_per_block(c: &mut Criterion) {
+    c.bench_function(
+        "linear interpolation fitted per block, 1000 nodes x 2000 steps",
+        |b| {
+            let (points, values) = setup_node_tensors(1000);
+            let target = setup_time_steps(2000);
+            b.iter(|| {
+                for block in 0..4 {
+                    let tensors = values.columns(6 * 250 * block, 6 * 250).into_owned();
+                    black_box(
+                        Linear
+                            .interpolate_values(&points, &tensors, &target)
+                            .unwrap(),
+                    );
+                }
+            });
+        },
+    );
+}
+
+fn bench_interpolation_prepared(c: &mut Criterion) {
+    c.bench_function(
+        "linear interpolation prepared once, 1000 nodes x 2000 steps",
+        |b| {
+            let (points, values) = setup_node_tensors(1000);
+            let target = setup_time_steps(2000);
+            b.iter(|| {
+                let weights = Linear.prepare(&points).unwrap().weights(&target).unwrap();
+                for block in 0..4 {
+                    black_box(&weights * values.columns(6 * 250 * block, 6 * 250));
+                }
+            });
+        },
+    );
+}
+
+/// The corners of the cube [-1, 1]^3, which holds the time steps, and scattered points inside.
+fn setup_scattered_points(points: usize) -> Vec<Point> {
+    let step = Uniform::new(-1.0, 1.0);
+    let mut rng = rand::thread_rng();
+    let corners = (0..8).map(|corner: usize| {
+        (0..3)
+            .map(|axis| if corner >> axis & 1 == 1 { 1.0 } else { -1.0 })
+            .collect()
+    });
+    let inside = (0..points - 8).map(|_| (0..3).map(|_| step.sample(&mut rng)).collect());
+    corners
+        .chain(inside)
+        .map(|coordinates| Point {
+            coordinates,
+            file: None,
+        })
+        .collect()
+}
+
+fn bench_delaunay_prepare(c: &mut Criterion) {
+    c.bench_function("Delaunay triangulation of 200 scattered points", |b| {
+        let points = setup_scattered_points(200);
+        let delaunay = Delaunay {
+            extrapolation: Extrapolation::Error,
+        };
+        b.iter(|| black_box(delaunay.prepare(&points).unwrap()));
+    });
+}
+
+fn bench_delaunay_weights(c: &mut Criterion) {
+    c.bench_function("Delaunay weights, 200 scattered points x 2000 steps", |b| {
+        let points = setup_scattered_points(200);
+        let target = setup_time_steps(2000);
+        let prepared = Delaunay {
+            extrapolation: Extrapolation::Error,
+        }
+        .prepare(&points)
+        .unwrap();
+        b.iter(|| black_box(prepared.weights(&target).unwrap()));
+    });
+}
+
+fn bench_delaunay_weights_dense(c: &mut Criterion) {
+    c.bench_function(
+        "Delaunay weights, 3000 scattered points x 2000 steps",
+        |b| {
+            let points = setup_scattered_points(3000);
+            let target = setup_time_steps(2000);
+            let prepared = Delaunay {
+                extrapolation: Extrapolation::Error,
+            }
+            .prepare(&points)
+            .unwrap();
+            b.iter(|| black_box(prepared.weights(&target).unwrap()));
+        },
+    );
+}
+
+/// The points of a grid over the cube [-1, 1]^3 with `values` coordinates per axis.
+fn setup_grid_points(values: usize) -> Vec<Point> {
+    let coordinate = |index: usize| -1.0 + 2.0 * index as f64 / (values - 1) as f64;
+    (0..values.pow(3))
+        .map(|index| Point {
+            coordinates: (0..3)
+                .map(|axis| coordinate(index / values.pow(axis) % values))
+                .collect(),
+            file: None,
+        })
+        .collect()
+}
+
+fn grid(order: GridOrder) -> Grid {
+    Grid {
+        order,
+        extrapolation: Extrapolation::Error,
+        axes: None,
+    }
+}
+
+fn bench_grid_prepare(c: &mut Criterion) {
+    for (order, name) in [
+        (GridOrder::Linear, "multilinear"),
+        (GridOrder::Cubic, "cubic"),
+    ] {
+        c.bench_function(&format!("{name} grid of 5 x 5 x 5 points prepared"), |b| {
+            let points = setup_grid_points(5);
+            let grid = grid(order);
+            b.iter(|| black_box(grid.prepare(&points).unwrap()));
+        });
+    }
+}
+
+fn bench_grid_weights(c: &mut Criterion) {
+    for (order, name) in [
+        (GridOrder::Linear, "multilinear"),
+        (GridOrder::Cubic, "cubic"),
+    ] {
+        c.bench_function(
+            &format!("{name} grid weights, 5 x 5 x 5 points x 2000 steps"),
+            |b| {
+                let points = setup_grid_points(5);
+                let target = setup_time_steps(2000);
+                let prepared = grid(order).prepare(&points).unwrap();
+                b.iter(|| black_box(prepared.weights(&target).unwrap()));
+            },
+        );
+    }
+}
+
+fn thin_plate() -> Rbf {
+    Rbf {
+        kernel: RbfKernel::ThinPlate,
+        shape: 1.0,
+        polynomial: true,
+    }
+}
+
+fn bench_rbf_prepare(c: &mut Criterion) {
+    c.bench_function(
+        "thin-plate RBF system of 200 scattered points solved",
+        |b| {
+            let points = setup_scattered_points(200);
+            b.iter(|| black_box(thin_plate().prepare(&points).unwrap()));
+        },
+    );
+}
+
+fn bench_rbf_weights(c: &mut Criterion) {
+    c.bench_function(
+        "thin-plate RBF weights, 200 scattered points x 2000 steps",
+        |b| {
+            let points = setup_scattered_points(200);
+            let target = setup_time_steps(2000);
+            let prepared = thin_plate().prepare(&points).unwrap();
+            b.iter(|| black_box(prepared.weights(&target).unwrap()));
+        },
+    );
+}
+
+fn bench_inverse_distance_prepare(c: &mut Criterion) {
+    c.bench_function("inverse distance k-d tree of 200 scattered points", |b| {
+        let points = setup_scattered_points(200);
+        let strategy = InverseDistance {
+            power: 2.0,
+            neighbors: Some(8),
+        };
+        b.iter(|| black_box(strategy.prepare(&points).unwrap()));
+    });
+}
+
+fn bench_inverse_distance_weights(c: &mut Criterion) {
+    for neighbors in [None, Some(8)] {
+        let name = match neighbors {
+            Some(k) => format!("{k} nearest"),
+            None => "all".to_string(),
+        };
+        c.bench_function(
+            &format!("inverse distance weights of {name} of 200 points x 2000 steps"),
+            |b| {
+                let points = setup_scattered_points(200);
+                let target = setup_time_steps(2000);
+                let prepared = InverseDistance {
+                    power: 2.0,
+                    neighbors,
+                }
+                .prepare(&points)
+                .unwrap();
+                b.iter(|| black_box(prepared.weights(&target).unwrap()));
+            },
+        );
+    }
+}
+
+/// A lookup table of uniformly scattered 3D points with their values.
+fn setup_lookup_table(points: usize) -> (Vec<Vec<f64>>, Vec<f64>) {
+    let step = Uniform::new(-100.0, 100.0);
+    let mut rng = rand::thread_rng();
+    let coordinates: Vec<Vec<f64>> = (0..points)
+        .map(|_| (0..3).map(|_| step.sample(&mut rng)).collect())
+        .collect();
+    let values = coordinates.iter().map(|c| c.iter().sum()).collect();
+    (coordinates, values)
+}
+
+fn bench_nearest_neighbor_scan(c: &mut Criterion) {
+    c.bench_function(
+        "nearest neighbor by linear scan, 50k points x 2000 targets",
+        |b| {
+            let (coordinates, values) = setup_lookup_table(50_000);
+            let target = setup_time_steps(2000);
+            b.iter(|| {
+                let result: Vec<f64> = target
+                    .iter()
+                    .map(|t| {
+                        let nearest = coordinates
+                            .iter()
+                            .map(|p| p.iter().zip(t).map(|(a, b)| (a - b).powi(2)).sum::<f64>())
+                            .enumerate()
+                            .min_by(|a, b| a.1.total_cmp(&b.1))
+                            .map_or(0, |(index, _)| index);
+                        values[nearest]
+                    })
+                    .collect();
+                black_box(result)
+            });
+        },
+    );
+}
+
+fn bench_nearest_neighbor_kd_tree(c: &mut Criterion) {
+    c.bench_function(
+        "nearest neighbor by k-d tree, 50k points x 2000 targets",
+        |b| {
+            let (coordinates, values) = setup_lookup_table(50_000);
+            let target = setup_time_steps(2000);
+            let strategy = InterpolationStrategyEnum::NearestNeighbor(NearestNeighbor);
+            let mut interpolator = NDInterpolation::new(&strategy);
+            for (coordinates, value) in coordinates.into_iter().zip(values) {
+                interpolator.add_point(
+                    Point {
+                        coordinates,
+                        file: None,
+                    },
+                    value,
+                );
+            }
+            // The tree is built by the first interpolation and reused by the others.
+            interpolator.interpolate(&target).unwrap();
+            b.iter(|| black_box(interpolator.interpolate(&target).unwrap()));
+        },
+    );
+}
+
 fn bench_rainflow(c: &mut Criterion) {
     c.bench_function("Rainflow counting algorithm on large dataset", |b| {
         let step = Uniform::new(0.0, 50.0);
         let mut rng = rand::thread_rng();
         let choices: Vec<f64> = step.sample_iter(&mut rng).take(100000).collect();
+        b.iter(|| black_box(rainflow(&choices)));
+    });
+}
+
+const CHANNEL_EXPRESSION: &str = "math::sqrt(Fx^2 + Fy^2) * math::cos(angle) + Fz / 2";
+
+fn setup_channels(rows: usize) -> Vec<Vec<f64>> {
+    let step = Uniform::new(-1000.0, 1000.0);
+    let mut rng = rand::thread_rng();
+    (0..3)
+        .map(|_| step.sample_iter(&mut rng).take(rows).collect())
+        .collect()
+}
+
+fn bench_expression_per_sample(c: &mut Criterion) {
+    c.bench_function("expression per sample with evalexpr on 200k rows", |b| {
+        let channels = setup_channels(200_000);
+        let node = build_operator_tree(CHANNEL_EXPRESSION).unwrap();
         b.iter(|| {
-            let (_means, _ranges) = rainflow(&choices);
+            let mut context = HashMapContext::new();
+            context
+                .set_value("angle".into(), ExprValue::Float(0.3))
+                .unwrap();
+            let values: Vec<f64> = (0..channels[0].len())
+                .map(|row| {
+                    for (name, channel) in ["Fx", "Fy", "Fz"].iter().zip(&channels) {
+                        context
+                            .set_value(name.to_string(), ExprValue::Float(channel[row]))
+                            .unwrap();
+                    }
+                    node.eval_number_with_context(&context).unwrap()
+                })
+                .collect();
+            black_box(values);
         });
     });
 }
 
-criterion_group!(benches, bench_linear_interpolation, bench_rainflow);
+fn bench_expression_compiled(c: &mut Criterion) {
+    c.bench_function("compiled expression on 200k rows", |b| {
+        let channels = setup_channels(200_000);
+        let columns: Vec<&[f64]> = channels.iter().map(Vec::as_slice).collect();
+        let constants =
+            std::collections::HashMap::from([("angle".to_string(), ExprValue::Float(0.3))]);
+        let expression =
+            CompiledExpression::compile(CHANNEL_EXPRESSION, &["Fx", "Fy", "Fz"], &constants)
+                .unwrap();
+        b.iter(|| black_box(expression.evaluate(black_box(&columns)).unwrap()));
+    });
+}
+
+/// The 19k-node unit-load stress file; its `.usf.cache` is ignored by git.
+fn usf_path() -> std::path::PathBuf {
+    ["tests", "stressfile", "Fx.usf"].iter().collect()
+}
+
+fn bench_read_usf(c: &mut Criterion) {
+    let path = usf_path();
+    let parse_config = ParseConfig {
+        header: 0,
+        delimiter: " ".into(),
+    };
+    c.bench_function("read usf 19k nodes parsed", |b| {
+        b.iter(|| black_box(read_stress_tensors_from_file(&path, &parse_config).unwrap()))
+    });
+}
+
+fn bench_read_usf_cached(c: &mut Criterion) {
+    let path = usf_path();
+    let parse_config = ParseConfig {
+        header: 0,
+        delimiter: " ".into(),
+    };
+    read_cached_stress_tensors(&path, &parse_config, |_| true).unwrap();
+    c.bench_function("read usf 19k nodes cached", |b| {
+        b.iter(|| black_box(read_cached_stress_tensors(&path, &parse_config, |_| true).unwrap()))
+    });
+}
+
+criterion_group!(
+    benches,
+    bench_linear_interpolation,
+    bench_interpolation_per_node,
+    bench_interpolation_per_block,
+    bench_interpolation_prepared,
+    bench_delaunay_prepare,
+    bench_delaunay_weights,
+    bench_delaunay_weights_dense,
+    bench_grid_prepare,
+    bench_grid_weights,
+    bench_rbf_prepare,
+    bench_rbf_weights,
+    bench_inverse_distance_prepare,
+    bench_inverse_distance_weights,
+    bench_nearest_neighbor_scan,
+    bench_nearest_neighbor_kd_tree,
+    bench_rainflow,
+    bench_expression_per_sample,
+    bench_expression_compiled,
+    bench_read_usf,
+    bench_read_usf_cached
+);
 criterion_main!(benches);
@@ -1,27 +1,68 @@
 //! A module for the main application logic for the fatigue assessment tool
-use crate::config::load_config;
-pub use crate::stress::read_stress_tensors_from_file;
+use crate::assessment::{assess, Assessment};
+use crate::config::{load_config, Config};
+use crate::interpolate::Outside;
+use crate::report::{report_path, Report};
 use anyhow::Result;
-use std::path::PathBuf;
 
 pub fn run(config_path: &str) -> Result<(), Box<dyn std::error::Error>> {
     println!("Running with configuration: {}", config_path);
     let conf = load_config(config_path)?;
     let res = conf.timeseries.parse_input();
+    let selection = conf.solution.node.selection()?;
     for inter in conf.timeseries.interpolations.iter() {
         for point in inter.points.iter() {
             if let Some(ref file_name) = point.file {
-                let path = PathBuf::from(&inter.path).join(file_name); // Correctly constructs the path
-                let tensors = read_stress_tensors_from_file(&path)?; // Assuming the function accepts a `&Path`
+                let tensors = inter
+                    .read_stress_tensors(file_name, &conf.units, |node| selection.contains(node))?;
                 println!("Stress tensors: {:?}", tensors);
             }
         }
     }
 
     println!("Results: {:?}", res);
-    if let Err(err) = conf.validate() {
+    match conf.validate() {
         // Handle the error here
-        println!("Validation error: {:?}", err);
+        Err(err) => println!("Validation error: {:?}", err),
+        Ok(()) => {
+            let psds = conf.timeseries.loadcase_psds(&conf.units)?;
+            for channel in psds.iter() {
+                println!(
+                    "PSD {} {}: {} bins up to {} Hz, variance {}",
+                    channel.loadcase,
+                    channel.channel,
+                    channel.psd.frequencies.len(),
+                    channel.psd.frequencies.last().copied().unwrap_or_default(),
+                    channel.psd.moment(0)
+                );
+            }
+            if conf.solution.run_type == "FAT" {
+                let assessment = assess(&conf)?;
+                print_diagnostics(&conf, &assessment);
+                print_outside(&conf, &assessment);
+                if conf.solution.output == "JSON" {
+                    let path = report_path(&conf, config_path);
+                    Report::new(&conf, config_path, &assessment, &psds).write(&path)?;
+                    println!("Report: {}", path.display());
+                }
+                for set in assessment.sets {
+                    match set.max() {
+                        Some(max) => println!(
+                            "Set {} ({}): {} nodes, max damage {} at node {} plane {}",
+                            set.name,
+                            set.material,
+                            set.nodes.len(),
+                            max.damage,
+                            max.node,
+                            max.plane
+                        ),
+                        None => {
+                            println!("Set {} ({}): no nodes assessed", set.name, set.material)
+                        }
+                    }
+                }
+            }
+        }
     }
     println!("Configuration: {:?}", conf);
     // Here, you would add the logic to load the configuration from the specified path,
@@ -31,3 +72,63 @@ pub fn run(config_path: &str) -> Result<(), Box<dyn std::error::Error>> {
 
     Ok(())
 }
+
+/// Prints how well each assessed interpolation reproduces its unit-load stresses, and warns
+/// about points that share coordinates.
+fn print_diagnostics(conf: &Config, assessment: &Assessment) {
+    for (name, diagnostics) in assessment.diagnostics.iter() {
+        let Some(inter) = conf
+            .timeseries
+            .interpolations
+            .iter()
+            .find(|i| &i.name == name)
+        else {
+            continue;
+        };
+        for (first, second) in diagnostics.duplicates.iter() {
+            println!(
+                "Warning: interpolation '{}': points {} and {} share coordinates {:?}",
+                inter.name,
+                first.file.as_deref().unwrap_or_default(),
+                second.file.as_deref().unwrap_or_default(),
+                second.coordinates
+            );
+        }
+        let leave_one_out = match diagnostics.max_leave_one_out() {
+            Some(error) => format!("{:.3e}", error),
+            None => "n/a".to_string(),
+        };
+        let conditioning = match diagnostics.conditioning {
+            Some(c) => format!(
+                ", rank {} of {}, condition {:e}",
+                c.rank, c.columns, c.condition
+            ),
+            None => String::new(),
+        };
+        println!(
+            "Interpolation {} ({}): max residual {:.3e}, max leave-one-out error {}{}",
+            inter.name,
+            inter.method,
+            diagnostics.max_residual(),
+            leave_one_out,
+            conditioning
+        );
+    }
+}
+
+/// Warns about load-case samples outside the points of interpolations with `outside: WARN`.
+fn print_outside(conf: &Config, assessment: &Assessment) {
+    for samples in assessment.outside.iter().filter(|s| s.outside > 0) {
+        let warn = conf
+            .timeseries
+            .interpolations
+            .iter()
+            .any(|i| i.name == samples.interpolation && i.outside_policy() == Outside::Warn);
+        if warn {
+            println!(
+                "Warning: interpolation '{}': {} of {} samples of load case {} are outside the points",
+                samples.interpolation, samples.outside, samples.samples, samples.loadcase
+            );
+        }
+    }
+}
@@ -0,0 +1,890 @@
+//! A module for the time-domain fatigue assessment of node sets.
+//!
+//! The stresses of a node are superposed from the unit-load stress files of every
+//! interpolation, driven by the load-case channels, reduced to a scalar history by the
+//! stress criterion of the node's set, rainflow counted and summed with Palmgren-Miner
+//! against the S-N curve of the set's material.
+use crate::config::{Config, NodeSetSelection};
+use crate::interpolate::{
+    merge_duplicates, BoundingBox, Diagnostics, InterpolationStrategy, Outside, Point,
+    PreparedInterpolation,
+};
+use crate::material::{Material, StrainLife};
+use crate::notch::{NotchCorrection, NotchRule};
+use crate::rainflow::rainflow;
+use crate::stress::{StressCriterion, StressTensor};
+use crate::timeseries::{Interpolation, SampledSeries};
+use crate::units::UnitSystem;
+use anyhow::{anyhow, Result};
+use nalgebra::{DMatrix, Matrix3};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+/// The number of nodes whose stresses are interpolated together.
+const NODE_BLOCK: usize = 256;
+
+/// The name of the single set assessed when the configuration defines no node sets.
+pub const DEFAULT_SET: &str = "ALL";
+
+/// The fatigue damage of a node on its governing plane.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NodeDamage {
+    /// The node number.
+    pub node: usize,
+    /// The Palmgren-Miner damage summed over the load cases, weighted by their frequency.
+    pub damage: f64,
+    /// The plane of the stress criterion with the largest damage, 0 for single-plane criteria.
+    pub plane: usize,
+    /// The index in `TimeSeries.loadcases` of the load case with the largest damage on the
+    /// plane, or `None` if the node has no damage.
+    pub loadcase: Option<usize>,
+    /// The load-case family `LoadCase.fam` whose load cases sum to the largest damage on the
+    /// plane, or `None` if the node has no damage.
+    pub family: Option<usize>,
+}
+
+/// The fatigue damage of the nodes of a node set.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SetDamage {
+    /// The name of the node set.
+    pub name: String,
+    /// The name of the material the set is assessed with.
+    pub material: String,
+    /// The number of planes of the set's stress criterion, 1 for single-plane criteria.
+    pub planes: usize,
+    /// The damage of the assessed nodes of the set, in ascending node order.
+    pub nodes: Vec<NodeDamage>,
+}
+
+impl SetDamage {
+    /// Returns the node with the largest damage, or `None` if no node of the set was assessed.
+    pub fn max(&self) -> Option<&NodeDamage> {
+        self.nodes
+            .iter()
+            .max_by(|a, b| a.damage.total_cmp(&b.damage))
+    }
+}
+
+/// The samples of a load case outside the bounding box of the points of an interpolation,
+/// see `Interpolation.outside`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OutsideSamples {
+    /// The name of the interpolation.
+    pub interpolation: String,
+    /// The file of the load case.
+    pub loadcase: String,
+    /// The number of samples of the load case.
+    pub samples: usize,
+    /// The number of samples outside the bounding box, before any clamping.
+    pub outside: usize,
+}
+
+/// The result of `assess`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Assessment {
+    /// The damage per set in the order of `node_sets`.
+    pub sets: Vec<SetDamage>,
+    /// The samples outside each interpolation per load case, in the order of the load cases
+    /// and then of `TimeSeries.interpolations`.
+    pub outside: Vec<OutsideSamples>,
+    /// The name and diagnostics of each interpolation, in the order of
+    /// `TimeSeries.interpolations`, see `interpolation_diagnostics`.
+    pub diagnostics: Vec<(String, Diagnostics)>,
+}
+
+/// A node set resolved against the global defaults of the configuration.
+struct AssessedSet<'a> {
+    name: &'a str,
+    /// The nodes of the set, or `None` for all nodes of `Solution.node`.
+    selection: Option<NodeSetSelection>,
+    material: &'a Material,
+    criterion: StressCriterion,
+    /// The factor applied to the stress ranges besides the load-case `gf_fat`.
+    factor: f64,
+    /// The notch correction and strain-life curve of the material in "STRAIN" mode.
+    strain: Option<(NotchCorrection<'a>, &'a StrainLife)>,
+}
+
+impl AssessedSet<'_> {
+    fn contains(&self, node: usize) -> bool {
+        self.selection
+            .as_ref()
+            .is_none_or(|selection| selection.contains(node))
+    }
+
+    /// Returns the damage of a scalar elastic stress history scaled by `factor`: of its
+    /// rainflow-counted stress ranges on the S-N curve or, in "STRAIN" mode, of the local
+    /// strain ranges at the notch on the strain-life curve.
+    fn damage(&self, history: &[f64], factor: f64) -> Result<f64> {
+        let Some((notch, strain_life)) = &self.strain else {
+            let cycles = rainflow(history);
+            let ranges: Vec<f64> = cycles.iter().map(|c| c.range * factor).collect();
+            let counts: Vec<f64> = cycles.iter().map(|c| c.count).collect();
+            return self.material.fatigue.damage(&ranges, &counts);
+        };
+        let elastic: Vec<f64> = history.iter().map(|stress| stress * factor).collect();
+        let cycles = notch.local_response(&elastic)?.cycles;
+        let ranges: Vec<f64> = cycles.iter().map(|c| c.strain_range).collect();
+        let counts: Vec<f64> = cycles.iter().map(|c| c.count).collect();
+        strain_life.damage(&ranges, &counts, self.material.youngs_modulus)
+    }
+}
+
+/// The unit-load stresses of the points of one interpolation.
+struct UnitLoadStresses<'a> {
+    interpolation: &'a Interpolation,
+    /// The interpolation over the points, prepared once for all load cases.
+    prepared: Box<dyn PreparedInterpolation>,
+    /// The bounding box of the points, which `Interpolation.outside` applies to.
+    bounds: BoundingBox,
+    /// The points with a stress file, after merging those sharing coordinates.
+    points: Vec<Point>,
+    /// The nodes of the stress files in ascending order.
+    nodes: Vec<usize>,
+    /// The stress components, one row per point and six columns per node of `nodes`.
+    values: DMatrix<f64>,
+    /// The pairs of points merged into the first of them by `Interpolation.duplicates`.
+    duplicates: Vec<(Point, Point)>,
+}
+
+/// Assesses the fatigue damage of every node set of a validated configuration.
+///
+/// Nodes are assessed if they are selected by `Solution.node` and belong to a set; without
+/// `node_sets`, all selected nodes form the set `DEFAULT_SET` with the global `material`.
+/// Stress ranges are scaled by `SafetyFactor.gmfat`, the load case `gf_fat` and the
+/// thickness correction of the set's material. In "STRAIN" mode the scaled elastic stresses
+/// are corrected to local notch stresses and strains by `Solution.notch` and the cyclic curve
+/// of the material, and the local strain ranges are assessed on its strain-life curve.
+///
+/// # Returns
+///
+/// Returns the damage per set, the samples outside each interpolation and the diagnostics of
+/// the interpolations, or an error if a stress file or load case cannot be read, a node is
+/// missing from a stress file, the interpolation fails, a sample is outside an interpolation
+/// with `outside: ERROR`, or the configuration asks for an unsupported mode, mean stress
+/// correction or criterion. Interpolations with method "NONE" are not assessed.
+pub fn assess(config: &Config) -> Result<Assessment> {
+    if !matches!(config.solution.mode.as_str(), "STRESS" | "STRAIN") {
+        return Err(anyhow!(
+            "mode {} is not supported by the assessment",
+            config.solution.mode
+        ));
+    }
+    if config.solution.mean.mean != "NONE" {
+        return Err(anyhow!(
+            "mean stress correction {} is not implemented",
+            config.solution.mean.mean
+        ));
+    }
+    let sets = assessed_sets(config)?;
+    let stresses = read_assessed_stresses(config, &sets)?;
+    let nodes = assessed_nodes(&stresses, &sets);
+    let diagnostics = interpolation_diagnostics(&stresses, &nodes)?;
+
+    // Damage per node, load case and plane.
+    let loadcases = &config.timeseries.loadcases;
+    let mut damages: Vec<Vec<Vec<f64>>> = nodes
+        .iter()
+        .map(|(_, set)| vec![vec![0.0; sets[*set].criterion.planes()]; loadcases.len()])
+        .collect();
+    let mut outside = Vec::new();
+    for (lc_index, lc) in loadcases.iter().enumerate() {
+        let series = config.timeseries.read_loadcase(lc, &config.units)?;
+        // The weights of each interpolation are shared by all nodes of the load case.
+        let weights = stresses
+            .iter()
+            .map(|unit| {
+                let interpolation = unit.interpolation;
+                let mut targets = interpolation_targets(interpolation, &series)?;
+                // Piecewise methods apply the policy to their hull and only count here.
+                let policy = match interpolation.extrapolates() {
+                    true => Outside::Allow,
+                    false => interpolation.outside_policy(),
+                };
+                let count = unit.bounds.restrict(&mut targets, policy).map_err(|e| {
+                    anyhow!(
+                        "interpolation '{}', load case {}: {}",
+                        interpolation.name,
+                        lc.file,
+                        e
+                    )
+                })?;
+                outside.push(OutsideSamples {
+                    interpolation: interpolation.name.clone(),
+                    loadcase: lc.file.clone(),
+                    samples: targets.len(),
+                    outside: count,
+                });
+                unit.prepared
+                    .weights(&targets)
+                    .map_err(|e| anyhow!("interpolation '{}': {}", interpolation.name, e))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let blocks = nodes.chunks(NODE_BLOCK).zip(damages.chunks_mut(NODE_BLOCK));
+        for (block, block_damages) in blocks {
+            let block_nodes: Vec<usize> = block.iter().map(|(node, _)| *node).collect();
+            let histories = stress_histories(&block_nodes, &stresses, &weights)?;
+            for ((&(_, set), history), damage) in block.iter().zip(histories).zip(block_damages) {
+                let set = &sets[set];
+                let factor = set.factor * lc.gf_fat;
+                for (plane, plane_damage) in damage[lc_index].iter_mut().enumerate() {
+                    let scalar: Vec<f64> = history
+                        .iter()
+                        .map(|tensor| set.criterion.stress(tensor, plane))
+                        .collect();
+                    *plane_damage = lc.frequency * set.damage(&scalar, factor)?;
+                }
+            }
+        }
+    }
+
+    let mut results: Vec<SetDamage> = sets
+        .iter()
+        .map(|set| SetDamage {
+            name: set.name.to_string(),
+            material: set.material.name.clone(),
+            planes: set.criterion.planes(),
+            nodes: Vec::new(),
+        })
+        .collect();
+    for (&(node, set), damage) in nodes.iter().zip(damages.iter()) {
+        let planes = results[set].planes;
+        let totals = (0..planes).map(|plane| damage.iter().map(|lc| lc[plane]).sum::<f64>());
+        let (plane, total) = largest(totals);
+        let (loadcase, _) = largest(damage.iter().map(|lc| lc[plane]));
+        let mut families: BTreeMap<usize, f64> = BTreeMap::new();
+        for (lc, damage) in loadcases.iter().zip(damage) {
+            *families.entry(lc.fam).or_insert(0.0) += damage[plane];
+        }
+        let (family, _) = largest(families.values().copied());
+        results[set].nodes.push(NodeDamage {
+            node,
+            damage: total,
+            plane,
+            loadcase: (total > 0.0).then_some(loadcase),
+            family: families.keys().nth(family).filter(|_| total > 0.0).copied(),
+        });
+    }
+    Ok(Assessment {
+        sets: results,
+        outside,
+        diagnostics,
+    })
+}
+
+/// Returns the name of each interpolation with how well it reproduces the unit-load stresses
+/// of the assessed nodes.
+///
+/// The values are the six stress components of every node, so residuals and leave-one-out
+/// errors are in the stress unit of the stress files.
+fn interpolation_diagnostics(
+    stresses: &[UnitLoadStresses],
+    nodes: &[(usize, usize)],
+) -> Result<Vec<(String, Diagnostics)>> {
+    let nodes: Vec<usize> = nodes.iter().map(|(node, _)| *node).collect();
+    stresses
+        .iter()
+        .map(|unit| {
+            let name = &unit.interpolation.name;
+            let mut diagnostics = unit
+                .interpolation
+                .strategy()?
+                .diagnostics(&unit.points, &unit_load_values(unit, &nodes)?)
+                .map_err(|e| anyhow!("interpolation '{}': {}", name, e))?;
+            diagnostics.duplicates = unit.duplicates.clone();
+            Ok((name.clone(), diagnostics))
+        })
+        .collect()
+}
+
+/// Reads the unit-load stresses of the nodes selected by `Solution.node` and the sets for
+/// every interpolation whose method is not "NONE".
+fn read_assessed_stresses<'a>(
+    config: &'a Config,
+    sets: &[AssessedSet],
+) -> Result<Vec<UnitLoadStresses<'a>>> {
+    let selection = config.solution.node.selection()?;
+    let select = |node: usize| selection.contains(node) && sets.iter().any(|s| s.contains(node));
+    config
+        .timeseries
+        .interpolations
+        .iter()
+        .filter(|interpolation| interpolation.method != "NONE")
+        .map(|interpolation| read_unit_load_stresses(interpolation, &config.units, &select))
+        .collect()
+}
+
+/// Returns the assessed nodes with the index of their set, in ascending node order.
+fn assessed_nodes(stresses: &[UnitLoadStresses], sets: &[AssessedSet]) -> Vec<(usize, usize)> {
+    stresses
+        .iter()
+        .flat_map(|unit| unit.nodes.iter().copied())
+        .collect::<BTreeSet<usize>>()
+        .into_iter()
+        .filter_map(|node| Some((node, sets.iter().position(|set| set.contains(node))?)))
+        .collect()
+}
+
+/// Resolves the node sets of the configuration, or the default set if none are defined.
+fn assessed_sets(config: &Config) -> Result<Vec<AssessedSet<'_>>> {
+    let gmfat = config.safety_factor.gmfat;
+    if config.node_sets.is_empty() {
+        return Ok(vec![AssessedSet {
+            name: DEFAULT_SET,
+            selection: None,
+            material: &config.material,
+            criterion: config.solution.stress_criteria.criterion()?,
+            factor: gmfat,
+            strain: strain_life(config, &config.material)?,
+        }]);
+    }
+    config
+        .node_sets
+        .iter()
+        .map(|set| {
+            let material = set.material.as_ref().unwrap_or(&config.material);
+            let criteria = set
+                .stress_criteria
+                .as_ref()
+                .unwrap_or(&config.solution.stress_criteria);
+            let thickness = match (set.thickness, &material.thickness) {
+                (Some(thickness), Some(correction)) => correction.factor(thickness),
+                _ => 1.0,
+            };
+            Ok(AssessedSet {
+                name: &set.name,
+                selection: Some(set.selection()?),
+                material,
+                criterion: criteria
+                    .criterion()
+                    .map_err(|e| anyhow!("node set '{}': {}", set.name, e))?,
+                factor: gmfat * thickness,
+                strain: strain_life(config, material)
+                    .map_err(|e| anyhow!("node set '{}': {}", set.name, e))?,
+            })
+        })
+        .collect()
+}
+
+/// Returns the notch correction and strain-life curve of a material in "STRAIN" mode, or
+/// `None` in "STRESS" mode.
+fn strain_life<'a>(
+    config: &Config,
+    material: &'a Material,
+) -> Result<Option<(NotchCorrection<'a>, &'a StrainLife)>> {
+    if config.solution.mode != "STRAIN" {
+        return Ok(None);
+    }
+    let rule = NotchRule::from_name(config.solution.notch.as_deref().unwrap_or_default())?;
+    let (Some(cyclic), Some(strain_life)) = (&material.cyclic, &material.strain_life) else {
+        return Err(anyhow!(
+            "mode STRAIN requires a cyclic curve and a strain-life curve for material '{}'",
+            material.name
+        ));
+    };
+    let notch = NotchCorrection::new(cyclic, material.youngs_modulus, rule)?;
+    Ok(Some((notch, strain_life)))
+}
+
+/// Reads the stresses of the selected nodes from the stress files of an interpolation and
+/// merges the points sharing coordinates by `Interpolation.duplicates`.
+///
+/// # Returns
+///
+/// Returns an error if a node of one stress file is missing from another.
+fn read_unit_load_stresses<'a, F>(
+    interpolation: &'a Interpolation,
+    units: &UnitSystem,
+    select: &F,
+) -> Result<UnitLoadStresses<'a>>
+where
+    F: Fn(usize) -> bool,
+{
+    let strategy = interpolation.strategy()?;
+    let mut points = Vec::with_capacity(interpolation.points.len());
+    let mut tensors = Vec::with_capacity(interpolation.points.len());
+    for point in interpolation.points.iter() {
+        let Some(file) = &point.file else {
+            continue;
+        };
+        let components: HashMap<usize, [f64; 6]> = interpolation
+            .read_stress_tensors(file, units, select)?
+            .into_iter()
+            .map(|(node, tensor)| (node, components(&tensor)))
+            .collect();
+        points.push(point.clone());
+        tensors.push(components);
+    }
+    let nodes: Vec<usize> = tensors
+        .iter()
+        .flat_map(|components| components.keys().copied())
+        .collect::<BTreeSet<usize>>()
+        .into_iter()
+        .collect();
+    let mut values = DMatrix::zeros(points.len(), 6 * nodes.len());
+    for (row, (point, tensors)) in points.iter().zip(&tensors).enumerate() {
+        for (index, node) in nodes.iter().enumerate() {
+            let components = tensors.get(node).ok_or_else(|| {
+                anyhow!(
+                    "node {} is missing from stress file {} of interpolation '{}'",
+                    node,
+                    point.file.as_deref().unwrap_or_default(),
+                    interpolation.name
+                )
+            })?;
+            for (component, value) in components.iter().enumerate() {
+                values[(row, 6 * index + component)] = *value;
+            }
+        }
+    }
+    let merged = merge_duplicates(&points, &values, interpolation.duplicate_points())
+        .map_err(|e| anyhow!("interpolation '{}': {}", interpolation.name, e))?;
+    let prepared = strategy
+        .prepare(&merged.points)
+        .map_err(|e| anyhow!("interpolation '{}': {}", interpolation.name, e))?;
+    Ok(UnitLoadStresses {
+        interpolation,
+        prepared,
+        bounds: BoundingBox::new(&merged.points)?,
+        points: merged.points,
+        nodes,
+        values: merged.values,
+        duplicates: merged.duplicates,
+    })
+}
+
+/// Returns the scaled interpolation coordinates of every sample of a load case.
+fn interpolation_targets(
+    interpolation: &Interpolation,
+    series: &SampledSeries,
+) -> Result<Vec<Vec<f64>>> {
+    let channels = interpolation
+        .sensor
+        .iter()
+        .map(|sensor| {
+            series.channel(sensor).ok_or_else(|| {
+                anyhow!(
+                    "channel '{}' of interpolation '{}' is not in the load case",
+                    sensor,
+                    interpolation.name
+                )
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+    Ok((0..series.time.len())
+        .map(|sample| {
+            channels
+                .iter()
+                .map(|channel| channel.values[sample] * interpolation.scale)
+                .collect()
+        })
+        .collect())
+}
+
+/// Returns the stress components of the nodes at the points of an interpolation, one row per
+/// point and six columns per node.
+fn unit_load_values(unit: &UnitLoadStresses, nodes: &[usize]) -> Result<DMatrix<f64>> {
+    let mut values = DMatrix::zeros(unit.points.len(), 6 * nodes.len());
+    for (index, node) in nodes.iter().enumerate() {
+        let column = unit.nodes.binary_search(node).map_err(|_| {
+            anyhow!(
+                "node {} is missing from the stress files of interpolation '{}'",
+                node,
+                unit.interpolation.name
+            )
+        })?;
+        values
+            .columns_mut(6 * index, 6)
+            .copy_from(&unit.values.columns(6 * column, 6));
+    }
+    Ok(values)
+}
+
+/// Returns the stress tensors of each node at every sample, summed over the interpolations.
+///
+/// `weights` holds the interpolation weights of every sample per interpolation, which are
+/// applied to the components of all nodes in one product.
+fn stress_histories(
+    nodes: &[usize],
+    stresses: &[UnitLoadStresses],
+    weights: &[DMatrix<f64>],
+) -> Result<Vec<Vec<StressTensor>>> {
+    let samples = weights.first().map_or(0, DMatrix::nrows);
+    let mut histories = vec![vec![[0.0; 6]; samples]; nodes.len()];
+    for (unit, weights) in stresses.iter().zip(weights) {
+        let values = weights * unit_load_values(unit, nodes)?;
+        for (index, history) in histories.iter_mut().enumerate() {
+            for (sample, tensor) in history.iter_mut().enumerate() {
+                for (component, value) in tensor.iter_mut().enumerate() {
+                    *value += values[(sample, 6 * index + component)];
+                }
+            }
+        }
+    }
+    Ok(histories
+        .into_iter()
+        .map(|history| {
+            history
+                .into_iter()
+                .map(|[sxx, syy, szz, sxy, syz, szx]| {
+                    StressTensor::new(Matrix3::new(sxx, sxy, szx, sxy, syy, syz, szx, syz, szz))
+                })
+                .collect()
+        })
+        .collect())
+}
+
+/// Returns the index and value of the first largest value, or `(0, 0.0)` if no value is
+/// positive.
+fn largest(values: impl Iterator<Item = f64>) -> (usize, f64) {
+    values.enumerate().fold(
+        (0, 0.0),
+        |max, item| if item.1 > max.1 { item } else { max },
+    )
+}
+
+/// Returns the stress components `sxx syy szz sxy syz szx` of a tensor.
+fn components(tensor: &StressTensor) -> [f64; 6] {
+    [
+        tensor.sxx(),
+        tensor.syy(),
+        tensor.szz(),
+        tensor.sxy(),
+        tensor.syz(),
+        tensor.szx(),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::load_config;
+    use crate::material::CyclicCurve;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn test_assess_node_sets() {
+        let mut config = load_config("tests/config_node_sets.yaml").expect("Failed to load config");
+        let results = assess(&config).unwrap().sets;
+        let names: Vec<&str> = results.iter().map(|set| set.name.as_str()).collect();
+        assert_eq!(names, vec!["base", "weld"]);
+        assert_eq!(results[1].material, "Weld FAT71");
+        // Solution.node ends at 25, so node 500 and 26..30 of the weld set are not assessed.
+        let base: Vec<usize> = results[0].nodes.iter().map(|n| n.node).collect();
+        let weld: Vec<usize> = results[1].nodes.iter().map(|n| n.node).collect();
+        assert_eq!(base, (1..=20).collect::<Vec<_>>());
+        assert_eq!(weld, (21..=25).collect::<Vec<_>>());
+        assert!(results[0]
+            .nodes
+            .iter()
+            .all(|n| n.damage > 0.0 && n.plane < 4));
+        assert!(results[1]
+            .nodes
+            .iter()
+            .all(|n| n.damage > 0.0 && n.plane == 0));
+        let max = results[0].max().unwrap();
+        assert!(results[0].nodes.iter().all(|n| n.damage <= max.damage));
+
+        // The thickness correction scales up the stress ranges of the weld set.
+        config.node_sets[1].thickness = None;
+        let uncorrected = assess(&config).unwrap().sets;
+        assert_eq!(results[0], uncorrected[0]);
+        for (corrected, plain) in results[1].nodes.iter().zip(&uncorrected[1].nodes) {
+            assert!(corrected.damage > plain.damage);
+        }
+    }
+
+    #[test]
+    fn test_assess_without_node_sets() {
+        let mut config = load_config("tests/config_node_sets.yaml").expect("Failed to load config");
+        config.node_sets.clear();
+        config.solution.node.to = 3;
+        let results = assess(&config).unwrap().sets;
+        assert_eq!(results.len(), 1);
+        assert_eq!(
+            (results[0].name.as_str(), results[0].material.as_str()),
+            (DEFAULT_SET, "Steel")
+        );
+        assert_eq!(results[0].nodes.len(), 3);
+        assert_eq!(results[0].planes, 4);
+        // The only load case governs every damaged node.
+        assert!(results[0].nodes.iter().all(|n| n.loadcase == Some(0)));
+
+        config.solution.mean.mean = "GOODMAN".to_string();
+        let err = assess(&config).unwrap_err();
+        assert!(format!("{err:#}").contains("mean stress correction GOODMAN is not implemented"));
+    }
+
+    #[test]
+    fn test_assess_strain_mode() {
+        let mut config = load_config("tests/config_node_sets.yaml").expect("Failed to load config");
+        config.node_sets.clear();
+        config.solution.node.to = 25;
+        // A single-slope S-N curve through (1e6 cycles, knee stress) is Basquin's line with
+        // b = -1/m and 2N = 1 at twice sigma_f.
+        let (m, sigma_f) = (5, 900.0);
+        let fatigue = &mut config.material.fatigue;
+        fatigue.slope.m1 = m;
+        fatigue.slope.m2 = m;
+        fatigue.knee.cycle = 1_000_000;
+        fatigue.knee.stress = 2.0 * sigma_f * (0.5e-6_f64).powf(1.0 / m as f64);
+        fatigue.cutoff.min = 0.0;
+        let stress = assess(&config).unwrap().sets;
+
+        // An elastic cyclic curve and a negligible plastic line make the strain-life
+        // assessment of the local strains that of the stresses.
+        config.solution.mode = "STRAIN".to_string();
+        config.solution.notch = Some("NEUBER".to_string());
+        config.material.cyclic = Some(CyclicCurve {
+            k_prime: 1.0e12,
+            n_prime: 0.1,
+        });
+        config.material.strain_life = Some(StrainLife {
+            sigma_f,
+            b: -1.0 / m as f64,
+            epsilon_f: 1.0e-15,
+            c: -0.6,
+        });
+        assert!(config.validate().is_ok(), "{:?}", config.validate());
+        let strain = assess(&config).unwrap().sets;
+        // Counting with the material memory differs from the four-point method only in the
+        // residue, which keeps the envelope reversals open.
+        assert_eq!(stress[0].nodes.len(), strain[0].nodes.len());
+        for (stress, strain) in stress[0].nodes.iter().zip(&strain[0].nodes) {
+            assert_eq!(stress.node, strain.node);
+            assert!(strain.damage > 0.0, "node {}", strain.node);
+            assert_relative_eq!(strain.damage, stress.damage, max_relative = 0.02);
+        }
+
+        // Plasticity at the notch raises the local strains, Neuber's rule more than Glinka's.
+        config.safety_factor.gmfat = 5.0;
+        config.material.cyclic = Some(CyclicCurve {
+            k_prime: 1100.0,
+            n_prime: 0.15,
+        });
+        config.material.strain_life = Some(StrainLife {
+            sigma_f,
+            b: -0.09,
+            epsilon_f: 0.3,
+            c: -0.55,
+        });
+        let neuber = assess(&config).unwrap().sets;
+        config.solution.notch = Some("GLINKA".to_string());
+        let glinka = assess(&config).unwrap().sets;
+        for (neuber, glinka) in neuber[0].nodes.iter().zip(&glinka[0].nodes) {
+            assert_eq!(neuber.node, glinka.node);
+            assert!(neuber.damage > glinka.damage, "node {}", neuber.node);
+        }
+    }
+
+    #[test]
+    fn test_governing_family() {
+        let mut config = load_config("tests/config_node_sets.yaml").expect("Failed to load config");
+        config.solution.node.to = 25;
+        let loadcase = |index: usize, fam: usize, factor: f64| {
+            let mut loadcases = load_config("tests/config.yaml")
+                .expect("Failed to load config")
+                .timeseries
+                .loadcases;
+            let mut loadcase = loadcases.swap_remove(index);
+            loadcase.fam = fam;
+            loadcase.frequency *= factor;
+            loadcase
+        };
+        // Each repetition of the first load case damages less than the fifth, but together
+        // they damage more, so their family governs.
+        config.timeseries.loadcases = vec![
+            loadcase(0, 2, 4.0),
+            loadcase(0, 2, 4.0),
+            loadcase(4, 1, 1.0),
+        ];
+        let results = assess(&config).unwrap().sets;
+        for node in results.iter().flat_map(|set| &set.nodes) {
+            assert_eq!(
+                (node.loadcase, node.family),
+                (Some(2), Some(2)),
+                "{}",
+                node.node
+            );
+        }
+
+        config.timeseries.loadcases.truncate(1);
+        config.timeseries.loadcases.push(loadcase(4, 1, 1.0));
+        let results = assess(&config).unwrap().sets;
+        for node in results.iter().flat_map(|set| &set.nodes) {
+            assert_eq!(
+                (node.loadcase, node.family),
+                (Some(1), Some(1)),
+                "{}",
+                node.node
+            );
+        }
+    }
+
+    #[test]
+    fn test_assess_delaunay_interpolation() {
+        let mut config = load_config("tests/config_node_sets.yaml").expect("Failed to load config");
+        config.node_sets.clear();
+        config.solution.node.to = 3;
+        // Four affinely independent points form a single simplex, whose linear extension
+        // outside the hull is the exact regression through them.
+        config.timeseries.interpolations[0].points[0].coordinates = vec![0.0, 0.0, 0.0];
+        let regression = assess(&config).unwrap().sets;
+
+        let interpolation = &mut config.timeseries.interpolations[0];
+        interpolation.method = "LINEAR_DELAUNAY".to_string();
+        let delaunay = assess(&config).unwrap().sets;
+        for (a, b) in regression[0].nodes.iter().zip(&delaunay[0].nodes) {
+            assert_eq!((a.node, a.plane), (b.node, b.plane));
+            assert_relative_eq!(a.damage, b.damage, max_relative = 1e-6);
+        }
+
+        // With as many points as polynomial terms, the thin-plate spline is the polynomial.
+        let interpolation = &mut config.timeseries.interpolations[0];
+        interpolation.method = "RBF_THIN_PLATE".to_string();
+        let rbf = assess(&config).unwrap().sets;
+        for (a, b) in regression[0].nodes.iter().zip(&rbf[0].nodes) {
+            assert_relative_eq!(a.damage, b.damage, max_relative = 1e-6);
+        }
+
+        // Samples clamped to the hull, not only to its bounding box, are interpolated.
+        let interpolation = &mut config.timeseries.interpolations[0];
+        interpolation.method = "LINEAR_DELAUNAY".to_string();
+        interpolation.outside = Some("CLAMP".to_string());
+        let clamped = assess(&config).unwrap().sets;
+        assert_eq!(clamped[0].nodes.len(), delaunay[0].nodes.len());
+
+        config.timeseries.interpolations[0].outside = Some("ERROR".to_string());
+        let err = assess(&config).unwrap_err();
+        assert!(format!("{err:#}").contains("outside the convex hull"));
+    }
+
+    #[test]
+    fn test_assess_outside_points() {
+        let mut config = load_config("tests/config_node_sets.yaml").expect("Failed to load config");
+        config.node_sets.clear();
+        config.solution.node.to = 3;
+        let allowed = assess(&config).unwrap();
+        let loadcases = config.timeseries.loadcases.len();
+        assert_eq!(allowed.outside.len(), loadcases);
+        let first = &allowed.outside[0];
+        assert_eq!(first.interpolation, "StressTimeseries");
+        assert_eq!(first.loadcase, config.timeseries.loadcases[0].file);
+        assert!(first.outside > 0 && first.outside <= first.samples);
+
+        // Warnings leave the samples as they are.
+        config.timeseries.interpolations[0].outside = Some("WARN".to_string());
+        assert_eq!(assess(&config).unwrap(), allowed);
+
+        // Clamped samples stay in the unit cube of the points, so the stresses vary less.
+        config.timeseries.interpolations[0].outside = Some("CLAMP".to_string());
+        let clamped = assess(&config).unwrap();
+        assert_eq!(clamped.outside, allowed.outside);
+        for (clamped, allowed) in clamped.sets[0].nodes.iter().zip(&allowed.sets[0].nodes) {
+            assert!(clamped.damage < allowed.damage);
+        }
+
+        config.timeseries.interpolations[0].outside = Some("ERROR".to_string());
+        let err = assess(&config).unwrap_err();
+        assert!(format!("{err:#}").contains(&format!(
+            "interpolation 'StressTimeseries', load case {}: target",
+            config.timeseries.loadcases[0].file
+        )));
+        assert!(format!("{err:#}").contains("is outside the bounding box of the points"));
+    }
+
+    #[test]
+    fn test_interpolation_method_none_is_not_assessed() {
+        let mut config = load_config("tests/config_node_sets.yaml").expect("Failed to load config");
+        config.node_sets.clear();
+        config.solution.node.to = 3;
+        let assessed = assess(&config).unwrap();
+        let mut disabled = load_config("tests/config_node_sets.yaml")
+            .expect("Failed to load config")
+            .timeseries
+            .interpolations
+            .remove(0);
+        disabled.name = "Disabled".to_string();
+        disabled.method = "NONE".to_string();
+        config.timeseries.interpolations.push(disabled);
+        let assessment = assess(&config).unwrap();
+        assert_eq!(assessment.sets, assessed.sets);
+        let names: Vec<&str> = assessment
+            .diagnostics
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .collect();
+        assert_eq!(names, vec!["StressTimeseries"]);
+    }
+
+    /// Returns the diagnostics of the interpolations assessed for a configuration.
+    fn assessed_diagnostics(config: &Config) -> Result<Vec<Diagnostics>> {
+        let assessment = assess(config)?;
+        Ok(assessment.diagnostics.into_iter().map(|(_, d)| d).collect())
+    }
+
+    #[test]
+    fn test_interpolation_diagnostics() {
+        let mut config = load_config("tests/config_node_sets.yaml").expect("Failed to load config");
+        config.node_sets.clear();
+        config.solution.node.to = 3;
+        // FX0FY0FZ0 and FX1FY0FZ0 share coordinates and are merged into one point, so the
+        // regression has rank 3 of 4.
+        let diagnostics = assessed_diagnostics(&config).unwrap();
+        assert_eq!(diagnostics.len(), 1);
+        let files: Vec<Option<&str>> = [
+            &diagnostics[0].duplicates[0].0,
+            &diagnostics[0].duplicates[0].1,
+        ]
+        .iter()
+        .map(|point| point.file.as_deref())
+        .collect();
+        assert_eq!(files, vec![Some("FX0FY0FZ0.usf"), Some("FX1FY0FZ0.usf")]);
+        let conditioning = diagnostics[0].conditioning.unwrap();
+        assert_eq!((conditioning.rank, conditioning.columns), (3, 4));
+        assert_eq!(diagnostics[0].residuals.shape(), (3, 18));
+
+        // Without a policy, the shared coordinates are an error.
+        config.timeseries.interpolations[0].duplicates = None;
+        let err = assessed_diagnostics(&config).unwrap_err();
+        assert!(format!("{err:#}").contains(
+            "interpolation 'StressTimeseries': points FX0FY0FZ0.usf and FX1FY0FZ0.usf share coordinates [1.0, 0.0, 0.0]"
+        ));
+
+        // Four affinely independent unit cases are fitted exactly.
+        config.timeseries.interpolations[0].points[0].coordinates = vec![0.0, 0.0, 0.0];
+        let diagnostics = assessed_diagnostics(&config).unwrap();
+        assert!(diagnostics[0].duplicates.is_empty());
+        assert_eq!(diagnostics[0].conditioning.unwrap().rank, 4);
+        assert_eq!(diagnostics[0].residuals.shape(), (4, 18));
+        assert!(diagnostics[0].max_residual() < 1e-6);
+        assert!(diagnostics[0].max_leave_one_out().is_some());
+    }
+
+    #[test]
+    fn test_unit_load_duplicates() {
+        let mut config = load_config("tests/config.yaml").expect("Failed to load config");
+        let select = |node: usize| node <= 3;
+        let interpolation = &mut config.timeseries.interpolations[0];
+        interpolation.points[0].coordinates = vec![0.0, 0.0, 0.0];
+        let separate = read_unit_load_stresses(interpolation, &config.units, &select).unwrap();
+        assert_eq!(separate.nodes, vec![1, 2, 3]);
+        let values = separate.values;
+        assert_eq!(values.shape(), (4, 18));
+
+        let interpolation = &mut config.timeseries.interpolations[0];
+        interpolation.points[0].coordinates = vec![1.0, 0.0, 0.0];
+        for (duplicates, expected) in [
+            ("FIRST", values.row(0).into_owned()),
+            ("LAST", values.row(1).into_owned()),
+            ("AVERAGE", (values.row(0) + values.row(1)) / 2.0),
+        ] {
+            interpolation.duplicates = Some(duplicates.to_string());
+            let merged = read_unit_load_stresses(interpolation, &config.units, &select).unwrap();
+            assert_eq!(merged.points.len(), 3);
+            assert_eq!(merged.duplicates.len(), 1);
+            assert_eq!(merged.values.row(0), expected, "{duplicates}");
+            assert_eq!(merged.values.rows(1, 2), values.rows(2, 2));
+        }
+    }
+}
@@ -1,10 +1,14 @@
 //! A module for validating and managing configurations for a structural analysis application.
 
 use crate::material::Material;
-use crate::timeseries::TimeSeries;
+use crate::stress::StressCriterion;
+use crate::timeseries::{derived_variables, TimeSeries};
+pub use crate::units::UnitSystem;
+use crate::units::{parse_measure, Unit};
 use anyhow::{anyhow, Result};
 use serde::Deserialize;
-use serde_yaml;
+use serde_yaml::{self, Value};
+use std::collections::HashSet;
 use std::fs;
 use std::path::Path;
 
@@ -15,6 +19,13 @@ pub struct Config {
     pub material: Material,
     pub safety_factor: SafetyFactor,
     pub timeseries: TimeSeries,
+    /// The unit system bare numbers and unit-load stress files are expressed in.
+    #[serde(default)]
+    pub units: UnitSystem,
+    /// Optional named node sets assessed with their own material, thickness and stress
+    /// criterion. Without sets, the nodes of `Solution.node` are assessed with `material`.
+    #[serde(default)]
+    pub node_sets: Vec<NodeSet>,
 }
 
 impl Config {
@@ -27,23 +38,113 @@ impl Config {
         self.material.validate()?;
         self.safety_factor.validate()?;
         self.timeseries.validate()?;
+        self.units.validate()?;
         self.validate_sensor_against_sensorfile()?;
+        self.validate_notch_against_material()?;
+        self.validate_node_sets()?;
+        Ok(())
+    }
+
+    /// Validates the node sets, that their names are unique, that no node is in two sets
+    /// and that a set thickness has a thickness correction in the set's material.
+    fn validate_node_sets(&self) -> Result<()> {
+        let mut selections: Vec<(&str, NodeSetSelection)> = Vec::new();
+        for set in self.node_sets.iter() {
+            set.validate()
+                .map_err(|e| anyhow!("node set '{}': {}", set.name, e))?;
+            if selections.iter().any(|(name, _)| *name == set.name) {
+                return Err(anyhow!("node set '{}' is defined twice", set.name));
+            }
+            let material = set.material.as_ref().unwrap_or(&self.material);
+            if set.thickness.is_some() && material.thickness.is_none() {
+                return Err(anyhow!(
+                    "node set '{}' has a thickness, but material '{}' has no thickness correction",
+                    set.name,
+                    material.name
+                ));
+            }
+            let selection = set.selection()?;
+            for (name, other) in selections.iter() {
+                if let Some(node) = selection.shared_node(other) {
+                    return Err(anyhow!(
+                        "node sets '{}' and '{}' both contain node {}",
+                        name,
+                        set.name,
+                        node
+                    ));
+                }
+            }
+            selections.push((&set.name, selection));
+        }
+        Ok(())
+    }
+
+    /// Validates that a strain-based run has a notch rule, and a cyclic stress-strain curve
+    /// and a strain-life curve for the global material and the material of every node set.
+    fn validate_notch_against_material(&self) -> Result<()> {
+        if self.solution.mode != "STRAIN" {
+            return Ok(());
+        }
+        match self.solution.notch.as_deref() {
+            Some("NEUBER") | Some("GLINKA") => (),
+            _ => return Err(anyhow!("mode STRAIN requires notch NEUBER or GLINKA")),
+        }
+        let materials = self
+            .node_sets
+            .iter()
+            .filter_map(|set| set.material.as_ref());
+        for material in std::iter::once(&self.material).chain(materials) {
+            if material.cyclic.is_none() {
+                return Err(anyhow!(
+                    "mode STRAIN requires a cyclic curve for material '{}'",
+                    material.name
+                ));
+            }
+            if material.strain_life.is_none() {
+                return Err(anyhow!(
+                    "mode STRAIN requires a strain-life curve for material '{}'",
+                    material.name
+                ));
+            }
+        }
         Ok(())
     }
 
     /// Validates that all sensors specified in the `TimeSeries` configuration
-    /// exist within the sensor file.
+    /// exist within the sensor file or are derived channels, that every sensor
+    /// unit is known and that interpolations are driven by force or moment sensors.
     fn validate_sensor_against_sensorfile(&self) -> Result<()> {
         let sen = self
             .timeseries
             .read_sensorfile()
             .map_err(|e| anyhow!("Failed to read sensor file: {}", e))?;
 
+        for sensor in sen.iter() {
+            Unit::parse(&sensor.unit).map_err(|e| anyhow!("Sensor '{}': {}", sensor.name, e))?;
+        }
+        let names: Vec<&str> = sen.iter().map(|s| s.name.as_str()).collect();
+        let order = self.timeseries.evaluation_order(&names)?;
+        let derived = derived_variables(&self.timeseries.variables, &order, &names)?;
         for interp in self.timeseries.interpolations.iter() {
             for sensor in interp.sensor.iter() {
-                if !sen.iter().any(|s| s.name == *sensor) {
+                // Derived channels have no declared unit to check.
+                if derived.iter().any(|(name, _)| name == sensor) {
+                    continue;
+                }
+                let Some(definition) = sen.iter().find(|s| s.name == *sensor) else {
                     // Use anyhow! to create an error with a dynamic message
                     return Err(anyhow!("Sensor '{}' not found in sensorfile", sensor));
+                };
+                // Unit-load stress files are scaled by loads, so strain gauges and stresses cannot drive them.
+                let unit = Unit::parse(&definition.unit)?;
+                if !unit.quantity.is_load() {
+                    return Err(anyhow!(
+                        "Sensor '{}' measures {} ({}), but interpolation '{}' needs force or moment sensors",
+                        sensor,
+                        unit.quantity,
+                        definition.unit,
+                        interp.name
+                    ));
                 }
             }
         }
@@ -61,10 +162,17 @@ impl Config {
 pub struct Solution {
     /// Specifies the type of run. Valid values are "FAT" for fatigue analysis and "NONE" for no analysis.
     pub run_type: String,
-    /// Defines the mode of operation. Valid modes are "STRESS" for stress analysis and "NONE" for no specific mode.
+    /// Defines the mode of operation. Valid modes are "STRESS" for stress analysis, "STRAIN" for
+    /// local strain analysis with notch plasticity correction and the material's strain-life
+    /// curve, and "NONE" for no specific mode.
     pub mode: String,
+    /// Optional notch plasticity rule used in "STRAIN" mode. Valid rules are "NEUBER", "GLINKA" and "NONE".
+    pub notch: Option<String>,
     /// The desired output format. Currently, "JSON" is supported as a valid output.
     pub output: String,
+    /// Optional path of the report written by "FAT" runs, see `report::Report`. Defaults to
+    /// the configuration path with the extension `report.json`.
+    pub report: Option<String>,
     /// Criteria for evaluating stress within the analysis.
     pub stress_criteria: StressCriteria,
     /// Parameters for mean stress correction.
@@ -81,7 +189,8 @@ impl Solution {
     ///
     /// This method checks each field to verify that:
     /// - `run_type` is either "FAT" or "NONE".
-    /// - `mode` is either "STRESS" or "NONE".
+    /// - `mode` is either "STRESS", "STRAIN" or "NONE".
+    /// - `notch`, if given, is either "NEUBER", "GLINKA" or "NONE".
     /// - `output` is "JSON", indicating the output format.
     ///
     /// Additionally, it invokes validation on nested structs (`stress_criteria`,
@@ -103,19 +212,32 @@ impl Solution {
         }?;
 
         match self.mode.as_str() {
-            "STRESS" | "NONE" => Ok(()),
+            "STRESS" | "STRAIN" | "NONE" => Ok(()),
             _ => Err(anyhow!(
                 "mode must be STRESS, STRAIN, or NONE, got {}",
                 self.mode
             )),
         }?;
+        if let Some(notch) = &self.notch {
+            match notch.as_str() {
+                "NEUBER" | "GLINKA" | "NONE" => Ok(()),
+                _ => Err(anyhow!(
+                    "notch must be NEUBER, GLINKA, or NONE, got {}",
+                    notch
+                )),
+            }?;
+        }
         match self.output.as_str() {
             "JSON" => Ok(()),
-            _ => Err(anyhow!(
-                "output must be ANSYS or ASCII, got {}",
-                self.output
-            )),
+            _ => Err(anyhow!("output must be JSON, got {}", self.output)),
         }?;
+        if self
+            .report
+            .as_ref()
+            .is_some_and(|path| path.trim().is_empty())
+        {
+            return Err(anyhow!("report must not be empty"));
+        }
 
         self.stress_criteria.validate()?;
         self.mean.validate()?;
@@ -189,6 +311,22 @@ impl StressCriteria {
         }?;
         Ok(())
     }
+
+    /// Returns the stress criterion of the method.
+    ///
+    /// # Returns
+    ///
+    /// Returns the `StressCriterion`, or an error if the criteria are invalid or the
+    /// method is "NONE", which cannot be assessed.
+    pub fn criterion(&self) -> Result<StressCriterion> {
+        self.validate()?;
+        match (self.method.as_str(), self.number) {
+            ("VONMISES", _) => Ok(StressCriterion::VonMises),
+            ("MAXIMUM", _) => Ok(StressCriterion::MaximumPrincipal),
+            ("SXXCRIT", Some(number)) => Ok(StressCriterion::CriticalPlane(number as usize)),
+            _ => Err(anyhow!("method {} has no stress criterion", self.method)),
+        }
+    }
 }
 
 /// Represents the mean stress correction factors in a structural analysis context.
@@ -276,8 +414,8 @@ pub struct Node {
     pub from: i32,
     /// The ending node of the range. Must be greater than 0 and should be greater than or equal to `from`.
     pub to: i32,
-    /// The file path associated with the nodes. This could be a path to a file containing
-    /// additional data or configuration related to the specified node range.    
+    /// Optional node list file restricting the range to the listed nodes, or "NONE".
+    /// Node numbers are separated by whitespace or commas; `#` starts a comment.
     pub path: String,
 }
 
@@ -285,8 +423,8 @@ impl Node {
     /// Validates the `Node` struct's fields to ensure they are within acceptable ranges and conditions.
     ///
     /// Specifically, it checks that both `from` and `to` fields are greater than 0 to ensure
-    /// valid node indices. Additionally, although not explicitly checked here, `to` should ideally
-    /// be greater than or equal to `from` to represent a valid range.
+    /// valid node indices, that `to` is greater than or equal to `from`, and that the node list
+    /// file exists if one is given.
     ///
     /// # Returns
     ///
@@ -298,11 +436,14 @@ impl Node {
     /// ```
     /// use fatigue::config::Node;
     ///
-    /// let node_range = Node { from: 1, to: 10, path: String::from("path/to/data") };
+    /// let node_range = Node { from: 1, to: 10, path: String::from("NONE") };
     /// assert!(node_range.validate().is_ok());
     ///
-    /// let invalid_node_range = Node { from: 0, to: 5, path: String::from("path/to/data") };
+    /// let invalid_node_range = Node { from: 0, to: 5, path: String::from("NONE") };
     /// assert!(invalid_node_range.validate().is_err());
+    ///
+    /// let reversed_node_range = Node { from: 10, to: 5, path: String::from("NONE") };
+    /// assert!(reversed_node_range.validate().is_err());
     /// ```
     pub fn validate(&self) -> Result<()> {
         // Validate the 'from' field to ensure it's greater than 0
@@ -313,8 +454,238 @@ impl Node {
         if self.to <= 0 {
             return Err(anyhow!("'to' must be greater than 0, got {}", self.to));
         };
+        if self.to < self.from {
+            return Err(anyhow!(
+                "'to' must be greater than or equal to 'from' ({}), got {}",
+                self.from,
+                self.to
+            ));
+        }
+        if let Some(path) = self.list_path() {
+            if !path.exists() {
+                return Err(anyhow!("node list file does not exist: {}", path.display()));
+            }
+        }
         Ok(())
     }
+
+    /// Returns the node list file, or `None` if `path` is empty or "NONE".
+    fn list_path(&self) -> Option<&Path> {
+        match self.path.trim() {
+            "" | "NONE" => None,
+            path => Some(Path::new(path)),
+        }
+    }
+
+    /// Returns the selection of nodes to load and assess, reading the node list file if given.
+    ///
+    /// # Returns
+    ///
+    /// Returns the `NodeSelection`, or an error naming the line if the node list file cannot
+    /// be read or holds something other than node numbers.
+    pub fn selection(&self) -> Result<NodeSelection> {
+        let nodes = match self.list_path() {
+            Some(path) => Some(read_node_list(path)?),
+            None => None,
+        };
+        Ok(NodeSelection {
+            from: self.from.max(0) as usize,
+            to: self.to.max(0) as usize,
+            nodes,
+        })
+    }
+}
+
+/// The nodes selected by `Solution.node`: a range, optionally restricted to a node list.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NodeSelection {
+    /// The first node of the inclusive range.
+    pub from: usize,
+    /// The last node of the inclusive range.
+    pub to: usize,
+    /// The listed nodes, or `None` to select the whole range.
+    pub nodes: Option<HashSet<usize>>,
+}
+
+impl NodeSelection {
+    /// Returns `true` if the node is in the range and, if a node list is given, in the list.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fatigue::config::NodeSelection;
+    ///
+    /// let range = NodeSelection { from: 10, to: 20, nodes: None };
+    /// assert!(range.contains(10) && range.contains(20) && !range.contains(21));
+    ///
+    /// let listed = NodeSelection { from: 10, to: 20, nodes: Some([12, 30].into()) };
+    /// assert!(listed.contains(12) && !listed.contains(13) && !listed.contains(30));
+    /// ```
+    pub fn contains(&self, node: usize) -> bool {
+        (self.from..=self.to).contains(&node)
+            && self
+                .nodes
+                .as_ref()
+                .is_none_or(|nodes| nodes.contains(&node))
+    }
+}
+
+/// Reads node numbers separated by whitespace or commas, ignoring `#` comments.
+fn read_node_list(path: &Path) -> Result<HashSet<usize>> {
+    let content = fs::read_to_string(path)
+        .map_err(|e| anyhow!("Failed to read node list {}: {}", path.display(), e))?;
+    let mut nodes = HashSet::new();
+    for (index, line) in content.lines().enumerate() {
+        let data = line.split('#').next().unwrap_or_default();
+        for field in data.split(|c: char| c == ',' || c.is_whitespace()) {
+            if field.is_empty() {
+                continue;
+            }
+            let node = field.parse::<usize>().map_err(|_| {
+                anyhow!(
+                    "{}:{}: expected a node number, got '{}'",
+                    path.display(),
+                    index + 1,
+                    field
+                )
+            })?;
+            nodes.insert(node);
+        }
+    }
+    Ok(nodes)
+}
+
+/// Represents a named set of nodes assessed with its own material and stress criterion.
+///
+/// The set holds the nodes of its inclusive `ranges`, its `nodes` and the node list `file`.
+/// Unset fields fall back to the global `material`, no thickness correction and
+/// `Solution.stress_criteria`.
+#[derive(Debug, Deserialize)]
+pub struct NodeSet {
+    /// The name the damage of the set is reported under.
+    pub name: String,
+    /// Inclusive node ranges `[from, to]`.
+    #[serde(default)]
+    pub ranges: Vec<[usize; 2]>,
+    /// Individual nodes.
+    #[serde(default)]
+    pub nodes: Vec<usize>,
+    /// Optional node list file, read like the list file of `Solution.node`.
+    pub file: Option<String>,
+    /// Optional material and S-N curve of the set.
+    pub material: Option<Material>,
+    /// Optional member thickness for the thickness correction of the material.
+    pub thickness: Option<f64>,
+    /// Optional stress criterion of the set.
+    pub stress_criteria: Option<StressCriteria>,
+}
+
+impl NodeSet {
+    /// Validates the `NodeSet` struct's fields.
+    ///
+    /// Checks that the name is not empty, that the set selects nodes, that ranges are
+    /// positive and ascending, that the node list file exists and that the thickness,
+    /// material and stress criteria are valid.
+    pub fn validate(&self) -> Result<()> {
+        if self.name.trim().is_empty() {
+            return Err(anyhow!("name must not be empty"));
+        }
+        if self.ranges.is_empty() && self.nodes.is_empty() && self.file.is_none() {
+            return Err(anyhow!("ranges, nodes or file must be given"));
+        }
+        for [from, to] in self.ranges.iter() {
+            if *from == 0 || to < from {
+                return Err(anyhow!(
+                    "ranges must be [from, to] with 0 < from <= to, got [{}, {}]",
+                    from,
+                    to
+                ));
+            }
+        }
+        if self.nodes.contains(&0) {
+            return Err(anyhow!("nodes must be greater than 0"));
+        }
+        if let Some(file) = &self.file {
+            if !Path::new(file).exists() {
+                return Err(anyhow!("node list file does not exist: {}", file));
+            }
+        }
+        if let Some(thickness) = self.thickness {
+            if thickness <= 0.0 {
+                return Err(anyhow!(
+                    "thickness must be greater than 0.0, got {}",
+                    thickness
+                ));
+            }
+        }
+        if let Some(material) = &self.material {
+            material.validate()?;
+        }
+        if let Some(criteria) = &self.stress_criteria {
+            criteria.validate()?;
+        }
+        Ok(())
+    }
+
+    /// Returns the nodes of the set, reading the node list file if given.
+    pub fn selection(&self) -> Result<NodeSetSelection> {
+        let mut nodes: HashSet<usize> = self.nodes.iter().copied().collect();
+        if let Some(file) = &self.file {
+            nodes.extend(read_node_list(Path::new(file))?);
+        }
+        Ok(NodeSetSelection {
+            ranges: self.ranges.clone(),
+            nodes,
+        })
+    }
+}
+
+/// The nodes of a `NodeSet`: the union of inclusive ranges and individual nodes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NodeSetSelection {
+    /// Inclusive node ranges `[from, to]`.
+    pub ranges: Vec<[usize; 2]>,
+    /// Individual nodes.
+    pub nodes: HashSet<usize>,
+}
+
+impl NodeSetSelection {
+    /// Returns `true` if the node is in one of the ranges or among the nodes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fatigue::config::NodeSetSelection;
+    ///
+    /// let set = NodeSetSelection { ranges: vec![[1, 10]], nodes: [42].into() };
+    /// assert!(set.contains(1) && set.contains(10) && set.contains(42));
+    /// assert!(!set.contains(11));
+    /// ```
+    pub fn contains(&self, node: usize) -> bool {
+        self.nodes.contains(&node)
+            || self
+                .ranges
+                .iter()
+                .any(|[from, to]| (*from..=*to).contains(&node))
+    }
+
+    /// Returns the smallest node of the listed nodes or the first overlap of the ranges
+    /// contained in both selections, if any.
+    pub fn shared_node(&self, other: &NodeSetSelection) -> Option<usize> {
+        for [from, to] in self.ranges.iter() {
+            for [other_from, other_to] in other.ranges.iter() {
+                if from.max(other_from) <= to.min(other_to) {
+                    return Some(*from.max(other_from));
+                }
+            }
+        }
+        self.nodes
+            .iter()
+            .filter(|node| other.contains(**node))
+            .chain(other.nodes.iter().filter(|node| self.contains(**node)))
+            .min()
+            .copied()
+    }
 }
 
 /// Represents damage metrics associated with a material under analysis.
@@ -416,6 +787,46 @@ impl SafetyFactor {
     }
 }
 
+/// Length units material values such as a thickness `reference` may be annotated with.
+const LENGTH_UNITS: &[&str] = &["mm", "cm", "m"];
+
+/// Replaces unit-annotated stresses in the `material` section by numbers in the stress unit.
+///
+/// Strings that parse as a unit-annotated value, such as `"-5 MPa"` or `".5 GPa"`, must
+/// be stresses, and lengths such as `"25 mm"` are refused; other strings such as the
+/// material name are left untouched. Returns the number of replaced values.
+fn normalize_material_units(value: &mut Value, stress: &Unit, key: &str) -> Result<usize> {
+    match value {
+        Value::Mapping(mapping) => {
+            let mut replaced = 0;
+            for (name, item) in mapping.iter_mut() {
+                let name = name.as_str().unwrap_or_default();
+                replaced += normalize_material_units(item, stress, &format!("{}.{}", key, name))?;
+            }
+            Ok(replaced)
+        }
+        Value::String(text) => {
+            let Ok((number, unit)) = parse_measure(text) else {
+                let length = LENGTH_UNITS.iter().find(|symbol| {
+                    text.trim()
+                        .strip_suffix(**symbol)
+                        .is_some_and(|number| number.trim().parse::<f64>().is_ok())
+                });
+                return match length {
+                    Some(symbol) => Err(anyhow!("{}: {} is not a stress unit", key, symbol)),
+                    None => Ok(0),
+                };
+            };
+            if unit.quantity != stress.quantity {
+                return Err(anyhow!("{}: {} is not a stress unit", key, unit.symbol));
+            }
+            *value = Value::from(unit.convert(number, stress)?);
+            Ok(1)
+        }
+        _ => Ok(0),
+    }
+}
+
 /// Loads the configuration from a YAML file.
 ///
 /// # Arguments
@@ -428,9 +839,32 @@ impl SafetyFactor {
 ///
 /// # Errors
 ///
-/// This function will return an error if reading or parsing the configuration file fails.
+/// This function will return an error if reading or parsing the configuration file fails,
+/// or if a unit-annotated material value is not a valid stress.
 pub fn load_config<P: AsRef<Path>>(config_path: P) -> Result<Config, Box<dyn std::error::Error>> {
-    let content = fs::read_to_string(config_path)?;
+    let mut content = fs::read_to_string(config_path)?;
+    let mut document: Value = serde_yaml::from_str(&content)?;
+    let stress = match document.get("units").and_then(|units| units.get("stress")) {
+        Some(symbol) => Unit::parse(symbol.as_str().unwrap_or_default())
+            .map_err(|e| anyhow!("units.stress: {}", e))?,
+        None => Unit::parse(&UnitSystem::default().stress)?,
+    };
+    let mut replaced = 0;
+    if let Some(material) = document.get_mut("material") {
+        replaced += normalize_material_units(material, &stress, "material")?;
+    }
+    if let Some(Value::Sequence(sets)) = document.get_mut("node_sets") {
+        for (index, set) in sets.iter_mut().enumerate() {
+            if let Some(material) = set.get_mut("material") {
+                let key = format!("node_sets[{}].material", index);
+                replaced += normalize_material_units(material, &stress, &key)?;
+            }
+        }
+    }
+    // Re-serialize only when needed, so parse errors of plain files keep their locations.
+    if replaced > 0 {
+        content = serde_yaml::to_string(&document)?;
+    }
     let config: Config = serde_yaml::from_str(&content)?;
     Ok(config)
 }
@@ -438,6 +872,12 @@ pub fn load_config<P: AsRef<Path>>(config_path: P) -> Result<Config, Box<dyn std
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::interpolate::{
+        Delaunay, DuplicatePoints, Extrapolation, Grid, GridOrder, InterpolationStrategy,
+        InterpolationStrategyEnum, InverseDistance, Outside, Rbf, RbfKernel,
+    };
+    use crate::material::StrainLife;
+    use approx::assert_relative_eq;
 
     #[test]
     fn test_load_config() {
@@ -450,4 +890,510 @@ mod tests {
         );
         // Additional tests as needed
     }
+
+    #[test]
+    fn test_strain_mode_requires_cyclic_curve() {
+        let mut config = load_config("tests/config.yaml").expect("Failed to load config");
+        config.solution.mode = "STRAIN".to_string();
+        let err = config.validate().unwrap_err();
+        assert!(format!("{err:#}").contains("requires notch"));
+
+        config.solution.notch = Some("NEUBER".to_string());
+        let err = config.validate().unwrap_err();
+        assert!(format!("{err:#}").contains("cyclic curve"));
+
+        config.material.cyclic = Some(crate::material::CyclicCurve {
+            k_prime: 1100.0,
+            n_prime: 0.15,
+        });
+        let err = config.validate().unwrap_err();
+        assert!(format!("{err:#}")
+            .contains("mode STRAIN requires a strain-life curve for material 'Steel'"));
+
+        config.material.strain_life = Some(StrainLife {
+            sigma_f: 900.0,
+            b: -0.09,
+            epsilon_f: 0.3,
+            c: -0.55,
+        });
+        assert!(config.validate().is_ok(), "{:?}", config.validate());
+
+        // The material of a node set needs the curves as well.
+        let mut config = load_config("tests/config_node_sets.yaml").expect("Failed to load config");
+        config.solution.mode = "STRAIN".to_string();
+        config.solution.notch = Some("GLINKA".to_string());
+        config.material.cyclic = Some(crate::material::CyclicCurve {
+            k_prime: 1100.0,
+            n_prime: 0.15,
+        });
+        config.material.strain_life = Some(StrainLife {
+            sigma_f: 900.0,
+            b: -0.09,
+            epsilon_f: 0.3,
+            c: -0.55,
+        });
+        let err = config.validate().unwrap_err();
+        assert!(format!("{err:#}")
+            .contains("mode STRAIN requires a cyclic curve for material 'Weld FAT71'"));
+    }
+
+    #[test]
+    fn test_strain_life_validation() {
+        let mut config = load_config("tests/config.yaml").expect("Failed to load config");
+        for (sigma_f, b, epsilon_f, c, message) in [
+            (
+                0.0,
+                -0.09,
+                0.3,
+                -0.55,
+                "sigma_f must be greater than 0.0, got 0",
+            ),
+            (
+                900.0,
+                -0.09,
+                f64::NAN,
+                -0.55,
+                "epsilon_f must be greater than 0.0, got NaN",
+            ),
+            (900.0, 0.1, 0.3, -0.55, "b must be less than 0.0, got 0.1"),
+            (900.0, -0.09, 0.3, 0.0, "c must be less than 0.0, got 0"),
+        ] {
+            config.material.strain_life = Some(StrainLife {
+                sigma_f,
+                b,
+                epsilon_f,
+                c,
+            });
+            let err = config.validate().unwrap_err();
+            assert!(format!("{err:#}").contains(message), "{err:#}");
+        }
+    }
+
+    #[test]
+    fn test_interpolation_rejects_strain_sensors() {
+        let mut config = load_config("tests/config.yaml").expect("Failed to load config");
+        config.timeseries.sensorfile = "tests/timeseries/sensors_strain_gauge.json".to_string();
+        let err = config.validate().unwrap_err();
+        assert!(format!("{err:#}").contains("Sensor 'Fx' measures strain"));
+    }
+
+    #[test]
+    fn test_unit_annotated_material() {
+        let mut config = load_config("tests/config_units.yaml").expect("Failed to load config");
+        assert!(config.validate().is_ok(), "{:?}", config.validate());
+        assert_relative_eq!(config.material.youngs_modulus, 210000.0);
+        assert_relative_eq!(config.material.yield_stress, 355.0);
+        assert_relative_eq!(config.material.fatigue.knee.stress, 52.0);
+        assert_relative_eq!(config.material.ultimate_stress, 510.0);
+
+        // Stress files in another stress unit are converted when read, other units are refused.
+        let file = config.timeseries.interpolations[0].points[0]
+            .file
+            .clone()
+            .unwrap();
+        let read = |config: &Config| {
+            config.timeseries.interpolations[0]
+                .read_stress_tensors(&file, &config.units, |node| node <= 3)
+                .unwrap()
+        };
+        let mpa = read(&config);
+        config.timeseries.interpolations[0].unit = Some("kPa".to_string());
+        assert!(config.validate().is_ok(), "{:?}", config.validate());
+        let kpa = read(&config);
+        assert_eq!(kpa.len(), 3);
+        for ((_, kpa), (_, mpa)) in kpa.iter().zip(&mpa) {
+            assert_relative_eq!(kpa.sxx(), 1.0e-3 * mpa.sxx(), max_relative = 1e-12);
+            assert_relative_eq!(kpa.szx(), 1.0e-3 * mpa.szx(), max_relative = 1e-12);
+        }
+        config.timeseries.interpolations[0].unit = Some("N".to_string());
+        let err = config.validate().unwrap_err();
+        assert!(format!("{err:#}").contains("must be a stress unit, got N (force)"));
+
+        config.timeseries.interpolations[0].unit = None;
+        config.units.force = "kN".to_string();
+        assert!(config.validate().is_ok(), "{:?}", config.validate());
+        let kn = config
+            .timeseries
+            .read_loadcase(&config.timeseries.loadcases[0], &config.units)
+            .unwrap();
+        let n = config
+            .timeseries
+            .read_loadcase(&config.timeseries.loadcases[0], &UnitSystem::default())
+            .unwrap();
+        let (kn, n) = (kn.channel("Fx").unwrap(), n.channel("Fx").unwrap());
+        assert_eq!((kn.unit.as_str(), n.unit.as_str()), ("kN", "N"));
+        for (kn, n) in kn.values.iter().zip(&n.values) {
+            assert_relative_eq!(*kn * 1000.0, *n, max_relative = 1e-12);
+        }
+
+        config.units.force = "MPa".to_string();
+        let err = config.validate().unwrap_err();
+        assert!(format!("{err:#}").contains("units.force must be a force unit"));
+    }
+
+    #[test]
+    fn test_unit_annotated_material_in_declared_system() {
+        let mut document: Value =
+            serde_yaml::from_str("{youngs_modulus: 210 GPa, name: Steel}").unwrap();
+        let gpa = Unit::parse("GPa").unwrap();
+        normalize_material_units(&mut document, &gpa, "material").unwrap();
+        assert_relative_eq!(document["youngs_modulus"].as_f64().unwrap(), 210.0);
+        assert_eq!(document["name"].as_str(), Some("Steel"));
+
+        let mut document: Value = serde_yaml::from_str("{knee: {stress: 52 kN}}").unwrap();
+        let err = normalize_material_units(&mut document, &gpa, "material").unwrap_err();
+        assert!(format!("{err:#}").contains("material.knee.stress: kN is not a stress unit"));
+        let mut document: Value =
+            serde_yaml::from_str("{thickness: {reference: 25 mm, exponent: 0.2}}").unwrap();
+        let err = normalize_material_units(&mut document, &gpa, "material").unwrap_err();
+        assert!(
+            format!("{err:#}").contains("material.thickness.reference: mm is not a stress unit")
+        );
+
+        let mut document: Value =
+            serde_yaml::from_str("{mean: -5 MPa, modulus: .5 GPa, name: 5 Steel}").unwrap();
+        let mpa = Unit::parse("MPa").unwrap();
+        assert_eq!(
+            normalize_material_units(&mut document, &mpa, "material").unwrap(),
+            2
+        );
+        assert_relative_eq!(document["mean"].as_f64().unwrap(), -5.0);
+        assert_relative_eq!(document["modulus"].as_f64().unwrap(), 500.0);
+        assert_eq!(document["name"].as_str(), Some("5 Steel"));
+    }
+
+    #[test]
+    fn test_interpolation_accepts_derived_channels() {
+        let mut config = load_config("tests/config.yaml").expect("Failed to load config");
+        config.timeseries.interpolations[0].sensor[2] = "Fres".to_string();
+        let err = config.validate().unwrap_err();
+        assert!(format!("{err:#}").contains("Sensor 'Fres' not found"));
+
+        config
+            .timeseries
+            .variables
+            .insert("Fres".to_string(), "math::sqrt(Fx^2 + Fy^2)".to_string());
+        config.timeseries.expressions.order.push("Fres".to_string());
+        assert!(config.validate().is_ok(), "{:?}", config.validate());
+    }
+
+    #[test]
+    fn test_node_selection() {
+        let mut config = load_config("tests/config.yaml").expect("Failed to load config");
+        let selection = config.solution.node.selection().unwrap();
+        assert_eq!(
+            (selection.from, selection.to, selection.nodes),
+            (1, 1000, None)
+        );
+
+        config.solution.node.path = "tests/nodes/hotspot.txt".to_string();
+        assert!(config.validate().is_ok(), "{:?}", config.validate());
+        let selection = config.solution.node.selection().unwrap();
+        assert_eq!(selection.nodes, Some(HashSet::from([3, 5, 7, 9, 19000])));
+        assert!(selection.contains(7));
+        assert!(!selection.contains(4));
+        assert!(!selection.contains(19000));
+
+        config.solution.node.path = "tests/nodes/malformed.txt".to_string();
+        let err = config.solution.node.selection().unwrap_err();
+        assert!(format!("{err:#}").contains("malformed.txt:2: expected a node number, got 'seven'"));
+
+        config.solution.node.path = "tests/nodes/missing.txt".to_string();
+        let err = config.validate().unwrap_err();
+        assert!(format!("{err:#}").contains("node list file does not exist"));
+
+        config.solution.node.path = "NONE".to_string();
+        config.solution.node.to = 5;
+        config.solution.node.from = 6;
+        let err = config.validate().unwrap_err();
+        assert!(
+            format!("{err:#}").contains("'to' must be greater than or equal to 'from' (6), got 5")
+        );
+    }
+
+    #[test]
+    fn test_node_sets() {
+        let mut config = load_config("tests/config_node_sets.yaml").expect("Failed to load config");
+        assert!(config.validate().is_ok(), "{:?}", config.validate());
+        let weld = &config.node_sets[1];
+        let material = weld.material.as_ref().unwrap();
+        assert_relative_eq!(material.youngs_modulus, 210000.0);
+        assert_eq!(
+            weld.stress_criteria.as_ref().unwrap().criterion().unwrap(),
+            StressCriterion::VonMises
+        );
+        let selection = weld.selection().unwrap();
+        assert!(selection.contains(21) && selection.contains(500) && !selection.contains(31));
+
+        config.node_sets[1].file = Some("tests/nodes/hotspot.txt".to_string());
+        let err = config.validate().unwrap_err();
+        assert!(format!("{err:#}").contains("node sets 'base' and 'weld' both contain node 3"));
+
+        config.node_sets[1].file = None;
+        config.node_sets[1].ranges = vec![[30, 21]];
+        let err = config.validate().unwrap_err();
+        assert!(format!("{err:#}").contains("node set 'weld': ranges must be [from, to]"));
+
+        config.node_sets[1].ranges = vec![];
+        config.node_sets[0].thickness = Some(40.0);
+        let err = config.validate().unwrap_err();
+        assert!(format!("{err:#}").contains(
+            "node set 'base' has a thickness, but material 'Steel' has no thickness correction"
+        ));
+
+        config.node_sets[0].thickness = None;
+        config.node_sets[0].name = "weld".to_string();
+        let err = config.validate().unwrap_err();
+        assert!(format!("{err:#}").contains("node set 'weld' is defined twice"));
+    }
+
+    #[test]
+    fn test_interpolation_report_format() {
+        let mut config = load_config("tests/config.yaml").expect("Failed to load config");
+        let interp = &mut config.timeseries.interpolations[0];
+        interp.format = "RPT".to_string();
+        for point in interp.points.iter_mut() {
+            point.file = Some("unit_fx.rpt".to_string());
+        }
+        assert!(config.validate().is_ok(), "{:?}", config.validate());
+        let interp = &config.timeseries.interpolations[0];
+        let tensors = interp
+            .read_stress_tensors("unit_fx.rpt", &config.units, |node| node > 1)
+            .unwrap();
+        let nodes: Vec<usize> = tensors.iter().map(|(node, _)| *node).collect();
+        assert_eq!(nodes, vec![2, 3]);
+    }
+
+    #[test]
+    fn test_interpolation_csv_requires_columns() {
+        let mut config = load_config("tests/config.yaml").expect("Failed to load config");
+        let interp = &mut config.timeseries.interpolations[0];
+        interp.format = "CSV".to_string();
+        let err = config.validate().unwrap_err();
+        assert!(format!("{err:#}").contains("format CSV requires columns"));
+    }
+
+    #[test]
+    fn test_interpolation_cache_requires_usf() {
+        let mut config = load_config("tests/config.yaml").expect("Failed to load config");
+        let interp = &mut config.timeseries.interpolations[0];
+        interp.format = "PCH".to_string();
+        interp.cache = true;
+        let err = config.validate().unwrap_err();
+        assert!(format!("{err:#}").contains("cache is only supported for format USF"));
+    }
+
+    #[test]
+    fn test_interpolation_unknown_format() {
+        let mut config = load_config("tests/config.yaml").expect("Failed to load config");
+        let interp = &mut config.timeseries.interpolations[0];
+        interp.format = "OP2".to_string();
+        let err = config.validate().unwrap_err();
+        assert!(format!("{err:#}").contains("format must be USF, PCH, RPT, or CSV, got OP2"));
+    }
+
+    #[test]
+    fn test_interpolation_extrapolation() {
+        let mut config = load_config("tests/config.yaml").expect("Failed to load config");
+        assert!(!config.timeseries.interpolations[0].extrapolates());
+
+        // The triangulation needs the unit-load points to span all three dimensions.
+        let interp = &mut config.timeseries.interpolations[0];
+        interp.method = "LINEAR_DELAUNAY".to_string();
+        interp.points[0].coordinates = vec![0.0, 0.0, 0.0];
+        assert!(config.validate().is_ok(), "{:?}", config.validate());
+        // Piecewise methods apply the outside policy to their hull.
+        for (outside, expected) in [
+            (None, Extrapolation::Linear),
+            (Some("WARN"), Extrapolation::Linear),
+            (Some("CLAMP"), Extrapolation::Clamp),
+            (Some("ERROR"), Extrapolation::Error),
+        ] {
+            let interp = &mut config.timeseries.interpolations[0];
+            interp.outside = outside.map(str::to_string);
+            assert!(interp.extrapolates());
+            assert!(matches!(
+                interp.strategy().unwrap(),
+                InterpolationStrategyEnum::Delaunay(Delaunay { extrapolation }) if extrapolation == expected
+            ));
+        }
+
+        let interp = &mut config.timeseries.interpolations[0];
+        interp.method = "NONE".to_string();
+        let err = interp.strategy().unwrap_err();
+        assert!(format!("{err:#}").contains("with method NONE does not interpolate"));
+    }
+
+    #[test]
+    fn test_interpolation_grid() {
+        let mut config = load_config("tests/config.yaml").expect("Failed to load config");
+        let interp = &mut config.timeseries.interpolations[0];
+        interp.grid = Some(vec![vec![0.0, 1.0], vec![0.0, 1.0], vec![0.0, 1.0]]);
+        let err = config.validate().unwrap_err();
+        assert!(
+            format!("{err:#}").contains("grid is only used by methods LINEAR_GRID and CUBIC_GRID")
+        );
+
+        let interp = &mut config.timeseries.interpolations[0];
+        interp.method = "CUBIC_GRID".to_string();
+        interp.points[0].coordinates = vec![0.0, 0.0, 0.0];
+        assert!(config.validate().is_ok(), "{:?}", config.validate());
+        let interp = &config.timeseries.interpolations[0];
+        assert!(matches!(
+            interp.strategy().unwrap(),
+            InterpolationStrategyEnum::Grid(Grid {
+                order: GridOrder::Cubic,
+                extrapolation: Extrapolation::Linear,
+                axes: Some(_),
+            })
+        ));
+        // The unit-load points are not a full grid of the axes.
+        let Err(err) = interp.strategy().unwrap().prepare(&interp.points) else {
+            panic!("the unit-load points must not form a grid");
+        };
+        assert!(format!("{err:#}").contains("the grid is missing 4 of 8 nodes"));
+
+        let interp = &mut config.timeseries.interpolations[0];
+        interp.grid = Some(vec![vec![0.0, 1.0], vec![1.0, 0.0], vec![0.0, 1.0]]);
+        let err = config.validate().unwrap_err();
+        assert!(format!("{err:#}").contains("grid axes must have at least 2 ascending values"));
+    }
+
+    #[test]
+    fn test_interpolation_rbf_and_idw() {
+        let mut config = load_config("tests/config.yaml").expect("Failed to load config");
+        let interp = &mut config.timeseries.interpolations[0];
+        interp.method = "RBF_GAUSSIAN".to_string();
+        interp.shape = Some(0.5);
+        interp.polynomial = Some(false);
+        assert!(config.validate().is_ok(), "{:?}", config.validate());
+        for shape in [0.0, f64::NAN, f64::INFINITY] {
+            config.timeseries.interpolations[0].shape = Some(shape);
+            let err = config.validate().unwrap_err();
+            assert!(format!("{err:#}")
+                .contains(&format!("shape must be greater than 0.0, got {shape}")));
+        }
+        config.timeseries.interpolations[0].shape = Some(0.5);
+        assert!(matches!(
+            config.timeseries.interpolations[0].strategy().unwrap(),
+            InterpolationStrategyEnum::Rbf(Rbf {
+                kernel: RbfKernel::Gaussian,
+                shape: 0.5,
+                polynomial: false,
+            })
+        ));
+
+        let interp = &mut config.timeseries.interpolations[0];
+        interp.method = "RBF_THIN_PLATE".to_string();
+        let err = config.validate().unwrap_err();
+        assert!(format!("{err:#}")
+            .contains("shape is only used by methods RBF_MULTIQUADRIC and RBF_GAUSSIAN"));
+
+        let interp = &mut config.timeseries.interpolations[0];
+        interp.method = "IDW".to_string();
+        interp.shape = None;
+        let err = config.validate().unwrap_err();
+        assert!(format!("{err:#}").contains("polynomial is only used by RBF_* methods"));
+
+        let interp = &mut config.timeseries.interpolations[0];
+        interp.polynomial = None;
+        interp.power = Some(0.0);
+        let err = config.validate().unwrap_err();
+        assert!(format!("{err:#}").contains("power must be greater than 0.0, got 0"));
+        let interp = &mut config.timeseries.interpolations[0];
+        interp.power = Some(f64::NAN);
+        let err = config.validate().unwrap_err();
+        assert!(format!("{err:#}").contains("power must be greater than 0.0, got NaN"));
+        let interp = &mut config.timeseries.interpolations[0];
+        interp.power = None;
+        interp.neighbors = Some(0);
+        let err = config.validate().unwrap_err();
+        assert!(format!("{err:#}").contains("neighbors must be greater than 0"));
+        let interp = &mut config.timeseries.interpolations[0];
+        interp.neighbors = None;
+        assert!(config.validate().is_ok(), "{:?}", config.validate());
+        assert!(matches!(
+            config.timeseries.interpolations[0].strategy().unwrap(),
+            InterpolationStrategyEnum::InverseDistance(InverseDistance {
+                power,
+                neighbors: None,
+            }) if power == 2.0
+        ));
+    }
+
+    #[test]
+    fn test_interpolation_duplicates_and_span() {
+        let mut config = load_config("tests/config.yaml").expect("Failed to load config");
+        let interp = &mut config.timeseries.interpolations[0];
+        interp.points[0].coordinates = vec![1.0, 0.0, 0.000004];
+        interp.duplicates = None;
+        let err = config.validate().unwrap_err();
+        assert!(format!("{err:#}").contains(
+            "points FX0FY0FZ0.usf and FX1FY0FZ0.usf of interpolation 'StressTimeseries' share coordinates [1.0, 0.0, 0.0]"
+        ));
+        // Merged, the three remaining points span a plane only, which the regression of
+        // LINEAR allows.
+        let interp = &mut config.timeseries.interpolations[0];
+        interp.duplicates = Some("AVERAGE".to_string());
+        assert!(config.validate().is_ok(), "{:?}", config.validate());
+        config.timeseries.interpolations[0].method = "LINEAR_DELAUNAY".to_string();
+        let err = config.validate().unwrap_err();
+        assert!(format!("{err:#}").contains(
+            "points of interpolation 'StressTimeseries' span 2 of 3 dimensions, method LINEAR_DELAUNAY needs all of them"
+        ));
+        for (method, valid) in [
+            ("LINEAR_GRID", false),
+            ("RBF_THIN_PLATE", false),
+            ("IDW", true),
+            ("NEAREST", true),
+        ] {
+            config.timeseries.interpolations[0].method = method.to_string();
+            assert_eq!(config.validate().is_ok(), valid, "{method}");
+        }
+        let interp = &mut config.timeseries.interpolations[0];
+        interp.method = "RBF_GAUSSIAN".to_string();
+        interp.polynomial = Some(false);
+        assert!(config.validate().is_ok(), "{:?}", config.validate());
+        assert_eq!(
+            config.timeseries.interpolations[0].duplicate_points(),
+            DuplicatePoints::Average
+        );
+
+        config.timeseries.interpolations[0].duplicates = Some("MEAN".to_string());
+        let err = config.validate().unwrap_err();
+        assert!(format!("{err:#}")
+            .contains("duplicates must be ERROR, FIRST, LAST, or AVERAGE, got MEAN"));
+    }
+
+    #[test]
+    fn test_interpolation_outside() {
+        let mut config = load_config("tests/config.yaml").expect("Failed to load config");
+        assert_eq!(
+            config.timeseries.interpolations[0].outside_policy(),
+            Outside::Allow
+        );
+        config.timeseries.interpolations[0].outside = Some("CLAMP".to_string());
+        assert!(config.validate().is_ok(), "{:?}", config.validate());
+        assert_eq!(
+            config.timeseries.interpolations[0].outside_policy(),
+            Outside::Clamp
+        );
+        config.timeseries.interpolations[0].outside = Some("EXTEND".to_string());
+        let err = config.validate().unwrap_err();
+        assert!(
+            format!("{err:#}").contains("outside must be ALLOW, CLAMP, ERROR, or WARN, got EXTEND")
+        );
+    }
+
+    #[test]
+    fn test_solution_output() {
+        let mut config = load_config("tests/config.yaml").expect("Failed to load config");
+        config.solution.output = "ASCII".to_string();
+        let err = config.validate().unwrap_err();
+        assert!(format!("{err:#}").contains("output must be JSON, got ASCII"));
+        config.solution.output = "JSON".to_string();
+        config.solution.report = Some(" ".to_string());
+        let err = config.validate().unwrap_err();
+        assert!(format!("{err:#}").contains("report must not be empty"));
+    }
 }
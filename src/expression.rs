@@ -0,0 +1,531 @@
+//! A module for compiled arithmetic expressions evaluated over whole channels.
+//!
+//! The `variables` of the configuration are written in evalexpr syntax. Evaluating them
+//! with evalexpr sample by sample sets up a context and walks the operator tree for every
+//! row, which dominates the run time on long load cases. `CompiledExpression` parses an
+//! expression once, folds parameters and other constants, and then evaluates every node
+//! over a block of rows at a time. Blocks are independent, so long channels are split
+//! across threads with rayon.
+//!
+//! Results match evalexpr: subexpressions without channels are folded by evalexpr itself,
+//! so `1 / 2` is the integer `0`, and channels are floats, so every operation on them is
+//! a float operation. Only `min` and `max` of channels and integers have a value-dependent
+//! type in evalexpr; dividing them by an integer is rejected.
+use anyhow::{anyhow, Result};
+use evalexpr::{
+    build_operator_tree, ContextWithMutableVariables, HashMapContext, Node, Operator, Value,
+};
+use rayon::prelude::*;
+use std::collections::HashMap;
+use std::ops::Range;
+
+/// The number of rows evaluated per block and thread.
+const BLOCK: usize = 4096;
+
+/// The number of rows from which blocks are evaluated in parallel.
+const PARALLEL_ROWS: usize = 16 * BLOCK;
+
+/// The evalexpr type of the values of a compiled node.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    /// Integers, only for nodes without channels.
+    Int,
+    /// Floats.
+    Float,
+    /// Integers or floats depending on the values, from `min` and `max` of channels and integers.
+    Either,
+}
+
+/// A node of a compiled expression.
+#[derive(Debug, Clone)]
+enum Expr {
+    /// A literal, parameter or folded subexpression.
+    Constant(f64),
+    /// The column at an index of the compiled column names.
+    Column(usize),
+    /// A function of one argument, such as negation or `math::sqrt`.
+    Unary(fn(f64) -> f64, Box<Expr>),
+    /// A function of two arguments, such as addition or `math::atan2`.
+    Binary(fn(f64, f64) -> f64, Box<Expr>, Box<Expr>),
+}
+
+impl Expr {
+    /// Returns a unary node, folded if its argument is constant.
+    fn unary(f: fn(f64) -> f64, a: Expr) -> Expr {
+        match a {
+            Expr::Constant(a) => Expr::Constant(f(a)),
+            a => Expr::Unary(f, Box::new(a)),
+        }
+    }
+
+    /// Returns a binary node, folded if both arguments are constant.
+    fn binary(f: fn(f64, f64) -> f64, a: Expr, b: Expr) -> Expr {
+        match (a, b) {
+            (Expr::Constant(a), Expr::Constant(b)) => Expr::Constant(f(a, b)),
+            (a, b) => Expr::Binary(f, Box::new(a), Box::new(b)),
+        }
+    }
+
+    /// Evaluates the node over a range of rows.
+    fn eval(&self, columns: &[&[f64]], rows: Range<usize>) -> Vec<f64> {
+        match self {
+            Expr::Constant(c) => vec![*c; rows.len()],
+            Expr::Column(index) => columns[*index][rows].to_vec(),
+            Expr::Unary(f, a) => {
+                let mut values = a.eval(columns, rows);
+                values.iter_mut().for_each(|x| *x = f(*x));
+                values
+            }
+            Expr::Binary(f, a, b) => match (a.as_ref(), b.as_ref()) {
+                // Constant operands are common (`2 * Fx`, `Fx^2`) and need no buffer.
+                (a, Expr::Constant(c)) => {
+                    let mut values = a.eval(columns, rows);
+                    values.iter_mut().for_each(|x| *x = f(*x, *c));
+                    values
+                }
+                (Expr::Constant(c), b) => {
+                    let mut values = b.eval(columns, rows);
+                    values.iter_mut().for_each(|x| *x = f(*c, *x));
+                    values
+                }
+                (a, b) => {
+                    let mut values = a.eval(columns, rows.clone());
+                    let other = b.eval(columns, rows);
+                    values
+                        .iter_mut()
+                        .zip(other)
+                        .for_each(|(x, y)| *x = f(*x, y));
+                    values
+                }
+            },
+        }
+    }
+}
+
+/// An arithmetic expression compiled against named columns and constants.
+///
+/// Supported are the arithmetic operators `+ - * / % ^`, unary minus, `min`, `max`,
+/// `floor`, `round`, `ceil` and the `math::` functions `sqrt`, `cbrt`, `abs`, `exp`,
+/// `exp2`, `ln`, `log`, `log2`, `log10`, `pow`, `hypot`, the trigonometric and hyperbolic
+/// functions and their inverses, and `atan2`.
+#[derive(Debug, Clone)]
+pub struct CompiledExpression {
+    expr: Expr,
+    columns: usize,
+}
+
+impl CompiledExpression {
+    /// Compiles an evalexpr expression.
+    ///
+    /// Identifiers are resolved to the index of a name in `columns`, or else to a value
+    /// in `constants`. Subexpressions without columns are evaluated by evalexpr.
+    ///
+    /// # Returns
+    ///
+    /// Returns the compiled expression, or an error if the expression cannot be parsed,
+    /// uses an undefined identifier, uses an operator or function that is not supported,
+    /// or divides a value of value-dependent type by an integer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use evalexpr::Value;
+    /// use fatigue::expression::CompiledExpression;
+    /// use std::collections::HashMap;
+    ///
+    /// let constants = HashMap::from([("scale".to_string(), Value::Float(2.0))]);
+    /// let expression =
+    ///     CompiledExpression::compile("scale * math::hypot(Fx, Fy)", &["Fx", "Fy"], &constants)
+    ///         .unwrap();
+    /// let values = expression.evaluate(&[&[3.0, 0.0], &[4.0, 1.0]]).unwrap();
+    /// assert!((values[0] - 10.0).abs() < 1e-12 && (values[1] - 2.0).abs() < 1e-12);
+    ///
+    /// // Integer division as in evalexpr.
+    /// let half = CompiledExpression::compile("1 / 2 + Fx", &["Fx"], &constants).unwrap();
+    /// assert!((half.evaluate(&[&[1.5]]).unwrap()[0] - 1.5).abs() < 1e-12);
+    /// ```
+    pub fn compile(
+        expression: &str,
+        columns: &[&str],
+        constants: &HashMap<String, Value>,
+    ) -> Result<Self> {
+        let node = build_operator_tree(expression)
+            .map_err(|e| anyhow!("Failed to parse '{}': {}", expression, e))?;
+        let mut context = HashMapContext::new();
+        for (key, value) in constants {
+            context
+                .set_value(key.clone(), value.clone())
+                .map_err(|e| anyhow!("Failed to insert constant '{}': {}", key, e))?;
+        }
+        let compiler = Compiler {
+            columns,
+            constants,
+            context,
+        };
+        let (expr, _) = compiler
+            .node(&node)
+            .map_err(|e| anyhow!("Failed to compile '{}': {}", expression, e))?;
+        Ok(CompiledExpression {
+            expr,
+            columns: columns.len(),
+        })
+    }
+
+    /// Evaluates the expression for every row of the columns.
+    ///
+    /// `columns` are the column values in the order of the names the expression was
+    /// compiled with.
+    ///
+    /// # Returns
+    ///
+    /// Returns one value per row, or an error if the number of columns differs from
+    /// compilation or the columns differ in length.
+    pub fn evaluate(&self, columns: &[&[f64]]) -> Result<Vec<f64>> {
+        if columns.len() != self.columns {
+            return Err(anyhow!(
+                "expected {} columns, got {}",
+                self.columns,
+                columns.len()
+            ));
+        }
+        let rows = columns.first().map_or(0, |c| c.len());
+        if let Some(index) = columns.iter().position(|c| c.len() != rows) {
+            return Err(anyhow!(
+                "column {} has {} rows, expected {}",
+                index,
+                columns[index].len(),
+                rows
+            ));
+        }
+        let mut values = vec![0.0; rows];
+        let eval = |(block, out): (usize, &mut [f64])| {
+            let start = block * BLOCK;
+            out.copy_from_slice(&self.expr.eval(columns, start..start + out.len()));
+        };
+        if rows < PARALLEL_ROWS {
+            values.chunks_mut(BLOCK).enumerate().for_each(eval);
+        } else {
+            values.par_chunks_mut(BLOCK).enumerate().for_each(eval);
+        }
+        Ok(values)
+    }
+
+    /// Returns the value of the expression if it does not depend on any column.
+    pub fn constant(&self) -> Option<f64> {
+        match self.expr {
+            Expr::Constant(c) => Some(c),
+            _ => None,
+        }
+    }
+}
+
+/// Compiles evalexpr operator trees against named columns and constants.
+struct Compiler<'a> {
+    columns: &'a [&'a str],
+    constants: &'a HashMap<String, Value>,
+    context: HashMapContext,
+}
+
+impl Compiler<'_> {
+    /// Compiles an evalexpr operator tree node, returning it with the type of its values.
+    fn node(&self, node: &Node) -> Result<(Expr, Kind)> {
+        if !variables(node).any(|identifier| self.columns.contains(&identifier)) {
+            return self.constant(node);
+        }
+        let children = node.children();
+        let child = |index: usize| match children.get(index) {
+            Some(child) => self.node(child),
+            None => Err(anyhow!(
+                "operator '{}' is missing an operand",
+                node.operator()
+            )),
+        };
+        match node.operator() {
+            Operator::RootNode if children.len() == 1 => child(0),
+            Operator::VariableIdentifierRead { identifier } => {
+                match self.columns.iter().position(|c| c == identifier) {
+                    Some(index) => Ok((Expr::Column(index), Kind::Float)),
+                    None => Err(anyhow!("undefined identifier '{}'", identifier)),
+                }
+            }
+            Operator::Neg => {
+                let (a, kind) = child(0)?;
+                Ok((Expr::unary(|a| -a, a), kind))
+            }
+            Operator::Add => arithmetic(|a, b| a + b, child(0)?, child(1)?),
+            Operator::Sub => arithmetic(|a, b| a - b, child(0)?, child(1)?),
+            Operator::Mul => arithmetic(|a, b| a * b, child(0)?, child(1)?),
+            Operator::Div => division("/", |a, b| a / b, child(0)?, child(1)?),
+            Operator::Mod => division("%", |a, b| a % b, child(0)?, child(1)?),
+            Operator::Exp => Ok((
+                Expr::binary(f64::powf, child(0)?.0, child(1)?.0),
+                Kind::Float,
+            )),
+            Operator::FunctionIdentifier { identifier } if children.len() == 1 => {
+                // The argument list is parenthesized, i.e. a root node around a tuple.
+                let mut argument = &children[0];
+                while matches!(argument.operator(), Operator::RootNode)
+                    && argument.children().len() == 1
+                {
+                    argument = &argument.children()[0];
+                }
+                let arguments = match argument.operator() {
+                    Operator::Tuple => argument.children(),
+                    _ => std::slice::from_ref(argument),
+                };
+                let arguments = arguments
+                    .iter()
+                    .map(|argument| self.node(argument))
+                    .collect::<Result<Vec<_>>>()?;
+                compile_function(identifier, arguments)
+            }
+            other => Err(anyhow!("operator '{}' is not supported", other)),
+        }
+    }
+
+    /// Evaluates a node without columns with evalexpr.
+    fn constant(&self, node: &Node) -> Result<(Expr, Kind)> {
+        if let Some(identifier) =
+            variables(node).find(|identifier| !self.constants.contains_key(*identifier))
+        {
+            return Err(anyhow!("undefined identifier '{}'", identifier));
+        }
+        match node.eval_with_context(&self.context)? {
+            Value::Float(v) => Ok((Expr::Constant(v), Kind::Float)),
+            Value::Int(v) => Ok((Expr::Constant(v as f64), Kind::Int)),
+            other => Err(anyhow!("constant {} is not a number", other)),
+        }
+    }
+}
+
+/// Returns the variable identifiers read by a node and its descendants.
+fn variables(node: &Node) -> impl Iterator<Item = &str> {
+    let own = match node.operator() {
+        Operator::VariableIdentifierRead { identifier } => Some(identifier.as_str()),
+        _ => None,
+    };
+    // `iter_variable_identifiers` visits the descendants only.
+    own.into_iter().chain(node.iter_variable_identifiers())
+}
+
+/// Compiles `+`, `-` or `*` of a node with columns, which is a float operation unless both
+/// operands may be integers.
+fn arithmetic(f: fn(f64, f64) -> f64, a: (Expr, Kind), b: (Expr, Kind)) -> Result<(Expr, Kind)> {
+    let kind = match (a.1, b.1) {
+        (Kind::Float, _) | (_, Kind::Float) => Kind::Float,
+        _ => Kind::Either,
+    };
+    Ok((Expr::binary(f, a.0, b.0), kind))
+}
+
+/// Compiles `/` or `%` of a node with columns, rejecting operands that may both be integers.
+fn division(
+    symbol: &str,
+    f: fn(f64, f64) -> f64,
+    a: (Expr, Kind),
+    b: (Expr, Kind),
+) -> Result<(Expr, Kind)> {
+    if a.1 != Kind::Float && b.1 != Kind::Float {
+        return Err(anyhow!(
+            "operator '{}' of min or max of channels and integers is integer or float \
+             division depending on the values, write the integers as floats, e.g. 1.0",
+            symbol
+        ));
+    }
+    Ok((Expr::binary(f, a.0, b.0), Kind::Float))
+}
+
+/// Compiles a call of a supported builtin function.
+fn compile_function(identifier: &str, arguments: Vec<(Expr, Kind)>) -> Result<(Expr, Kind)> {
+    let unary: Option<fn(f64) -> f64> = match identifier {
+        "math::sqrt" => Some(f64::sqrt),
+        "math::cbrt" => Some(f64::cbrt),
+        "math::abs" => Some(f64::abs),
+        "math::exp" => Some(f64::exp),
+        "math::exp2" => Some(f64::exp2),
+        "math::ln" => Some(f64::ln),
+        "math::log2" => Some(f64::log2),
+        "math::log10" => Some(f64::log10),
+        "math::sin" => Some(f64::sin),
+        "math::cos" => Some(f64::cos),
+        "math::tan" => Some(f64::tan),
+        "math::asin" => Some(f64::asin),
+        "math::acos" => Some(f64::acos),
+        "math::atan" => Some(f64::atan),
+        "math::sinh" => Some(f64::sinh),
+        "math::cosh" => Some(f64::cosh),
+        "math::tanh" => Some(f64::tanh),
+        "math::asinh" => Some(f64::asinh),
+        "math::acosh" => Some(f64::acosh),
+        "math::atanh" => Some(f64::atanh),
+        "floor" => Some(f64::floor),
+        "round" => Some(f64::round),
+        "ceil" => Some(f64::ceil),
+        _ => None,
+    };
+    let binary: Option<fn(f64, f64) -> f64> = match identifier {
+        "math::log" => Some(f64::log),
+        "math::pow" => Some(f64::powf),
+        "math::hypot" => Some(f64::hypot),
+        "math::atan2" => Some(f64::atan2),
+        _ => None,
+    };
+    if let Some(f) = unary {
+        let [(a, kind)]: [(Expr, Kind); 1] =
+            arguments
+                .try_into()
+                .map_err(|arguments: Vec<(Expr, Kind)>| {
+                    anyhow!("{} takes 1 argument, got {}", identifier, arguments.len())
+                })?;
+        // evalexpr keeps integers integral only through `math::abs`.
+        let kind = if identifier == "math::abs" {
+            kind
+        } else {
+            Kind::Float
+        };
+        return Ok((Expr::unary(f, a), kind));
+    }
+    if let Some(f) = binary {
+        let [(a, _), (b, _)]: [(Expr, Kind); 2] =
+            arguments
+                .try_into()
+                .map_err(|arguments: Vec<(Expr, Kind)>| {
+                    anyhow!("{} takes 2 arguments, got {}", identifier, arguments.len())
+                })?;
+        return Ok((Expr::binary(f, a, b), Kind::Float));
+    }
+    let f: fn(f64, f64) -> f64 = match identifier {
+        "min" => f64::min,
+        "max" => f64::max,
+        _ => return Err(anyhow!("function '{}' is not supported", identifier)),
+    };
+    // evalexpr returns an integer argument if it is the extreme, else a float.
+    let kind = if arguments.iter().all(|(_, kind)| *kind == Kind::Float) {
+        Kind::Float
+    } else {
+        Kind::Either
+    };
+    let mut arguments = arguments.into_iter().map(|(expr, _)| expr);
+    let first = arguments
+        .next()
+        .ok_or_else(|| anyhow!("{} needs at least one argument", identifier))?;
+    Ok((
+        arguments.fold(first, |acc, a| Expr::binary(f, acc, a)),
+        kind,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+    use evalexpr::{eval_number_with_context, ContextWithMutableVariables, HashMapContext};
+
+    /// Asserts that two columns of values are equal up to rounding.
+    fn assert_values(actual: &[f64], expected: &[f64]) {
+        assert_eq!(actual.len(), expected.len());
+        for (actual, expected) in actual.iter().zip(expected) {
+            assert_relative_eq!(*actual, *expected, max_relative = 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_matches_evalexpr() {
+        // Both below and above the parallel threshold.
+        let rows = PARALLEL_ROWS + 1000;
+        let fx: Vec<f64> = (0..rows).map(|i| (i as f64 * 0.01).sin() * 50.0).collect();
+        let fy: Vec<f64> = (0..rows).map(|i| (i as f64 * 0.013).cos() * 20.0).collect();
+        let constants = HashMap::from([
+            ("a".to_string(), Value::Float(5.0)),
+            ("b".to_string(), Value::Float(3.0)),
+            ("n".to_string(), Value::Int(7)),
+        ]);
+        let expressions = [
+            "math::sqrt(Fx^2 + Fy^2)",
+            "Fx * math::cos(a) - Fy * math::sin(a) + b",
+            "-Fx / (b + 1) % 7",
+            "max(Fx, Fy, a) - min(Fy, 0)",
+            "math::atan2(Fy, Fx) + math::log(math::abs(Fx) + 1, 10)",
+            "floor(Fx) + round(Fy) * ceil(a / b)",
+            "math::hypot(Fx, 2) * math::exp(-math::abs(Fy) / 100)",
+            // Integer arithmetic without channels.
+            "1 / 2 + Fx * (n / 2) - n % 4",
+            "Fx / 2 + math::abs(-n) / 2 ^ 2",
+            "max(Fx, 1) * 2 - min(Fy, n, 0.5)",
+        ];
+        for expression in expressions {
+            let compiled =
+                CompiledExpression::compile(expression, &["Fx", "Fy"], &constants).unwrap();
+            let short = compiled.evaluate(&[&fx[..1000], &fy[..1000]]).unwrap();
+            let values = compiled.evaluate(&[&fx, &fy]).unwrap();
+            assert_values(&short, &values[..1000]);
+            let mut context = HashMapContext::new();
+            for (key, value) in &constants {
+                context.set_value(key.clone(), value.clone()).unwrap();
+            }
+            for row in (0..fx.len()).step_by(97) {
+                context
+                    .set_value("Fx".into(), Value::Float(fx[row]))
+                    .unwrap();
+                context
+                    .set_value("Fy".into(), Value::Float(fy[row]))
+                    .unwrap();
+                let expected = eval_number_with_context(expression, &context).unwrap();
+                assert_relative_eq!(values[row], expected, max_relative = 1e-12);
+            }
+        }
+    }
+
+    #[test]
+    fn test_constant_folding() {
+        let constants = HashMap::from([("a".to_string(), Value::Float(4.0))]);
+        let compiled =
+            CompiledExpression::compile("math::sqrt(a) * 3 / 2", &[], &constants).unwrap();
+        assert_relative_eq!(compiled.constant().unwrap(), 3.0);
+        assert!(compiled.evaluate(&[]).unwrap().is_empty());
+        let compiled = CompiledExpression::compile("a + 1", &["Fx"], &constants).unwrap();
+        assert_values(&compiled.evaluate(&[&[0.0, 1.0]]).unwrap(), &[5.0, 5.0]);
+        let compiled = CompiledExpression::compile("7 / 2 + 1 / 2.0", &[], &constants).unwrap();
+        assert_relative_eq!(compiled.constant().unwrap(), 3.5);
+    }
+
+    #[test]
+    fn test_value_dependent_division() {
+        let constants = HashMap::new();
+        for expression in ["min(Fx, 1) / 2", "-max(Fx, 1) % 2", "2 / (1 + min(1, Fx))"] {
+            let err = CompiledExpression::compile(expression, &["Fx"], &constants).unwrap_err();
+            assert!(
+                format!("{err:#}").contains("integer or float division"),
+                "{expression}"
+            );
+        }
+        let compiled =
+            CompiledExpression::compile("min(Fx, 1.0) / 2", &["Fx"], &constants).unwrap();
+        assert_values(&compiled.evaluate(&[&[3.0, 0.5]]).unwrap(), &[0.5, 0.25]);
+        let compiled =
+            CompiledExpression::compile("min(Fx, 1) / 2.0", &["Fx"], &constants).unwrap();
+        assert_values(&compiled.evaluate(&[&[3.0, 0.5]]).unwrap(), &[0.5, 0.25]);
+    }
+
+    #[test]
+    fn test_compile_errors() {
+        let constants = HashMap::new();
+        let message = |expression: &str| {
+            let err = CompiledExpression::compile(expression, &["Fx"], &constants).unwrap_err();
+            format!("{err:#}")
+        };
+        assert!(message("Fx + Fy").contains("undefined identifier 'Fy'"));
+        assert!(message("Fx > 1").contains("operator '>' is not supported"));
+        assert!(message("math::sinc(Fx)").contains("function 'math::sinc' is not supported"));
+        assert!(message("math::atan2(Fx)").contains("math::atan2 takes 2 arguments, got 1"));
+        assert!(message("Fx +").contains("operator '+' is missing an operand"));
+        assert!(message("(Fx").contains("Failed to parse"));
+
+        let compiled = CompiledExpression::compile("Fx", &["Fx"], &constants).unwrap();
+        let err = compiled.evaluate(&[&[1.0], &[2.0]]).unwrap_err();
+        assert!(format!("{err:#}").contains("expected 1 columns, got 2"));
+        let compiled = CompiledExpression::compile("Fx + Fy", &["Fx", "Fy"], &constants).unwrap();
+        let err = compiled.evaluate(&[&[1.0], &[2.0, 3.0]]).unwrap_err();
+        assert!(format!("{err:#}").contains("column 1 has 2 rows, expected 1"));
+    }
+}
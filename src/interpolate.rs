@@ -1,15 +1,25 @@
+use anyhow::{anyhow, Result};
 use nalgebra::{DMatrix, DVector};
 use rayon::prelude::*;
 use serde::Deserialize;
-use std::collections::HashMap;
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap};
 use std::hash::{Hash, Hasher};
+use std::sync::OnceLock;
 
 const TOLERANCE: f64 = 1e-5;
+/// The largest condition number of an RBF system that is solved; beyond it, rounding errors
+/// of the coefficients reach about 1e-6 of the values.
+const MAX_RBF_CONDITION: f64 = 1e10;
 
 /// An N-dimensional interpolation input point.
 ///
 /// `coordinates` participate in interpolation; `file` is optional metadata used
 /// by the CLI pipeline to locate the stress-tensor file backing this point.
+///
+/// `Eq` and `Hash` compare the coordinates rounded to multiples of 1e-5, so they agree and
+/// points can key a `HashMap`. Two points within 1e-5 of each other can still round apart;
+/// `duplicate_groups` finds points by distance instead.
 #[derive(Debug, Deserialize, Clone)]
 pub struct Point {
     pub file: Option<String>,
@@ -20,204 +30,2091 @@ impl Point {
     pub fn new(file: Option<String>, coordinates: Vec<f64>) -> Self {
         Point { file, coordinates }
     }
+
+    /// The coordinates rounded to multiples of `TOLERANCE`, which identify the point.
+    fn key(&self) -> impl Iterator<Item = u64> + '_ {
+        // Adding 0.0 turns a rounded -0.0 into 0.0.
+        self.coordinates
+            .iter()
+            .map(|c| ((c / TOLERANCE).round() + 0.0).to_bits())
+    }
+
+    /// Returns the file of the point, or its coordinates if it has none.
+    fn describe(&self) -> String {
+        match &self.file {
+            Some(file) => file.clone(),
+            None => format!("{:?}", self.coordinates),
+        }
+    }
 }
 
 impl Eq for Point {}
 
-impl PartialEq for Point {
-    fn eq(&self, other: &Self) -> bool {
-        self.coordinates.len() == other.coordinates.len()
-            && self
-                .coordinates
-                .iter()
-                .zip(other.coordinates.iter())
-                .all(|(a, b)| (a - b).abs() <= TOLERANCE)
+impl PartialEq for Point {
+    fn eq(&self, other: &Self) -> bool {
+        self.coordinates.len() == other.coordinates.len() && self.key().eq(other.key())
+    }
+}
+
+impl Hash for Point {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.coordinates.len().hash(state);
+        for bits in self.key() {
+            bits.hash(state);
+        }
+    }
+}
+
+/// What happens to points that share coordinates, see `duplicate_groups`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DuplicatePoints {
+    /// Points sharing coordinates are an error.
+    Error,
+    /// The values of the first of the points are kept.
+    First,
+    /// The values of the last of the points are kept.
+    Last,
+    /// The values of the points are averaged.
+    Average,
+}
+
+/// Returns for every point the index of the first point it shares coordinates with, i.e.
+/// lies within a distance of 1e-5 of, directly or through other points; its own index if it
+/// shares them with no earlier point.
+pub fn duplicate_groups(points: &[Point]) -> Vec<usize> {
+    let tree = KdTree::new(points.iter().map(|p| p.coordinates.clone()).collect());
+    let mut groups: Vec<usize> = (0..points.len()).collect();
+    for (index, point) in points.iter().enumerate() {
+        let first = tree
+            .within(&point.coordinates, TOLERANCE)
+            .into_iter()
+            .filter(|(other, _)| *other < index)
+            .map(|(other, _)| groups[other])
+            .min();
+        if let Some(first) = first {
+            groups[index] = first;
+        }
+    }
+    groups
+}
+
+/// Points whose values were merged by `merge_duplicates`.
+#[derive(Debug, Clone)]
+pub struct MergedPoints {
+    /// The first point of every group of points sharing coordinates.
+    pub points: Vec<Point>,
+    /// The merged values, one row per point.
+    pub values: DMatrix<f64>,
+    /// The pairs of the first and each other point of a group.
+    pub duplicates: Vec<(Point, Point)>,
+}
+
+/// Merges the values of points that share coordinates, see `duplicate_groups`.
+///
+/// # Returns
+///
+/// Returns an error for `DuplicatePoints::Error` if any points share coordinates.
+pub fn merge_duplicates(
+    points: &[Point],
+    values: &DMatrix<f64>,
+    duplicates: DuplicatePoints,
+) -> Result<MergedPoints> {
+    let groups = duplicate_groups(points);
+    let firsts: Vec<usize> = (0..points.len()).filter(|i| groups[*i] == *i).collect();
+    let pairs: Vec<(Point, Point)> = (0..points.len())
+        .filter(|i| groups[*i] != *i)
+        .map(|i| (points[groups[i]].clone(), points[i].clone()))
+        .collect();
+    if let (DuplicatePoints::Error, Some((first, other))) = (duplicates, pairs.first()) {
+        return Err(anyhow!(
+            "points {} and {} share coordinates {:?}",
+            first.describe(),
+            other.describe(),
+            other.coordinates
+        ));
+    }
+    let mut merged = DMatrix::zeros(firsts.len(), values.ncols());
+    for (row, &first) in firsts.iter().enumerate() {
+        let members: Vec<usize> = (0..points.len()).filter(|i| groups[*i] == first).collect();
+        let rows = match duplicates {
+            DuplicatePoints::Last => &members[members.len() - 1..],
+            DuplicatePoints::Average => &members[..],
+            _ => &members[..1],
+        };
+        for &member in rows {
+            let mut target = merged.row_mut(row);
+            target += values.row(member) / rows.len() as f64;
+        }
+    }
+    Ok(MergedPoints {
+        points: firsts.iter().map(|i| points[*i].clone()).collect(),
+        values: merged,
+        duplicates: pairs,
+    })
+}
+
+/// What happens to targets outside the `BoundingBox` of the points. Piecewise strategies take
+/// the same policy on their hull as an `Extrapolation` instead.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outside {
+    /// Targets are passed on unchanged.
+    Allow,
+    /// Targets are clamped to the bounding box.
+    Clamp,
+    /// A target outside is an error.
+    Error,
+    /// Targets are passed on unchanged and counted for a warning.
+    Warn,
+}
+
+/// The smallest axis-aligned box containing the points.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BoundingBox {
+    /// The smallest coordinate along each axis.
+    pub min: Vec<f64>,
+    /// The largest coordinate along each axis.
+    pub max: Vec<f64>,
+}
+
+impl BoundingBox {
+    /// Returns the bounding box of the points, or an error if there are none or their
+    /// dimensions differ.
+    pub fn new(points: &[Point]) -> Result<Self> {
+        if points.is_empty() {
+            return Err(anyhow!("No points available for interpolation."));
+        }
+        let dimension = dimension(points)?;
+        let mut min = vec![f64::INFINITY; dimension];
+        let mut max = vec![f64::NEG_INFINITY; dimension];
+        for point in points {
+            for (axis, coordinate) in point.coordinates.iter().enumerate() {
+                min[axis] = min[axis].min(*coordinate);
+                max[axis] = max[axis].max(*coordinate);
+            }
+        }
+        Ok(BoundingBox { min, max })
+    }
+
+    /// Returns whether the target lies in the box, to within 1e-5.
+    pub fn contains(&self, target: &[f64]) -> bool {
+        target
+            .iter()
+            .zip(self.min.iter().zip(&self.max))
+            .all(|(c, (min, max))| *c >= min - TOLERANCE && *c <= max + TOLERANCE)
+    }
+
+    /// Moves the targets outside the box as `outside` asks and returns how many were outside.
+    ///
+    /// # Returns
+    ///
+    /// Returns an error for `Outside::Error` if a target is outside the box.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fatigue::interpolate::{BoundingBox, Outside, Point};
+    ///
+    /// let points = [Point::new(None, vec![0.0, 0.0]), Point::new(None, vec![1.0, 2.0])];
+    /// let bounds = BoundingBox::new(&points).unwrap();
+    /// let mut targets = vec![vec![0.5, 1.0], vec![1.5, -1.0]];
+    /// assert_eq!(bounds.restrict(&mut targets, Outside::Clamp).unwrap(), 1);
+    /// assert_eq!(targets[1], vec![1.0, 0.0]);
+    /// assert!(bounds.restrict(&mut vec![vec![3.0, 0.0]], Outside::Error).is_err());
+    /// ```
+    pub fn restrict(&self, targets: &mut [Vec<f64>], outside: Outside) -> Result<usize> {
+        let mut count = 0;
+        for (index, target) in targets.iter_mut().enumerate() {
+            if self.contains(target) {
+                continue;
+            }
+            count += 1;
+            match outside {
+                Outside::Error => {
+                    return Err(anyhow!(
+                        "target {} at {:?} is outside the bounding box of the points, {:?} to {:?}",
+                        index,
+                        target,
+                        self.min,
+                        self.max
+                    ))
+                }
+                Outside::Clamp => {
+                    for (c, (min, max)) in target.iter_mut().zip(self.min.iter().zip(&self.max)) {
+                        *c = c.clamp(*min, *max);
+                    }
+                }
+                Outside::Allow | Outside::Warn => (),
+            }
+        }
+        Ok(count)
+    }
+}
+
+/// Returns the dimension of the affine hull of the points, e.g. 1 for points on a line.
+pub fn affine_span(points: &[Point]) -> usize {
+    let Some(first) = points.first() else {
+        return 0;
+    };
+    let dimension = first.coordinates.len();
+    let centered = DMatrix::from_fn(points.len(), dimension, |row, column| {
+        points[row]
+            .coordinates
+            .get(column)
+            .copied()
+            .unwrap_or_default()
+            - first.coordinates[column]
+    });
+    let singular_values = centered.singular_values();
+    let largest = singular_values.max();
+    singular_values
+        .iter()
+        .filter(|s| **s > largest * 1e-10 && **s > 0.0)
+        .count()
+}
+
+/// An interpolation strategy over N-dimensional points carrying scalar or vector values.
+///
+/// Interpolation is split into `prepare`, which does the work that depends on the points
+/// only (e.g. factorizing a regression) once, and cheap evaluations of the prepared
+/// weights at the targets. The weights are applied to every component of the values, so
+/// the stress components of all nodes and every time step share one preparation.
+///
+/// # Examples
+///
+/// ```
+/// use fatigue::interpolate::{InterpolationStrategy, Linear, Point};
+/// use nalgebra::{DMatrix, DVector};
+///
+/// let points = vec![
+///     Point::new(None, vec![0.0]),
+///     Point::new(None, vec![1.0]),
+/// ];
+/// // Two components per point: 2x and 10 - x.
+/// let values = DMatrix::from_row_slice(2, 2, &[0.0, 10.0, 2.0, 9.0]);
+/// let result = Linear.interpolate_values(&points, &values, &[vec![4.0]]).unwrap();
+/// assert!((result[(0, 0)] - 8.0).abs() < 1e-9 && (result[(0, 1)] - 6.0).abs() < 1e-9);
+///
+/// let prepared = Linear.prepare(&points).unwrap();
+/// let weights = prepared.weights(&[vec![0.25]]).unwrap();
+/// assert!((weights[(0, 0)] - 0.75).abs() < 1e-9 && (weights[(0, 1)] - 0.25).abs() < 1e-9);
+/// ```
+pub trait InterpolationStrategy {
+    /// Prepares the interpolation over the points.
+    ///
+    /// # Returns
+    ///
+    /// Returns the prepared weights, or an error if the points are degenerate for the
+    /// strategy or differ in dimension.
+    fn prepare(&self, points: &[Point]) -> Result<Box<dyn PreparedInterpolation>>;
+
+    /// Interpolates the values of the points at every target.
+    ///
+    /// `values` holds one row per point and one column per component; the result holds one
+    /// row per target and one column per component.
+    fn interpolate_values(
+        &self,
+        points: &[Point],
+        values: &DMatrix<f64>,
+        target: &[Vec<f64>],
+    ) -> Result<DMatrix<f64>> {
+        if values.nrows() != points.len() {
+            return Err(anyhow!(
+                "expected one row of values per point, got {} rows for {} points",
+                values.nrows(),
+                points.len()
+            ));
+        }
+        self.prepare(points)?.evaluate(values, target)
+    }
+
+    /// Interpolates the scalar values of the points at every target.
+    fn interpolate(&self, points: &HashMap<Point, f64>, target: &[Vec<f64>]) -> Result<Vec<f64>> {
+        let (points, values): (Vec<Point>, Vec<f64>) = points
+            .iter()
+            .map(|(point, value)| (point.clone(), *value))
+            .unzip();
+        let values = DMatrix::from_column_slice(values.len(), 1, &values);
+        let interpolated = self.interpolate_values(&points, &values, target)?;
+        Ok(interpolated.column(0).iter().copied().collect())
+    }
+
+    /// Returns how well the interpolation reproduces the values at the points.
+    ///
+    /// The leave-one-out errors prepare the strategy once per point, so they are meant for
+    /// tables of unit cases rather than large lookup tables.
+    ///
+    /// # Returns
+    ///
+    /// Returns an error if the strategy cannot be prepared over all points.
+    fn diagnostics(&self, points: &[Point], values: &DMatrix<f64>) -> Result<Diagnostics> {
+        let prepared = self.prepare(points)?;
+        let targets: Vec<Vec<f64>> = points.iter().map(|p| p.coordinates.clone()).collect();
+        let residuals = prepared.evaluate(values, &targets)? - values;
+        let leave_one_out = (0..points.len())
+            .map(|left_out| {
+                let others: Vec<Point> = points
+                    .iter()
+                    .enumerate()
+                    .filter(|(index, _)| *index != left_out)
+                    .map(|(_, point)| point.clone())
+                    .collect();
+                let rows: Vec<usize> = (0..points.len()).filter(|r| *r != left_out).collect();
+                let interpolated = self
+                    .prepare(&others)
+                    .and_then(|p| {
+                        p.evaluate(&values.select_rows(&rows), &targets[left_out..=left_out])
+                    })
+                    .ok()?;
+                Some(
+                    (interpolated.row(0) - values.row(left_out))
+                        .iter()
+                        .copied()
+                        .collect(),
+                )
+            })
+            .collect();
+        let groups = duplicate_groups(points);
+        let duplicates = (0..points.len())
+            .filter(|index| groups[*index] != *index)
+            .map(|index| (points[groups[index]].clone(), points[index].clone()))
+            .collect();
+        Ok(Diagnostics {
+            residuals,
+            leave_one_out,
+            conditioning: prepared.conditioning(),
+            duplicates,
+        })
+    }
+}
+
+/// The quality of an interpolation at its own points, see `InterpolationStrategy::diagnostics`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostics {
+    /// The interpolated minus the given values at every point, one row per point.
+    pub residuals: DMatrix<f64>,
+    /// The values at every point interpolated from all other points minus the given values,
+    /// or `None` where the strategy cannot be prepared or evaluated without the point.
+    pub leave_one_out: Vec<Option<Vec<f64>>>,
+    /// The conditioning of the strategy's linear system, if it solves one.
+    pub conditioning: Option<Conditioning>,
+    /// Pairs of points sharing coordinates, the first of their group first, see
+    /// `duplicate_groups`.
+    pub duplicates: Vec<(Point, Point)>,
+}
+
+impl Diagnostics {
+    /// Returns the largest absolute residual.
+    pub fn max_residual(&self) -> f64 {
+        self.residuals.iter().fold(0.0, |max, r| max.max(r.abs()))
+    }
+
+    /// Returns the largest absolute leave-one-out error, or `None` if no point could be left
+    /// out.
+    pub fn max_leave_one_out(&self) -> Option<f64> {
+        self.leave_one_out
+            .iter()
+            .flatten()
+            .map(|errors| errors.iter().fold(0.0, |max: f64, e| max.max(e.abs())))
+            .reduce(f64::max)
+    }
+}
+
+/// The rank and condition number of the linear system of a prepared interpolation.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Conditioning {
+    /// The number of singular values above the solver's tolerance.
+    pub rank: usize,
+    /// The ratio of the largest to the smallest singular value, infinite if singular.
+    pub condition: f64,
+    /// The number of columns of the system, i.e. its rank if it is not deficient.
+    pub columns: usize,
+}
+
+impl Conditioning {
+    fn new(singular_values: &DVector<f64>, tolerance: f64, columns: usize) -> Self {
+        let largest = singular_values.max();
+        let smallest = singular_values.min();
+        Conditioning {
+            rank: singular_values.iter().filter(|s| **s > tolerance).count(),
+            condition: if smallest > 0.0 {
+                largest / smallest
+            } else {
+                f64::INFINITY
+            },
+            columns,
+        }
+    }
+}
+
+/// The interpolation weights of prepared points, see `InterpolationStrategy::prepare`.
+pub trait PreparedInterpolation: Send + Sync {
+    /// Returns the weights of the points at every target, one row per target and one column
+    /// per point in the order they were prepared.
+    fn weights(&self, target: &[Vec<f64>]) -> Result<DMatrix<f64>>;
+
+    /// Interpolates values, one row per point and one column per component, at every target.
+    fn evaluate(&self, values: &DMatrix<f64>, target: &[Vec<f64>]) -> Result<DMatrix<f64>> {
+        let weights = self.weights(target)?;
+        if weights.ncols() != values.nrows() {
+            return Err(anyhow!(
+                "expected one row of values per point, got {} rows for {} points",
+                values.nrows(),
+                weights.ncols()
+            ));
+        }
+        Ok(weights * values)
+    }
+
+    /// Returns the conditioning of the linear system solved by `prepare`, if any.
+    fn conditioning(&self) -> Option<Conditioning> {
+        None
+    }
+}
+
+/// Returns the dimension of the points, or an error if they differ in dimension.
+fn dimension(points: &[Point]) -> Result<usize> {
+    let dimension = points.first().map_or(0, |point| point.coordinates.len());
+    if let Some(point) = points.iter().find(|p| p.coordinates.len() != dimension) {
+        return Err(anyhow!(
+            "points must have {} coordinates, got {:?}",
+            dimension,
+            point.coordinates
+        ));
+    }
+    Ok(dimension)
+}
+
+/// A k-d tree over the coordinates of points for nearest and k-nearest queries.
+///
+/// Distances are Euclidean over the coordinates a query shares with the points; equally
+/// distant points are ordered by index, so queries agree with a linear scan.
+///
+/// # Examples
+///
+/// ```
+/// use fatigue::interpolate::KdTree;
+///
+/// let tree = KdTree::new(vec![vec![0.0, 0.0], vec![1.0, 0.0], vec![0.0, 2.0]]);
+/// assert_eq!(tree.nearest(&[0.9, 0.2]).map(|(index, _)| index), Some(1));
+/// let nearest: Vec<usize> = tree.k_nearest(&[0.0, 1.5], 2).iter().map(|n| n.0).collect();
+/// assert_eq!(nearest, vec![2, 0]);
+/// ```
+pub struct KdTree {
+    coordinates: Vec<Vec<f64>>,
+    nodes: Vec<KdNode>,
+    root: Option<usize>,
+}
+
+/// A node of a `KdTree`: a point splitting its subtree along an axis.
+struct KdNode {
+    point: usize,
+    axis: usize,
+    /// The subtrees with coordinates below and above the point along the axis.
+    children: [Option<usize>; 2],
+}
+
+/// A candidate of a k-nearest query, ordered by squared distance and then index.
+#[derive(PartialEq)]
+struct Neighbor {
+    distance2: f64,
+    index: usize,
+}
+
+impl Eq for Neighbor {}
+
+impl PartialOrd for Neighbor {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Neighbor {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.distance2
+            .total_cmp(&other.distance2)
+            .then(self.index.cmp(&other.index))
+    }
+}
+
+impl KdTree {
+    /// Builds the tree of the coordinates, splitting at the median of alternating axes.
+    pub fn new(coordinates: Vec<Vec<f64>>) -> Self {
+        let dimension = coordinates.first().map_or(0, Vec::len).max(1);
+        let mut tree = KdTree {
+            coordinates,
+            nodes: Vec::new(),
+            root: None,
+        };
+        let mut indices: Vec<usize> = (0..tree.coordinates.len()).collect();
+        tree.root = tree.build(&mut indices, 0, dimension);
+        tree
+    }
+
+    fn build(&mut self, indices: &mut [usize], depth: usize, dimension: usize) -> Option<usize> {
+        if indices.is_empty() {
+            return None;
+        }
+        let axis = depth % dimension;
+        let median = indices.len() / 2;
+        let coordinates = &self.coordinates;
+        let key = |index: &usize| coordinates[*index].get(axis).copied().unwrap_or_default();
+        indices.select_nth_unstable_by(median, |a, b| key(a).total_cmp(&key(b)));
+        let point = indices[median];
+        let (below, above) = indices.split_at_mut(median);
+        let children = [
+            self.build(below, depth + 1, dimension),
+            self.build(&mut above[1..], depth + 1, dimension),
+        ];
+        self.nodes.push(KdNode {
+            point,
+            axis,
+            children,
+        });
+        Some(self.nodes.len() - 1)
+    }
+
+    /// Returns the number of points in the tree.
+    pub fn len(&self) -> usize {
+        self.coordinates.len()
+    }
+
+    /// Returns whether the tree has no points.
+    pub fn is_empty(&self) -> bool {
+        self.coordinates.is_empty()
+    }
+
+    /// Returns the index and distance of the point nearest to the target, or `None` if the
+    /// tree is empty.
+    pub fn nearest(&self, target: &[f64]) -> Option<(usize, f64)> {
+        self.k_nearest(target, 1).into_iter().next()
+    }
+
+    /// Returns the indices and distances of the `k` points nearest to the target, nearest
+    /// first.
+    pub fn k_nearest(&self, target: &[f64], k: usize) -> Vec<(usize, f64)> {
+        let mut heap = BinaryHeap::with_capacity(k + 1);
+        if k > 0 {
+            self.search(self.root, target, k, &mut heap);
+        }
+        heap.into_sorted_vec()
+            .into_iter()
+            .map(|n| (n.index, n.distance2.sqrt()))
+            .collect()
+    }
+
+    /// Returns the indices and distances of the points within `radius` of the target,
+    /// nearest first.
+    pub fn within(&self, target: &[f64], radius: f64) -> Vec<(usize, f64)> {
+        let mut found = Vec::new();
+        self.search_within(self.root, target, radius * radius, &mut found);
+        found.sort();
+        found
+            .into_iter()
+            .map(|n| (n.index, n.distance2.sqrt()))
+            .collect()
+    }
+
+    fn search_within(
+        &self,
+        node: Option<usize>,
+        target: &[f64],
+        radius2: f64,
+        found: &mut Vec<Neighbor>,
+    ) {
+        let Some(node) = node.map(|node| &self.nodes[node]) else {
+            return;
+        };
+        let point = &self.coordinates[node.point];
+        let distance2: f64 = point.iter().zip(target).map(|(a, b)| (a - b).powi(2)).sum();
+        if distance2 <= radius2 {
+            found.push(Neighbor {
+                distance2,
+                index: node.point,
+            });
+        }
+        let (Some(split), Some(value)) = (point.get(node.axis), target.get(node.axis)) else {
+            self.search_within(node.children[0], target, radius2, found);
+            self.search_within(node.children[1], target, radius2, found);
+            return;
+        };
+        let offset = value - split;
+        if offset <= 0.0 || offset * offset <= radius2 {
+            self.search_within(node.children[0], target, radius2, found);
+        }
+        if offset >= 0.0 || offset * offset <= radius2 {
+            self.search_within(node.children[1], target, radius2, found);
+        }
+    }
+
+    fn search(
+        &self,
+        node: Option<usize>,
+        target: &[f64],
+        k: usize,
+        heap: &mut BinaryHeap<Neighbor>,
+    ) {
+        let Some(node) = node.map(|node| &self.nodes[node]) else {
+            return;
+        };
+        let point = &self.coordinates[node.point];
+        let candidate = Neighbor {
+            distance2: point.iter().zip(target).map(|(a, b)| (a - b).powi(2)).sum(),
+            index: node.point,
+        };
+        if heap.len() < k {
+            heap.push(candidate);
+        } else if heap.peek().is_some_and(|worst| candidate < *worst) {
+            heap.pop();
+            heap.push(candidate);
+        }
+        // Without the coordinate of the axis, both subtrees may hold the nearest points.
+        let (Some(split), Some(value)) = (point.get(node.axis), target.get(node.axis)) else {
+            self.search(node.children[0], target, k, heap);
+            self.search(node.children[1], target, k, heap);
+            return;
+        };
+        let offset = value - split;
+        let (near, far) = if offset < 0.0 { (0, 1) } else { (1, 0) };
+        self.search(node.children[near], target, k, heap);
+        // Equal distances are searched too, so ties resolve to the lowest index.
+        if heap.len() < k
+            || heap
+                .peek()
+                .is_some_and(|worst| offset * offset <= worst.distance2)
+        {
+            self.search(node.children[far], target, k, heap);
+        }
+    }
+}
+
+/// Returns dense weights, one row per target, of sparse `(point, weight)` rows.
+fn dense_weights(rows: &[Vec<(usize, f64)>], points: usize) -> DMatrix<f64> {
+    let mut weights = DMatrix::zeros(rows.len(), points);
+    for (row, entries) in rows.iter().enumerate() {
+        for &(point, weight) in entries {
+            weights[(row, point)] += weight;
+        }
+    }
+    weights
+}
+
+/// Applies sparse `(point, weight)` rows to values with one row per point, without the
+/// dense weights of large point sets.
+fn sparse_evaluate(
+    rows: &[Vec<(usize, f64)>],
+    points: usize,
+    values: &DMatrix<f64>,
+) -> Result<DMatrix<f64>> {
+    if values.nrows() != points {
+        return Err(anyhow!(
+            "expected one row of values per point, got {} rows for {} points",
+            values.nrows(),
+            points
+        ));
+    }
+    let mut result = DMatrix::zeros(rows.len(), values.ncols());
+    for (row, entries) in rows.iter().enumerate() {
+        for &(point, weight) in entries {
+            for column in 0..values.ncols() {
+                result[(row, column)] += weight * values[(point, column)];
+            }
+        }
+    }
+    Ok(result)
+}
+
+// Implement nearest-neighbor interpolation
+#[derive(Debug)]
+pub struct NearestNeighbor;
+
+impl InterpolationStrategy for NearestNeighbor {
+    fn prepare(&self, points: &[Point]) -> Result<Box<dyn PreparedInterpolation>> {
+        if points.is_empty() {
+            return Err(anyhow!("No points available for interpolation."));
+        }
+        dimension(points)?;
+        let coordinates = points.iter().map(|p| p.coordinates.clone()).collect();
+        Ok(Box::new(NearestWeights {
+            tree: KdTree::new(coordinates),
+        }))
+    }
+}
+
+/// The prepared points of `NearestNeighbor`.
+struct NearestWeights {
+    tree: KdTree,
+}
+
+impl NearestWeights {
+    /// Returns the nearest point of every target, which takes the whole weight.
+    fn sparse_weights(&self, target: &[Vec<f64>]) -> Vec<Vec<(usize, f64)>> {
+        target
+            .par_iter()
+            .map(|target_vec| {
+                self.tree
+                    .nearest(target_vec)
+                    .map(|(index, _)| (index, 1.0))
+                    .into_iter()
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+impl PreparedInterpolation for NearestWeights {
+    fn weights(&self, target: &[Vec<f64>]) -> Result<DMatrix<f64>> {
+        Ok(dense_weights(&self.sparse_weights(target), self.tree.len()))
+    }
+
+    fn evaluate(&self, values: &DMatrix<f64>, target: &[Vec<f64>]) -> Result<DMatrix<f64>> {
+        sparse_evaluate(&self.sparse_weights(target), self.tree.len(), values)
+    }
+}
+
+// Implement linear interpolation
+#[derive(Debug)]
+pub struct Linear;
+
+impl InterpolationStrategy for Linear {
+    fn prepare(&self, points: &[Point]) -> Result<Box<dyn PreparedInterpolation>> {
+        if points.len() < 2 {
+            return Err(anyhow!("Not enough points for interpolation"));
+        }
+        let dimension = dimension(points)?;
+
+        // The design matrix with an intercept column
+        let x = DMatrix::from_fn(points.len(), dimension + 1, |row, column| match column {
+            0 => 1.0,
+            _ => points[row].coordinates[column - 1],
+        });
+
+        // The least-squares fit of any values is the pseudo-inverse applied to them, so the
+        // SVD is computed once for all components and targets.
+        let svd = x.svd(true, true);
+        let conditioning = Conditioning::new(&svd.singular_values, 1e-12, dimension + 1);
+        let pseudo_inverse = svd.pseudo_inverse(1e-12).map_err(|e| {
+            anyhow!(
+                "Failed to perform linear regression: Failed to solve the linear system using SVD: {}",
+                e
+            )
+        })?;
+        Ok(Box::new(LinearWeights {
+            dimension,
+            pseudo_inverse,
+            conditioning,
+        }))
+    }
+}
+
+/// The prepared regression of `Linear`.
+struct LinearWeights {
+    dimension: usize,
+    /// Maps values, one row per point, to the coefficients of the regression.
+    pseudo_inverse: DMatrix<f64>,
+    conditioning: Conditioning,
+}
+
+impl PreparedInterpolation for LinearWeights {
+    fn weights(&self, target: &[Vec<f64>]) -> Result<DMatrix<f64>> {
+        // Missing target coordinates count as 0, extra ones are ignored.
+        let targets = DMatrix::from_fn(
+            target.len(),
+            self.dimension + 1,
+            |row, column| match column {
+                0 => 1.0,
+                _ => target[row].get(column - 1).copied().unwrap_or_default(),
+            },
+        );
+        Ok(targets * &self.pseudo_inverse)
+    }
+
+    fn conditioning(&self) -> Option<Conditioning> {
+        Some(self.conditioning)
+    }
+}
+
+/// What piecewise interpolation does with targets outside the convex hull of the points.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Extrapolation {
+    /// Targets outside the hull are an error.
+    Error,
+    /// Targets outside the hull are moved to the nearest point of the hull, which is on a
+    /// boundary facet of the triangulation or, for `Grid`, the boundary of the grid.
+    Clamp,
+    /// Targets outside the hull extend the nearest simplex, or the boundary of the grid,
+    /// linearly.
+    Linear,
+}
+
+// Implement piecewise-linear interpolation over a Delaunay triangulation
+#[derive(Debug)]
+pub struct Delaunay {
+    pub extrapolation: Extrapolation,
+}
+
+impl InterpolationStrategy for Delaunay {
+    fn prepare(&self, points: &[Point]) -> Result<Box<dyn PreparedInterpolation>> {
+        let dimension = dimension(points)?;
+        if dimension == 0 {
+            return Err(anyhow!("points must have at least 1 coordinate"));
+        }
+        if points.len() < dimension + 1 {
+            return Err(anyhow!("Not enough points for interpolation"));
+        }
+        if let Some(index) = duplicate_groups(points)
+            .iter()
+            .enumerate()
+            .position(|(index, first)| index != *first)
+        {
+            return Err(anyhow!(
+                "point {:?} is defined twice",
+                points[index].coordinates
+            ));
+        }
+        let coordinates: Vec<DVector<f64>> = points
+            .iter()
+            .map(|p| DVector::from_column_slice(&p.coordinates))
+            .collect();
+        let simplices: Vec<Simplex> = triangulate(&coordinates)?
+            .into_iter()
+            .filter_map(|vertices| Simplex::new(&coordinates, vertices))
+            .collect();
+        // The simplices sharing each facet, with the position of the vertex opposite to it.
+        let mut shared: HashMap<Vec<usize>, Vec<(usize, usize)>> = HashMap::new();
+        for (position, simplex) in simplices.iter().enumerate() {
+            for (skip, (facet, _)) in facets(&simplex.vertices).enumerate() {
+                shared.entry(facet).or_default().push((position, skip));
+            }
+        }
+        let mut neighbors = vec![vec![None; dimension + 1]; simplices.len()];
+        let mut boundary = Vec::new();
+        for (facet, owners) in shared {
+            match owners[..] {
+                [(first, first_skip), (second, second_skip)] => {
+                    neighbors[first][first_skip] = Some(second);
+                    neighbors[second][second_skip] = Some(first);
+                }
+                _ => boundary.push(facet),
+            }
+        }
+        boundary.sort_unstable();
+        let mut incident = vec![0; points.len()];
+        for (position, simplex) in simplices.iter().enumerate().rev() {
+            for &vertex in simplex.vertices.iter() {
+                incident[vertex] = position;
+            }
+        }
+        let tree = KdTree::new(points.iter().map(|p| p.coordinates.clone()).collect());
+        Ok(Box::new(DelaunayWeights {
+            coordinates,
+            simplices,
+            neighbors,
+            incident,
+            tree,
+            boundary,
+            extrapolation: self.extrapolation,
+        }))
+    }
+}
+
+/// A simplex of the triangulation with the inverse of its edge matrix, which maps a target
+/// to its barycentric coordinates.
+struct Simplex {
+    vertices: Vec<usize>,
+    inverse: DMatrix<f64>,
+}
+
+impl Simplex {
+    /// Returns `None` for a simplex without volume, which covers no target.
+    fn new(coordinates: &[DVector<f64>], vertices: Vec<usize>) -> Option<Self> {
+        let origin = &coordinates[vertices[0]];
+        let edges = DMatrix::from_columns(
+            &vertices[1..]
+                .iter()
+                .map(|&v| &coordinates[v] - origin)
+                .collect::<Vec<_>>(),
+        );
+        let scale = edges.column_iter().map(|edge| edge.norm()).product::<f64>();
+        if edges.determinant().abs() <= 1e-12 * scale {
+            return None;
+        }
+        let inverse = edges.try_inverse()?;
+        Some(Simplex { vertices, inverse })
+    }
+
+    /// Returns the barycentric coordinates of the target, one per vertex.
+    fn barycentric(&self, coordinates: &[DVector<f64>], target: &DVector<f64>) -> Vec<f64> {
+        let local = &self.inverse * (target - &coordinates[self.vertices[0]]);
+        let mut weights = Vec::with_capacity(self.vertices.len());
+        weights.push(1.0 - local.sum());
+        weights.extend(local.iter());
+        weights
+    }
+}
+
+/// Returns the simplices of the Delaunay triangulation of the points, each as the sorted
+/// indices of its vertices, or an error if the points do not span their dimension.
+///
+/// Points are inserted one by one into the triangulation of an initial simplex of the points
+/// (Bowyer-Watson), whose hull facets are closed off by simplices through a symbolic vertex
+/// at infinity. The simplices in conflict with a new point, see `Conflict`, are replaced by
+/// connecting it to the boundary of their union. The simplices through the vertex at
+/// infinity are dropped at the end, so the others cover exactly the convex hull.
+fn triangulate(coordinates: &[DVector<f64>]) -> Result<Vec<Vec<usize>>> {
+    let dimension = coordinates[0].len();
+    let count = coordinates.len();
+    let centroid = coordinates.iter().sum::<DVector<f64>>() / count as f64;
+    let centered = DMatrix::from_columns(
+        &coordinates
+            .iter()
+            .map(|c| c - &centroid)
+            .collect::<Vec<_>>(),
+    );
+    let singular_values = centered.singular_values();
+    let largest = singular_values.max();
+    if largest <= 0.0 || singular_values.min() <= largest * 1e-10 {
+        return Err(anyhow!(
+            "points must span {} dimensions for Delaunay interpolation",
+            dimension
+        ));
+    }
+
+    // Triangulate centered coordinates scaled to a unit radius, so the tolerances of the
+    // conflict tests are independent of the units of the points.
+    let radius = coordinates
+        .iter()
+        .map(|c| (c - &centroid).norm())
+        .fold(0.0, f64::max);
+    let vertices: Vec<DVector<f64>> = coordinates
+        .iter()
+        .map(|c| (c - &centroid) / radius)
+        .collect();
+    // The initial simplex: the first point, then repeatedly the point farthest from the
+    // span of the edges chosen so far.
+    let mut initial = vec![0];
+    let mut basis: Vec<DVector<f64>> = Vec::new();
+    while initial.len() <= dimension {
+        let residual = |index: usize| {
+            let mut edge = &vertices[index] - &vertices[0];
+            for axis in basis.iter() {
+                edge -= axis * edge.dot(axis);
+            }
+            edge
+        };
+        let (farthest, edge) = (0..count)
+            .map(|index| (index, residual(index)))
+            .max_by(|a, b| a.1.norm().total_cmp(&b.1.norm()))
+            .ok_or_else(|| anyhow!("no points to triangulate"))?;
+        basis.push(edge.normalize());
+        initial.push(farthest);
+    }
+    // A point strictly inside the hull, which fixes the outer side of the hull facets.
+    let interior =
+        initial.iter().map(|&v| &vertices[v]).sum::<DVector<f64>>() / (dimension + 1) as f64;
+    let infinite = count;
+    let conflict = |simplex: &[usize]| Conflict::new(&vertices, simplex, infinite, &interior);
+
+    initial.sort_unstable();
+    let mut simplices = vec![(initial.clone(), conflict(&initial)?)];
+    for skip in 0..initial.len() {
+        let mut simplex = initial.clone();
+        simplex[skip] = infinite;
+        simplex.sort_unstable();
+        let region = conflict(&simplex)?;
+        simplices.push((simplex, region));
+    }
+    for index in (0..count).filter(|index| !initial.contains(index)) {
+        let point = &vertices[index];
+        let side = |facet: &[usize], vertex: usize| {
+            let opposite = if vertex == infinite {
+                // The vertex at infinity lies beyond every hull facet.
+                -orientation(&vertices, facet, &interior)
+            } else {
+                orientation(&vertices, facet, &vertices[vertex])
+            };
+            orientation(&vertices, facet, point) * opposite
+        };
+        let mut cavity: Vec<bool> = simplices
+            .iter()
+            .map(|(_, region)| region.contains(point))
+            .collect();
+        // The simplices enclosing the point are replaced even if rounding puts it outside
+        // their circumspheres.
+        let enclosing = enclosing(&simplices, &cavity, infinite, side);
+        for &position in enclosing.iter() {
+            cavity[position] = true;
+        }
+        // Facets of the cavity that belong to a single simplex form its boundary. Rounding
+        // can make the conflict tests inconsistent, so simplices with a boundary facet the
+        // point does not see are kept until the cavity is star-shaped from the point, and
+        // the new simplices cannot overlap.
+        let boundary = loop {
+            let mut boundary: HashMap<Vec<usize>, Vec<(usize, usize)>> = HashMap::new();
+            for (position, (simplex, _)) in simplices.iter().enumerate() {
+                if !cavity[position] {
+                    continue;
+                }
+                for (facet, opposite) in facets(simplex) {
+                    boundary
+                        .entry(facet)
+                        .or_default()
+                        .push((position, opposite));
+                }
+            }
+            let hidden: Vec<usize> = boundary
+                .iter()
+                .filter(|(facet, owners)| {
+                    let [(position, opposite)] = owners[..] else {
+                        return false;
+                    };
+                    !enclosing.contains(&position)
+                        && !facet.contains(&infinite)
+                        && side(facet, opposite) <= 0.0
+                })
+                .map(|(_, owners)| owners[0].0)
+                .collect();
+            if hidden.is_empty() {
+                break boundary;
+            }
+            for position in hidden {
+                cavity[position] = false;
+            }
+        };
+        let mut cavity = cavity.into_iter();
+        simplices.retain(|_| cavity.next() == Some(false));
+        for (facet, owners) in boundary {
+            if owners.len() == 1 {
+                let mut simplex = facet;
+                simplex.push(index);
+                simplex.sort_unstable();
+                let region = conflict(&simplex)?;
+                simplices.push((simplex, region));
+            }
+        }
+    }
+    let mut simplices: Vec<Vec<usize>> = simplices
+        .into_iter()
+        .map(|(simplex, _)| simplex)
+        .filter(|simplex| !simplex.contains(&infinite))
+        .collect();
+    simplices.sort();
+    Ok(simplices)
+}
+
+/// Returns the positions of the simplices enclosing a point: the finite simplices containing
+/// it, with their neighbours if it lies on a facet, or else the simplices through the vertex
+/// at infinity whose hull facet it lies beyond. `side` returns the sign of the side of a
+/// facet the point lies on relative to a vertex, or 0 if it lies on the facet's plane.
+///
+/// A simplex containing the point is in conflict with it, so the simplices flagged in
+/// `conflict` are searched first and the others only if rounding hides it among them.
+fn enclosing<F>(
+    simplices: &[(Vec<usize>, Conflict)],
+    conflict: &[bool],
+    infinite: usize,
+    side: F,
+) -> Vec<usize>
+where
+    F: Fn(&[usize], usize) -> f64,
+{
+    let mut enclosing = Vec::new();
+    let candidates = (0..simplices.len())
+        .filter(|&position| conflict[position])
+        .chain((0..simplices.len()).filter(|&position| !conflict[position]));
+    for position in candidates {
+        let simplex = &simplices[position].0;
+        if simplex.contains(&infinite) {
+            continue;
+        }
+        if !enclosing.is_empty() && !conflict[position] {
+            break;
+        }
+        let sides: Vec<(Vec<usize>, f64)> = facets(simplex)
+            .map(|(facet, opposite)| {
+                let sign = side(&facet, opposite);
+                (facet, sign)
+            })
+            .collect();
+        if sides.iter().any(|(_, sign)| *sign < 0.0) {
+            continue;
+        }
+        enclosing.push(position);
+        for (facet, _) in sides.iter().filter(|(_, sign)| *sign == 0.0) {
+            enclosing.extend(
+                simplices
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(other, (vertices, _))| {
+                        (other != position && facet.iter().all(|v| vertices.contains(v)))
+                            .then_some(other)
+                    }),
+            );
+        }
+    }
+    if enclosing.is_empty() {
+        enclosing.extend(
+            simplices
+                .iter()
+                .enumerate()
+                .filter_map(|(position, (simplex, _))| {
+                    let hull = simplex.iter().position(|&v| v == infinite)?;
+                    let (facet, opposite) = facets(simplex).nth(hull)?;
+                    (side(&facet, opposite) > 0.0).then_some(position)
+                }),
+        );
+    }
+    enclosing.sort_unstable();
+    enclosing.dedup();
+    enclosing
+}
+
+/// Returns the facets of a simplex, each with the vertex opposite to it.
+fn facets(simplex: &[usize]) -> impl Iterator<Item = (Vec<usize>, usize)> + '_ {
+    (0..simplex.len()).map(|skip| {
+        let mut facet = simplex.to_vec();
+        let opposite = facet.remove(skip);
+        (facet, opposite)
+    })
+}
+
+/// Returns the side of the hyperplane through a facet the point lies on, as the sign of the
+/// volume of the simplex they span, or 0 if the volume vanishes to rounding.
+fn orientation(coordinates: &[DVector<f64>], facet: &[usize], point: &DVector<f64>) -> f64 {
+    let origin = &coordinates[facet[0]];
+    let mut edges: Vec<DVector<f64>> = facet[1..]
+        .iter()
+        .map(|&v| &coordinates[v] - origin)
+        .collect();
+    edges.push(point - origin);
+    let scale = edges.iter().map(|edge| edge.norm()).product::<f64>();
+    let volume = DMatrix::from_columns(&edges).determinant();
+    if volume.abs() <= 1e-12 * scale {
+        0.0
+    } else {
+        volume.signum()
+    }
+}
+
+/// The open region of the points in conflict with a simplex of the triangulation, which
+/// must not be left inside its circumsphere.
+enum Conflict {
+    /// The circumsphere of a finite simplex, as its center and squared radius.
+    Sphere(DVector<f64>, f64),
+    /// The side beyond the hull facet of a simplex through the vertex at infinity, as the
+    /// unit normal of the facet pointing away from the hull and its offset, and the sphere
+    /// of points on the facet's plane, which conflict if they are inside its circumsphere.
+    Beyond {
+        normal: DVector<f64>,
+        offset: f64,
+        facet: (DVector<f64>, f64),
+    },
+}
+
+impl Conflict {
+    fn new(
+        coordinates: &[DVector<f64>],
+        simplex: &[usize],
+        infinite: usize,
+        interior: &DVector<f64>,
+    ) -> Result<Self> {
+        if !simplex.contains(&infinite) {
+            let (center, radius2) = circumsphere(coordinates, simplex)?;
+            return Ok(Conflict::Sphere(center, radius2));
+        }
+        let facet: Vec<usize> = simplex.iter().copied().filter(|&v| v != infinite).collect();
+        let origin = &coordinates[facet[0]];
+        let dimension = origin.len();
+        let edges = DMatrix::from_fn(facet.len() - 1, dimension, |row, column| {
+            coordinates[facet[row + 1]][column] - origin[column]
+        });
+        // The normal is orthogonal to every edge: its components are the cofactors of a row
+        // appended to the edges.
+        let normal = DVector::from_fn(dimension, |axis, _| {
+            let minor = edges.clone().remove_column(axis);
+            let determinant = if minor.is_empty() {
+                1.0
+            } else {
+                minor.determinant()
+            };
+            if axis % 2 == 0 {
+                determinant
+            } else {
+                -determinant
+            }
+        });
+        let length = normal.norm();
+        if length <= 1e-12 {
+            return Err(anyhow!("the triangulation has a degenerate simplex"));
+        }
+        let mut normal = normal / length;
+        if normal.dot(&(interior - origin)) > 0.0 {
+            normal = -normal;
+        }
+        let offset = normal.dot(origin);
+        // The circumcenter of the facet in its plane is origin + edges^T x, where
+        // (edges edges^T) x holds half the squared edge lengths.
+        let center = if edges.is_empty() {
+            origin.clone()
+        } else {
+            let lengths =
+                DVector::from_fn(edges.nrows(), |row, _| edges.row(row).norm_squared() / 2.0);
+            let x = (&edges * edges.transpose())
+                .lu()
+                .solve(&lengths)
+                .ok_or_else(|| anyhow!("the triangulation has a degenerate simplex"))?;
+            origin + edges.transpose() * x
+        };
+        let radius2 = (&center - origin).norm_squared();
+        Ok(Conflict::Beyond {
+            normal,
+            offset,
+            facet: (center, radius2),
+        })
+    }
+
+    fn contains(&self, point: &DVector<f64>) -> bool {
+        let inside = |center: &DVector<f64>, radius2: f64| {
+            (point - center).norm_squared() < radius2 - 1e-10 * radius2.max(1.0)
+        };
+        match self {
+            Conflict::Sphere(center, radius2) => inside(center, *radius2),
+            Conflict::Beyond {
+                normal,
+                offset,
+                facet: (center, radius2),
+            } => {
+                let distance = normal.dot(point) - offset;
+                distance > 1e-10 || (distance >= -1e-10 && inside(center, *radius2))
+            }
+        }
+    }
+}
+
+/// Returns the center and squared radius of the sphere through the vertices of a simplex.
+fn circumsphere(coordinates: &[DVector<f64>], simplex: &[usize]) -> Result<(DVector<f64>, f64)> {
+    let origin = &coordinates[simplex[0]];
+    let dimension = origin.len();
+    // |c - v|^2 = |c - v0|^2 for every vertex v is linear in c.
+    let mut a = DMatrix::zeros(dimension, dimension);
+    let mut b = DVector::zeros(dimension);
+    for (row, &vertex) in simplex[1..].iter().enumerate() {
+        let edge = &coordinates[vertex] - origin;
+        a.row_mut(row).copy_from(&edge.transpose());
+        b[row] = edge.norm_squared() / 2.0;
+    }
+    let center = a
+        .lu()
+        .solve(&b)
+        .ok_or_else(|| anyhow!("the triangulation has a degenerate simplex"))?;
+    Ok((origin + &center, center.norm_squared()))
+}
+
+/// The prepared triangulation of `Delaunay`.
+struct DelaunayWeights {
+    coordinates: Vec<DVector<f64>>,
+    simplices: Vec<Simplex>,
+    /// The simplex across the facet opposite to each vertex of each simplex, `None` on the
+    /// boundary.
+    neighbors: Vec<Vec<Option<usize>>>,
+    /// A simplex through each point, where a walk from the nearest point starts.
+    incident: Vec<usize>,
+    tree: KdTree,
+    /// The facets of the simplices that no other simplex shares, which bound the hull.
+    boundary: Vec<Vec<usize>>,
+    extrapolation: Extrapolation,
+}
+
+impl DelaunayWeights {
+    /// Returns the vertices of the face of a facet nearest to the target, the weights of the
+    /// nearest point of the face and its squared distance to the target.
+    ///
+    /// The nearest point is the projection onto one of the faces, of any dimension, with
+    /// non-negative weights; a facet has few vertices, so all faces are tried.
+    fn nearest_on_face(
+        &self,
+        facet: &[usize],
+        target: &DVector<f64>,
+    ) -> Option<(Vec<usize>, Vec<f64>, f64)> {
+        (1..1usize << facet.len())
+            .filter_map(|subset| {
+                let face: Vec<usize> = (0..facet.len())
+                    .filter(|bit| subset & (1 << bit) != 0)
+                    .map(|bit| facet[bit])
+                    .collect();
+                let origin = &self.coordinates[face[0]];
+                let offset = target - origin;
+                if face.len() == 1 {
+                    return Some((face, vec![1.0], offset.norm_squared()));
+                }
+                let edges = DMatrix::from_columns(
+                    &face[1..]
+                        .iter()
+                        .map(|&v| &self.coordinates[v] - origin)
+                        .collect::<Vec<_>>(),
+                );
+                let local =
+                    (edges.transpose() * &edges).try_inverse()? * edges.transpose() * &offset;
+                let mut weights = Vec::with_capacity(face.len());
+                weights.push(1.0 - local.sum());
+                weights.extend(local.iter());
+                if min_weight(&weights) < -1e-12 {
+                    return None;
+                }
+                let distance = (offset - edges * local).norm_squared();
+                Some((face, weights, distance))
+            })
+            .min_by(|a, b| a.2.total_cmp(&b.2))
+    }
+
+    /// Returns the position of the simplex containing the target and its weights, found by
+    /// walking from the simplex at `start` across the facet the target is farthest beyond.
+    ///
+    /// Returns `None` if the walk leaves the triangulation, i.e. the target is outside the
+    /// hull, or does not arrive within one step per simplex.
+    fn walk(&self, start: usize, target: &DVector<f64>) -> Option<(usize, Vec<f64>)> {
+        let mut position = start;
+        for _ in 0..self.simplices.len() {
+            let weights = self.simplices[position].barycentric(&self.coordinates, target);
+            let (skip, weight) = weights
+                .iter()
+                .copied()
+                .enumerate()
+                .min_by(|a, b| a.1.total_cmp(&b.1))?;
+            if weight >= -1e-9 {
+                return Some((position, weights));
+            }
+            position = self.neighbors[position][skip]?;
+        }
+        None
+    }
+}
+
+impl PreparedInterpolation for DelaunayWeights {
+    fn weights(&self, target: &[Vec<f64>]) -> Result<DMatrix<f64>> {
+        let dimension = self.coordinates[0].len();
+        let rows = target
+            .par_iter()
+            // Consecutive targets are usually close, so each walk starts at the simplex of the
+            // previous target of the same thread, or else at the nearest point.
+            .map_init(
+                || None,
+                |previous: &mut Option<usize>, target_vec| {
+                    // Missing target coordinates count as 0, extra ones are ignored.
+                    let target = DVector::from_fn(dimension, |i, _| {
+                        target_vec.get(i).copied().unwrap_or_default()
+                    });
+                    let start = previous.or_else(|| {
+                        let (nearest, _) = self.tree.nearest(target.as_slice())?;
+                        Some(self.incident[nearest])
+                    });
+                    let found = start.and_then(|start| self.walk(start, &target));
+                    if let Some((position, _)) = found {
+                        *previous = Some(position);
+                    }
+                    // The simplex containing the target, or the one it is least outside of.
+                    let (simplex, weights) = match found {
+                        Some((position, weights)) => (&self.simplices[position], weights),
+                        None => self
+                            .simplices
+                            .iter()
+                            .map(|simplex| {
+                                (simplex, simplex.barycentric(&self.coordinates, &target))
+                            })
+                            .max_by(|a, b| min_weight(&a.1).total_cmp(&min_weight(&b.1)))
+                            .ok_or_else(|| anyhow!("the triangulation has no simplices"))?,
+                    };
+                    let mut row = vec![0.0; self.coordinates.len()];
+                    if min_weight(&weights) >= -1e-9 {
+                        for (&vertex, weight) in simplex.vertices.iter().zip(weights) {
+                            row[vertex] = weight;
+                        }
+                        return Ok(row);
+                    }
+                    match self.extrapolation {
+                        Extrapolation::Error => {
+                            return Err(anyhow!(
+                                "target {:?} is outside the convex hull of the points",
+                                target_vec
+                            ))
+                        }
+                        Extrapolation::Clamp => {
+                            let (vertices, weights) = self
+                                .boundary
+                                .iter()
+                                .filter_map(|facet| self.nearest_on_face(facet, &target))
+                                .min_by(|a, b| a.2.total_cmp(&b.2))
+                                .map(|(vertices, weights, _)| (vertices, weights))
+                                .ok_or_else(|| anyhow!("the triangulation has no boundary"))?;
+                            for (vertex, weight) in vertices.into_iter().zip(weights) {
+                                row[vertex] = weight;
+                            }
+                        }
+                        Extrapolation::Linear => {
+                            for (&vertex, weight) in simplex.vertices.iter().zip(weights) {
+                                row[vertex] = weight;
+                            }
+                        }
+                    }
+                    Ok(row)
+                },
+            )
+            .collect::<Result<Vec<Vec<f64>>>>()?;
+        Ok(DMatrix::from_fn(
+            rows.len(),
+            self.coordinates.len(),
+            |row, column| rows[row][column],
+        ))
+    }
+}
+
+fn min_weight(weights: &[f64]) -> f64 {
+    weights.iter().copied().fold(f64::INFINITY, f64::min)
+}
+
+/// The interpolation between neighbouring nodes along each axis of a `Grid`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GridOrder {
+    /// Linear along each axis, i.e. multilinear in the cell around the target.
+    Linear,
+    /// A natural cubic spline along each axis.
+    Cubic,
+}
+
+// Implement tensor-product interpolation over a rectilinear grid of points
+#[derive(Debug)]
+pub struct Grid {
+    pub order: GridOrder,
+    pub extrapolation: Extrapolation,
+    /// The ascending node coordinates of each axis; detected from the points if `None`.
+    pub axes: Option<Vec<Vec<f64>>>,
+}
+
+impl InterpolationStrategy for Grid {
+    fn prepare(&self, points: &[Point]) -> Result<Box<dyn PreparedInterpolation>> {
+        let dimension = dimension(points)?;
+        if dimension == 0 {
+            return Err(anyhow!("points must have at least 1 coordinate"));
+        }
+        let axes = match &self.axes {
+            Some(axes) => {
+                if axes.len() != dimension {
+                    return Err(anyhow!(
+                        "the grid must have {} axes, got {}",
+                        dimension,
+                        axes.len()
+                    ));
+                }
+                axes.clone()
+            }
+            None => (0..dimension)
+                .map(|axis| {
+                    let mut values: Vec<f64> = points.iter().map(|p| p.coordinates[axis]).collect();
+                    values.sort_by(f64::total_cmp);
+                    values.dedup_by(|a, b| (*a - *b).abs() <= TOLERANCE);
+                    values
+                })
+                .collect(),
+        };
+        for (axis, values) in axes.iter().enumerate() {
+            if values.len() < 2 {
+                return Err(anyhow!("axis {} of the grid needs at least 2 values", axis));
+            }
+            if values.windows(2).any(|pair| pair[1] - pair[0] <= TOLERANCE) {
+                return Err(anyhow!(
+                    "axis {} of the grid must be strictly ascending, got {:?}",
+                    axis,
+                    values
+                ));
+            }
+        }
+
+        // The point of every grid node, the first axis varying fastest.
+        let strides: Vec<usize> = axes
+            .iter()
+            .scan(1, |stride, values| {
+                let current = *stride;
+                *stride *= values.len();
+                Some(current)
+            })
+            .collect();
+        let nodes = axes.iter().map(Vec::len).product::<usize>();
+        let mut node_points: Vec<Option<usize>> = vec![None; nodes];
+        for (index, point) in points.iter().enumerate() {
+            let mut node = 0;
+            for (axis, (values, stride)) in axes.iter().zip(&strides).enumerate() {
+                let coordinate = point.coordinates[axis];
+                let position = values
+                    .iter()
+                    .position(|value| (value - coordinate).abs() <= TOLERANCE)
+                    .ok_or_else(|| anyhow!("point {:?} is not on the grid", point.coordinates))?;
+                node += position * stride;
+            }
+            if node_points[node].replace(index).is_some() {
+                return Err(anyhow!("point {:?} is defined twice", point.coordinates));
+            }
+        }
+        let missing: Vec<Vec<f64>> = node_points
+            .iter()
+            .enumerate()
+            .filter(|(_, point)| point.is_none())
+            .map(|(node, _)| {
+                axes.iter()
+                    .zip(&strides)
+                    .map(|(values, stride)| values[(node / stride) % values.len()])
+                    .collect()
+            })
+            .collect();
+        if let Some(first) = missing.first() {
+            return Err(anyhow!(
+                "the grid is missing {} of {} nodes, e.g. {:?}",
+                missing.len(),
+                nodes,
+                first
+            ));
+        }
+
+        let splines = match self.order {
+            GridOrder::Linear => None,
+            GridOrder::Cubic => Some(
+                axes.iter()
+                    .map(|values| spline_curvatures(values))
+                    .collect(),
+            ),
+        };
+        Ok(Box::new(GridWeights {
+            points: points.len(),
+            axes,
+            strides,
+            node_points: node_points.into_iter().flatten().collect(),
+            splines,
+            extrapolation: self.extrapolation,
+        }))
     }
 }
 
-impl Hash for Point {
-    fn hash<H: Hasher>(&self, state: &mut H) {
-        for &coord in &self.coordinates {
-            let discretized = (coord / TOLERANCE).round() * TOLERANCE;
-            discretized.to_bits().hash(state);
+/// Returns the matrix mapping the values at the nodes of an axis to the second derivatives
+/// of their natural cubic spline, which vanish at both ends.
+fn spline_curvatures(values: &[f64]) -> DMatrix<f64> {
+    let n = values.len();
+    let mut curvatures = DMatrix::zeros(n, n);
+    if n < 3 {
+        return curvatures;
+    }
+    let h: Vec<f64> = values.windows(2).map(|pair| pair[1] - pair[0]).collect();
+    // The continuity of the first derivative at the interior nodes.
+    let mut system = DMatrix::zeros(n - 2, n - 2);
+    let mut differences = DMatrix::zeros(n - 2, n);
+    for row in 0..n - 2 {
+        system[(row, row)] = 2.0 * (h[row] + h[row + 1]);
+        if row > 0 {
+            system[(row, row - 1)] = h[row];
+        }
+        if row + 3 < n {
+            system[(row, row + 1)] = h[row + 1];
         }
+        differences[(row, row)] = 6.0 / h[row];
+        differences[(row, row + 1)] = -6.0 / h[row] - 6.0 / h[row + 1];
+        differences[(row, row + 2)] = 6.0 / h[row + 1];
     }
+    // The system is strictly diagonally dominant, so the solve cannot fail.
+    if let Some(interior) = system.lu().solve(&differences) {
+        curvatures.rows_mut(1, n - 2).copy_from(&interior);
+    }
+    curvatures
 }
 
-// Define a trait for our interpolation strategies
-pub trait InterpolationStrategy {
-    // Corrected to include only two parameters: points and target
-    fn interpolate(
-        &self,
-        points: &HashMap<Point, f64>,
-        target: &[Vec<f64>],
-    ) -> Result<Vec<f64>, String>;
+/// The prepared grid of `Grid`.
+struct GridWeights {
+    points: usize,
+    axes: Vec<Vec<f64>>,
+    strides: Vec<usize>,
+    /// The point of every grid node.
+    node_points: Vec<usize>,
+    /// The spline curvature matrix of every axis for `GridOrder::Cubic`.
+    splines: Option<Vec<DMatrix<f64>>>,
+    extrapolation: Extrapolation,
 }
 
-// Implement nearest-neighbor interpolation
-pub struct NearestNeighbor;
-
-impl InterpolationStrategy for NearestNeighbor {
-    fn interpolate(
-        &self,
-        points: &HashMap<Point, f64>,
-        target: &[Vec<f64>],
-    ) -> Result<Vec<f64>, String> {
-        if points.is_empty() {
-            return Err("No points available for interpolation.".to_string());
+impl GridWeights {
+    /// Returns the weights of the nodes of an axis at a coordinate.
+    fn axis_weights(&self, axis: usize, coordinate: f64) -> Result<Vec<f64>> {
+        let values = &self.axes[axis];
+        let n = values.len();
+        let (first, last) = (values[0], values[n - 1]);
+        let outside = coordinate < first - TOLERANCE || coordinate > last + TOLERANCE;
+        let x = match self.extrapolation {
+            Extrapolation::Error if outside => {
+                return Err(anyhow!(
+                    "coordinate {} of axis {} is outside the grid [{}, {}]",
+                    coordinate,
+                    axis,
+                    first,
+                    last
+                ))
+            }
+            Extrapolation::Clamp => coordinate.clamp(first, last),
+            _ => coordinate,
+        };
+        // The interval of the coordinate, the boundary one outside the grid.
+        let i = values[1..n - 1].partition_point(|value| *value <= x);
+        let h = values[i + 1] - values[i];
+        let mut weights = vec![0.0; n];
+        let Some(curvatures) = self.splines.as_ref().map(|splines| &splines[axis]) else {
+            let t = (x - values[i]) / h;
+            weights[i] = 1.0 - t;
+            weights[i + 1] = t;
+            return Ok(weights);
+        };
+        if x < first || x > last {
+            // A natural spline continues linearly with its slope at the end.
+            let (node, other, sign) = if x < first {
+                (0, 1, -1.0)
+            } else {
+                (n - 1, n - 2, 1.0)
+            };
+            let distance = x - values[node];
+            weights[node] += 1.0 + sign * distance / h;
+            weights[other] -= sign * distance / h;
+            for (weight, curvature) in weights.iter_mut().zip(curvatures.row(other).iter()) {
+                *weight += sign * distance * h * curvature / 6.0;
+            }
+            return Ok(weights);
         }
+        let b = (x - values[i]) / h;
+        let a = 1.0 - b;
+        weights[i] += a;
+        weights[i + 1] += b;
+        let (ca, cb) = ((a.powi(3) - a) * h * h / 6.0, (b.powi(3) - b) * h * h / 6.0);
+        for (weight, (mi, mj)) in weights
+            .iter_mut()
+            .zip(curvatures.row(i).iter().zip(curvatures.row(i + 1).iter()))
+        {
+            *weight += ca * mi + cb * mj;
+        }
+        Ok(weights)
+    }
+}
 
-        // Convert HashMap into a Vec once to avoid repetitive hashing operations
-        let points_vec: Vec<(&Point, &f64)> = points.iter().collect();
-
-        let results: Result<Vec<_>, _> = target
+impl PreparedInterpolation for GridWeights {
+    fn weights(&self, target: &[Vec<f64>]) -> Result<DMatrix<f64>> {
+        let rows = target
             .par_iter()
             .map(|target_vec| {
-                points_vec
-                    .iter()
-                    .map(|(point, &value)| {
-                        let distance = point
-                            .coordinates
-                            .iter()
-                            .zip(target_vec)
-                            .map(|(a, b)| (a - b).powi(2))
-                            .sum::<f64>()
-                            .sqrt();
-                        (distance, value)
+                // Missing target coordinates count as 0, extra ones are ignored.
+                let axis_weights = (0..self.axes.len())
+                    .map(|axis| {
+                        self.axis_weights(axis, target_vec.get(axis).copied().unwrap_or_default())
                     })
-                    .min_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(std::cmp::Ordering::Equal))
-                    .map(|(_, value)| value) // Dereference value to return the f64 directly
-                    .ok_or_else(|| "Error finding nearest neighbor.".to_string())
+                    .collect::<Result<Vec<_>>>()
+                    .map_err(|e| anyhow!("target {:?}: {}", target_vec, e))?;
+                // The tensor product of the axis weights, skipping nodes without weight.
+                let mut row = vec![0.0; self.points];
+                let mut products = vec![(0, 1.0)];
+                for (weights, stride) in axis_weights.iter().zip(&self.strides) {
+                    products = products
+                        .into_iter()
+                        .flat_map(|(node, product)| {
+                            weights
+                                .iter()
+                                .enumerate()
+                                .filter(|(_, weight)| **weight != 0.0)
+                                .map(move |(index, weight)| {
+                                    (node + index * stride, product * weight)
+                                })
+                        })
+                        .collect();
+                }
+                for (node, weight) in products {
+                    row[self.node_points[node]] += weight;
+                }
+                Ok(row)
             })
-            .collect();
-
-        results
+            .collect::<Result<Vec<Vec<f64>>>>()?;
+        Ok(DMatrix::from_fn(rows.len(), self.points, |row, column| {
+            rows[row][column]
+        }))
     }
 }
 
-// Implement linear interpolation
-pub struct Linear;
+/// The radial basis function of an `Rbf`, of the distance `r` and the shape parameter `e`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RbfKernel {
+    /// `r^2 ln r`, without shape parameter.
+    ThinPlate,
+    /// `sqrt(1 + (e r)^2)`.
+    Multiquadric,
+    /// `exp(-(e r)^2)`.
+    Gaussian,
+}
 
-impl InterpolationStrategy for Linear {
-    fn interpolate(
-        &self,
-        points: &HashMap<Point, f64>,
-        target: &[Vec<f64>],
-    ) -> Result<Vec<f64>, String> {
-        if points.len() < 2 {
-            return Err("Not enough points for interpolation".to_string());
+impl RbfKernel {
+    fn evaluate(&self, r: f64, shape: f64) -> f64 {
+        match self {
+            RbfKernel::ThinPlate if r > 0.0 => r * r * r.ln(),
+            RbfKernel::ThinPlate => 0.0,
+            RbfKernel::Multiquadric => (1.0 + (shape * r).powi(2)).sqrt(),
+            RbfKernel::Gaussian => (-(shape * r).powi(2)).exp(),
         }
+    }
+}
 
-        // Convert points to a format suitable for SVD and regression
-        let points_vec: Vec<(Vec<f64>, f64)> = points
+// Implement radial basis function interpolation
+#[derive(Debug)]
+pub struct Rbf {
+    pub kernel: RbfKernel,
+    /// The shape parameter of the multiquadric and Gaussian kernels, in inverse units of the
+    /// coordinates.
+    pub shape: f64,
+    /// Whether a linear polynomial is added to the radial functions, so linear values are
+    /// reproduced exactly and the thin-plate system is well posed.
+    pub polynomial: bool,
+}
+
+impl InterpolationStrategy for Rbf {
+    fn prepare(&self, points: &[Point]) -> Result<Box<dyn PreparedInterpolation>> {
+        if points.is_empty() {
+            return Err(anyhow!("No points available for interpolation."));
+        }
+        let dimension = dimension(points)?;
+        if let Some(index) = duplicate_groups(points)
             .iter()
-            .map(|(point, &value)| (point.coordinates.clone(), value))
+            .enumerate()
+            .position(|(index, first)| index != *first)
+        {
+            return Err(anyhow!(
+                "point {:?} is defined twice",
+                points[index].coordinates
+            ));
+        }
+        let coordinates: Vec<DVector<f64>> = points
+            .iter()
+            .map(|p| DVector::from_column_slice(&p.coordinates))
             .collect();
+        let n = points.len();
+        let terms = if self.polynomial { dimension + 1 } else { 0 };
+        // The interpolation conditions of the radial functions and, if any, the
+        // orthogonality of their coefficients to the polynomial.
+        let mut system = DMatrix::zeros(n + terms, n + terms);
+        for (row, a) in coordinates.iter().enumerate() {
+            for (column, b) in coordinates.iter().enumerate() {
+                system[(row, column)] = self.kernel.evaluate((a - b).norm(), self.shape);
+            }
+            for term in 0..terms {
+                let value = if term == 0 { 1.0 } else { a[term - 1] };
+                system[(row, n + term)] = value;
+                system[(n + term, row)] = value;
+            }
+        }
+        let conditioning =
+            Conditioning::new(&system.singular_values(), 1e-12 * system.norm(), n + terms);
+        let singular = || {
+            anyhow!(
+                "the RBF system of the points is singular, e.g. too few points for the polynomial"
+            )
+        };
+        if conditioning.rank < conditioning.columns {
+            return Err(singular());
+        }
+        if conditioning.condition > MAX_RBF_CONDITION {
+            return Err(anyhow!(
+                "the RBF system of the points is ill-conditioned, condition number {:.3e} \
+                 exceeds {:.0e}, e.g. increase the shape parameter or remove nearby points",
+                conditioning.condition,
+                MAX_RBF_CONDITION
+            ));
+        }
+        // The coefficients of unit values at each point: the system solved for the first n
+        // columns of the identity.
+        let coefficients = system
+            .lu()
+            .solve(&DMatrix::identity(n + terms, n))
+            .ok_or_else(singular)?;
+        Ok(Box::new(RbfWeights {
+            kernel: self.kernel,
+            shape: self.shape,
+            terms,
+            coefficients,
+            coordinates,
+            conditioning,
+        }))
+    }
+}
 
-        let coefficients = multivariate_linear_regression_svd(&points_vec)
-            .map_err(|e| format!("Failed to perform linear regression: {}", e))?;
+/// The prepared system of `Rbf`.
+struct RbfWeights {
+    kernel: RbfKernel,
+    shape: f64,
+    terms: usize,
+    /// Maps values, one row per point, to the coefficients of the radial functions followed
+    /// by those of the polynomial.
+    coefficients: DMatrix<f64>,
+    coordinates: Vec<DVector<f64>>,
+    conditioning: Conditioning,
+}
 
-        // Use parallel iterator on targets for prediction
-        let predictions: Result<Vec<f64>, _> = target
-            .par_iter()
-            .map(|t| {
-                let predicted_value = coefficients[0]
-                    + t.iter()
-                        .enumerate()
-                        .map(|(i, coord)| {
-                            if i + 1 < coefficients.len() {
-                                coefficients[i + 1] * coord
-                            } else {
-                                0.0
-                            }
-                        })
-                        .sum::<f64>();
-                Ok(predicted_value)
-            })
-            .collect();
+impl PreparedInterpolation for RbfWeights {
+    fn weights(&self, target: &[Vec<f64>]) -> Result<DMatrix<f64>> {
+        let dimension = self.coordinates[0].len();
+        let n = self.coordinates.len();
+        let basis = DMatrix::from_fn(target.len(), n + self.terms, |row, column| {
+            // Missing target coordinates count as 0, extra ones are ignored.
+            let coordinate = |i: usize| target[row].get(i).copied().unwrap_or_default();
+            match column {
+                c if c < n => {
+                    let r = (0..dimension)
+                        .map(|i| (coordinate(i) - self.coordinates[c][i]).powi(2))
+                        .sum::<f64>()
+                        .sqrt();
+                    self.kernel.evaluate(r, self.shape)
+                }
+                c if c == n => 1.0,
+                c => coordinate(c - n - 1),
+            }
+        });
+        Ok(basis * &self.coefficients)
+    }
 
-        predictions
+    fn conditioning(&self) -> Option<Conditioning> {
+        Some(self.conditioning)
     }
 }
 
-fn multivariate_linear_regression_svd(points: &[(Vec<f64>, f64)]) -> Result<Vec<f64>, String> {
-    if points.is_empty() {
-        return Err("No points provided for linear regression.".to_string());
+// Implement inverse-distance-weighting interpolation
+#[derive(Debug)]
+pub struct InverseDistance {
+    /// The power of the distance in the weights, e.g. 2.
+    pub power: f64,
+    /// The number of nearest points weighted per target; all points if `None`.
+    pub neighbors: Option<usize>,
+}
+
+impl InterpolationStrategy for InverseDistance {
+    fn prepare(&self, points: &[Point]) -> Result<Box<dyn PreparedInterpolation>> {
+        if points.is_empty() {
+            return Err(anyhow!("No points available for interpolation."));
+        }
+        if self.neighbors == Some(0) {
+            return Err(anyhow!("neighbors must be greater than 0"));
+        }
+        dimension(points)?;
+        let coordinates = points.iter().map(|p| p.coordinates.clone()).collect();
+        Ok(Box::new(InverseDistanceWeights {
+            tree: KdTree::new(coordinates),
+            power: self.power,
+            neighbors: self.neighbors.unwrap_or(points.len()),
+        }))
     }
+}
 
-    // Parallel processing to prepare x_data and y_data
-    let (x_data, y_data): (Vec<_>, Vec<f64>) = points
-        .par_iter()
-        .map(|(features, target)| {
-            let mut row = vec![1.0]; // Intercept term
-            row.extend(features.iter().cloned());
-            (row, *target)
-        })
-        .unzip();
+/// The prepared points of `InverseDistance`.
+struct InverseDistanceWeights {
+    tree: KdTree,
+    power: f64,
+    neighbors: usize,
+}
 
-    // Flatten x_data for DMatrix
-    let x_data: Vec<f64> = x_data.into_iter().flatten().collect();
-    let rows = points.len();
-    let cols = points[0].0.len() + 1; // Number of features + 1 for the intercept
+impl InverseDistanceWeights {
+    fn sparse_weights(&self, target: &[Vec<f64>]) -> Vec<Vec<(usize, f64)>> {
+        target
+            .par_iter()
+            .map(|target_vec| {
+                let nearest = self.tree.k_nearest(target_vec, self.neighbors);
+                // A target on a point takes its values.
+                if let Some(&(index, distance)) = nearest.first() {
+                    if distance <= TOLERANCE {
+                        return vec![(index, 1.0)];
+                    }
+                }
+                let total: f64 = nearest.iter().map(|(_, d)| d.powf(-self.power)).sum();
+                nearest
+                    .iter()
+                    .map(|&(index, d)| (index, d.powf(-self.power) / total))
+                    .collect()
+            })
+            .collect()
+    }
+}
 
-    // Convert the data into nalgebra's DMatrix and DVector
-    let x = DMatrix::from_row_slice(rows, cols, &x_data);
-    let y = DVector::from_vec(y_data);
+impl PreparedInterpolation for InverseDistanceWeights {
+    fn weights(&self, target: &[Vec<f64>]) -> Result<DMatrix<f64>> {
+        Ok(dense_weights(&self.sparse_weights(target), self.tree.len()))
+    }
 
-    // Perform SVD
-    let svd = x.svd(true, true);
-    match svd.solve(&y, 1e-12) {
-        Ok(solution) => Ok(solution.iter().cloned().collect()),
-        Err(e) => Err(format!(
-            "Failed to solve the linear system using SVD: {}",
-            e
-        )),
+    fn evaluate(&self, values: &DMatrix<f64>, target: &[Vec<f64>]) -> Result<DMatrix<f64>> {
+        sparse_evaluate(&self.sparse_weights(target), self.tree.len(), values)
     }
 }
 
 // Enum to encapsulate different strategies
+#[derive(Debug)]
 pub enum InterpolationStrategyEnum {
     Linear(Linear),
     NearestNeighbor(NearestNeighbor),
+    Delaunay(Delaunay),
+    Grid(Grid),
+    Rbf(Rbf),
+    InverseDistance(InverseDistance),
 }
 
-impl InterpolationStrategyEnum {
-    pub fn interpolate(
-        &self,
-        points: &HashMap<Point, f64>,
-        target: &[Vec<f64>],
-    ) -> Result<Vec<f64>, String> {
+impl InterpolationStrategy for InterpolationStrategyEnum {
+    fn prepare(&self, points: &[Point]) -> Result<Box<dyn PreparedInterpolation>> {
         match self {
-            InterpolationStrategyEnum::Linear(strategy) => strategy.interpolate(points, target),
-            InterpolationStrategyEnum::NearestNeighbor(strategy) => {
-                strategy.interpolate(points, target)
-            }
+            InterpolationStrategyEnum::Linear(strategy) => strategy.prepare(points),
+            InterpolationStrategyEnum::NearestNeighbor(strategy) => strategy.prepare(points),
+            InterpolationStrategyEnum::Delaunay(strategy) => strategy.prepare(points),
+            InterpolationStrategyEnum::Grid(strategy) => strategy.prepare(points),
+            InterpolationStrategyEnum::Rbf(strategy) => strategy.prepare(points),
+            InterpolationStrategyEnum::InverseDistance(strategy) => strategy.prepare(points),
         }
     }
 }
 
 // NDInterpolation struct utilizing the enum for static dispatch
 pub struct NDInterpolation<'a> {
-    points: HashMap<Point, f64>,
+    points: Vec<Point>,
+    values: Vec<Vec<f64>>,
     strategy: &'a InterpolationStrategyEnum,
+    /// How points sharing coordinates are merged.
+    duplicates: DuplicatePoints,
+    /// The merged points and the strategy prepared over them, e.g. its spatial index, built
+    /// by the first interpolation after the points change.
+    merged: OnceLock<Merged>,
+}
+
+/// The points of an `NDInterpolation` after `merge_duplicates`, with the strategy prepared
+/// over them.
+struct Merged {
+    points: MergedPoints,
+    prepared: Box<dyn PreparedInterpolation>,
 }
 
 impl<'a> NDInterpolation<'a> {
+    /// Creates an interpolation that fails on points sharing coordinates.
     pub fn new(strategy: &'a InterpolationStrategyEnum) -> Self {
+        Self::with_duplicates(strategy, DuplicatePoints::Error)
+    }
+
+    /// Creates an interpolation that merges points sharing coordinates by `duplicates`.
+    pub fn with_duplicates(
+        strategy: &'a InterpolationStrategyEnum,
+        duplicates: DuplicatePoints,
+    ) -> Self {
         NDInterpolation {
-            points: HashMap::new(),
+            points: Vec::new(),
+            values: Vec::new(),
             strategy,
+            duplicates,
+            merged: OnceLock::new(),
         }
     }
 
     // Method to add a point and its associated value to the interpolation dataset
     pub fn add_point(&mut self, point: Point, value: f64) {
-        self.points.insert(point, value);
+        self.add_values(point, &[value]);
+    }
+
+    /// Adds a point with vector values, e.g. `Vector6::as_slice` of a stress tensor or the
+    /// components of many nodes. Points sharing coordinates are merged when interpolating.
+    pub fn add_values(&mut self, point: Point, values: &[f64]) {
+        self.points.push(point);
+        self.values.push(values.to_vec());
+        self.merged = OnceLock::new();
     }
 
     // Delegates to the strategy's interpolate method
-    pub fn interpolate(&self, target: &[Vec<f64>]) -> Result<Vec<f64>, String> {
-        self.strategy.interpolate(&self.points, target)
+    pub fn interpolate(&self, target: &[Vec<f64>]) -> Result<Vec<f64>> {
+        let interpolated = self.interpolate_values(target)?;
+        if interpolated.ncols() != 1 {
+            return Err(anyhow!(
+                "points have {} values, interpolate them with interpolate_values",
+                interpolated.ncols()
+            ));
+        }
+        Ok(interpolated.column(0).iter().copied().collect())
+    }
+
+    /// Interpolates the vector values of the points at every target, one row per target.
+    ///
+    /// # Returns
+    ///
+    /// Returns an error if the points have different numbers of values, points share
+    /// coordinates under `DuplicatePoints::Error`, or the strategy fails.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fatigue::interpolate::{InterpolationStrategyEnum, Linear, NDInterpolation, Point};
+    /// use nalgebra::Vector6;
+    ///
+    /// let strategy = InterpolationStrategyEnum::Linear(Linear);
+    /// let mut interpolation = NDInterpolation::new(&strategy);
+    /// let unit = Vector6::new(1.0, 2.0, 3.0, 4.0, 5.0, 6.0);
+    /// interpolation.add_values(Point::new(None, vec![0.0]), Vector6::zeros().as_slice());
+    /// interpolation.add_values(Point::new(None, vec![1.0]), unit.as_slice());
+    /// let tensors = interpolation.interpolate_values(&[vec![2.0]]).unwrap();
+    /// assert!((tensors[(0, 5)] - 12.0).abs() < 1e-9);
+    /// ```
+    pub fn interpolate_values(&self, target: &[Vec<f64>]) -> Result<DMatrix<f64>> {
+        let merged = self.merged()?;
+        merged.prepared.evaluate(&merged.points.values, target)
+    }
+
+    /// Returns how well the strategy reproduces the values at the merged points, with the
+    /// pairs of points merged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fatigue::interpolate::{
+    ///     DuplicatePoints, InterpolationStrategyEnum, Linear, NDInterpolation, Point,
+    /// };
+    ///
+    /// let strategy = InterpolationStrategyEnum::Linear(Linear);
+    /// let mut interpolation = NDInterpolation::with_duplicates(&strategy, DuplicatePoints::Last);
+    /// interpolation.add_point(Point::new(Some("a.usf".into()), vec![0.0]), 0.0);
+    /// interpolation.add_point(Point::new(Some("b.usf".into()), vec![1.0]), 1.0);
+    /// interpolation.add_point(Point::new(Some("c.usf".into()), vec![2.0]), 4.0);
+    /// interpolation.add_point(Point::new(Some("d.usf".into()), vec![2.0]), 4.0);
+    /// let diagnostics = interpolation.diagnostics().unwrap();
+    /// // A line cannot pass through a parabola, and leaving a point out makes it worse.
+    /// assert!(diagnostics.max_residual() > 0.0);
+    /// assert!(diagnostics.max_leave_one_out().unwrap() > diagnostics.max_residual());
+    /// assert_eq!(diagnostics.conditioning.unwrap().rank, 2);
+    /// assert_eq!(diagnostics.duplicates[0].0.file.as_deref(), Some("c.usf"));
+    /// ```
+    pub fn diagnostics(&self) -> Result<Diagnostics> {
+        let merged = &self.merged()?.points;
+        let mut diagnostics = self.strategy.diagnostics(&merged.points, &merged.values)?;
+        diagnostics.duplicates = merged.duplicates.clone();
+        Ok(diagnostics)
+    }
+
+    /// Returns the merged points, preparing the strategy over them on first use.
+    fn merged(&self) -> Result<&Merged> {
+        if let Some(merged) = self.merged.get() {
+            return Ok(merged);
+        }
+        let points = merge_duplicates(&self.points, &self.value_matrix()?, self.duplicates)?;
+        let prepared = self.strategy.prepare(&points.points)?;
+        Ok(self.merged.get_or_init(|| Merged { points, prepared }))
+    }
+
+    /// Returns the values of the points, one row per point, or an error if the points have
+    /// different numbers of values.
+    fn value_matrix(&self) -> Result<DMatrix<f64>> {
+        let components = self.values.first().map_or(0, Vec::len);
+        if let Some(values) = self.values.iter().find(|v| v.len() != components) {
+            return Err(anyhow!(
+                "points must have {} values, got {}",
+                components,
+                values.len()
+            ));
+        }
+        Ok(DMatrix::from_fn(
+            self.points.len(),
+            components,
+            |row, column| self.values[row][column],
+        ))
     }
 }
 
@@ -434,4 +2331,797 @@ mod tests {
             "Interpolation took too long"
         );
     }
+
+    #[test]
+    fn test_vector_values_match_scalar_interpolation() {
+        // Six components per point, each a different function of the coordinates.
+        let coordinates = [[0.0, 0.0], [1.0, 0.0], [0.0, 1.0], [1.0, 1.0], [0.5, 2.0]];
+        let component =
+            |c: usize, p: &[f64; 2]| (c as f64 + 1.0) * p[0] - p[1] * c as f64 + p[0] * p[1];
+        let target = vec![vec![0.2, 0.3], vec![1.5, -0.5], vec![0.9, 1.9]];
+        for strategy in [
+            InterpolationStrategyEnum::Linear(Linear),
+            InterpolationStrategyEnum::NearestNeighbor(NearestNeighbor),
+        ] {
+            let mut vector = NDInterpolation::new(&strategy);
+            for p in coordinates.iter() {
+                let values: Vec<f64> = (0..6).map(|c| component(c, p)).collect();
+                vector.add_values(Point::new(None, p.to_vec()), &values);
+            }
+            let interpolated = vector.interpolate_values(&target).unwrap();
+            assert_eq!(interpolated.shape(), (3, 6));
+            for c in 0..6 {
+                let mut scalar = NDInterpolation::new(&strategy);
+                for p in coordinates.iter() {
+                    scalar.add_point(Point::new(None, p.to_vec()), component(c, p));
+                }
+                let expected = scalar.interpolate(&target).unwrap();
+                let column: Vec<f64> = interpolated.column(c).iter().copied().collect();
+                assert!(approx_eq(&column, &expected, TOLERANCE));
+            }
+            assert!(vector.interpolate(&target).is_err());
+        }
+
+        let strategy = InterpolationStrategyEnum::Linear(Linear);
+        let mut mixed = NDInterpolation::new(&strategy);
+        mixed.add_values(Point::new(None, vec![0.0]), &[1.0, 2.0]);
+        mixed.add_point(Point::new(None, vec![1.0]), 3.0);
+        let err = mixed.interpolate_values(&[vec![0.5]]).unwrap_err();
+        assert!(format!("{err:#}").contains("points must have 2 values, got 1"));
+    }
+
+    #[test]
+    fn test_prepared_weights() {
+        let points: Vec<Point> = [[0.0, 0.0], [1.0, 0.0], [0.0, 1.0], [1.0, 1.0]]
+            .iter()
+            .map(|p| Point::new(None, p.to_vec()))
+            .collect();
+        let values = DMatrix::from_row_slice(4, 2, &[0.0, 1.0, 2.0, 1.0, 3.0, 1.0, 5.0, 1.0]);
+        let target = vec![vec![0.5, 0.5], vec![2.0, -1.0], vec![0.9, 0.2]];
+
+        let prepared = Linear.prepare(&points).unwrap();
+        let weights = prepared.weights(&target).unwrap();
+        assert_eq!(weights.shape(), (3, 4));
+        // Linear weights reproduce constants, so every row sums to one.
+        for row in weights.row_iter() {
+            assert!((row.sum() - 1.0).abs() < TOLERANCE);
+        }
+        let evaluated = prepared.evaluate(&values, &target).unwrap();
+        let direct = Linear
+            .interpolate_values(&points, &values, &target)
+            .unwrap();
+        assert!(approx_eq(
+            evaluated.as_slice(),
+            direct.as_slice(),
+            TOLERANCE
+        ));
+        assert!(approx_eq(
+            &[evaluated[(0, 0)], evaluated[(1, 0)]],
+            &[2.5, 1.0],
+            TOLERANCE
+        ));
+
+        let nearest = NearestNeighbor.prepare(&points).unwrap();
+        let weights = nearest.weights(&target).unwrap();
+        assert!(approx_eq(
+            &weights.row(2).iter().copied().collect::<Vec<_>>(),
+            &[0.0, 1.0, 0.0, 0.0],
+            TOLERANCE
+        ));
+
+        let err = prepared
+            .evaluate(&values.rows(0, 3).into_owned(), &target)
+            .unwrap_err();
+        assert!(format!("{err:#}").contains("got 3 rows for 4 points"));
+        let Err(err) = Linear.prepare(&points[..1]) else {
+            panic!("a single point must not be prepared");
+        };
+        assert!(format!("{err:#}").contains("Not enough points"));
+    }
+
+    /// Points on a regular grid with `n` points per axis, which are cospherical in groups.
+    fn grid_points(n: usize, dimension: u32) -> Vec<Point> {
+        (0..n.pow(dimension))
+            .map(|index| {
+                let coordinates = (0..dimension)
+                    .map(|axis| ((index / n.pow(axis)) % n) as f64)
+                    .collect();
+                Point::new(None, coordinates)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_delaunay_triangulation_covers_hull() {
+        for (points, hull_volume, factorial) in [
+            (grid_points(3, 2), 4.0, 2.0),
+            (grid_points(3, 3), 8.0, 6.0),
+            (grid_points(4, 1), 3.0, 1.0),
+        ] {
+            let coordinates: Vec<DVector<f64>> = points
+                .iter()
+                .map(|p| DVector::from_column_slice(&p.coordinates))
+                .collect();
+            let simplices = triangulate(&coordinates).unwrap();
+            let volume: f64 = simplices
+                .iter()
+                .filter_map(|vertices| Simplex::new(&coordinates, vertices.clone()))
+                .map(|simplex| 1.0 / simplex.inverse.determinant().abs() / factorial)
+                .sum();
+            assert!((volume - hull_volume).abs() < 1e-9, "volume {volume}");
+        }
+    }
+
+    #[test]
+    fn test_delaunay_covers_thin_hull() {
+        // Points on a shallow convex arc: the triangles along it are slivers, and targets
+        // between the arc and its chord must all be inside a triangle.
+        let height = |x: f64| 0.5 * (1.0 - ((x - 50.0) / 50.0).powi(2));
+        let xs: Vec<f64> = (0..60).map(|i| 100.0 * i as f64 / 59.0).collect();
+        let points: Vec<Point> = xs
+            .iter()
+            .map(|&x| Point::new(None, vec![x, height(x)]))
+            .collect();
+        let mut target = Vec::new();
+        for pair in xs.windows(2) {
+            for step in 1..10 {
+                let x = pair[0] + (pair[1] - pair[0]) * step as f64 / 10.0;
+                // The hull between two arc points is bounded by their chord.
+                let top = height(pair[0])
+                    + (height(pair[1]) - height(pair[0])) * (x - pair[0]) / (pair[1] - pair[0]);
+                for fraction in [0.01, 0.3, 0.7, 0.99] {
+                    target.push(vec![x, top * fraction]);
+                }
+            }
+        }
+        let values = DMatrix::from_fn(points.len(), 1, |row, _| {
+            2.0 * points[row].coordinates[0] - 40.0 * points[row].coordinates[1]
+        });
+        let result = Delaunay {
+            extrapolation: Extrapolation::Error,
+        }
+        .interpolate_values(&points, &values, &target)
+        .unwrap();
+        for (row, target) in target.iter().enumerate() {
+            let expected = 2.0 * target[0] - 40.0 * target[1];
+            assert!((result[(row, 0)] - expected).abs() < 1e-6, "{target:?}");
+        }
+
+        // The arc extruded along a short third axis, with the points of the two layers offset.
+        let points: Vec<Point> = xs
+            .iter()
+            .enumerate()
+            .flat_map(|(i, &x)| {
+                let shifted = x + 0.5 * 100.0 / 59.0;
+                let mut layers = vec![Point::new(None, vec![x, height(x), 0.0])];
+                if i + 1 < xs.len() {
+                    layers.push(Point::new(None, vec![shifted, height(shifted), 0.2]));
+                }
+                layers
+            })
+            .collect();
+        // Inside the tetrahedron of two neighbouring points, the point between them in the
+        // other layer and the far end of the arc.
+        let target: Vec<Vec<f64>> = (0..xs.len() - 1)
+            .map(|i| {
+                let far = if i < xs.len() / 2 {
+                    2 * (xs.len() - 1)
+                } else {
+                    0
+                };
+                let corners = [2 * i, 2 * i + 2, 2 * i + 1, far];
+                (0..3)
+                    .map(|axis| {
+                        corners
+                            .iter()
+                            .zip([0.3, 0.3, 0.2, 0.2])
+                            .map(|(&corner, weight)| weight * points[corner].coordinates[axis])
+                            .sum()
+                    })
+                    .collect()
+            })
+            .collect();
+        let values = DMatrix::from_fn(points.len(), 1, |row, _| {
+            let [x, y, z] = [0, 1, 2].map(|axis| points[row].coordinates[axis]);
+            x - 20.0 * y + 5.0 * z
+        });
+        let result = Delaunay {
+            extrapolation: Extrapolation::Error,
+        }
+        .interpolate_values(&points, &values, &target)
+        .unwrap();
+        for (row, target) in target.iter().enumerate() {
+            let expected = target[0] - 20.0 * target[1] + 5.0 * target[2];
+            assert!((result[(row, 0)] - expected).abs() < 1e-6, "{target:?}");
+        }
+    }
+
+    #[test]
+    fn test_delaunay_interpolation() {
+        let points = grid_points(3, 2);
+        let delaunay = Delaunay {
+            extrapolation: Extrapolation::Error,
+        };
+        // A linear component is reproduced everywhere, a bilinear one at the points.
+        let values = DMatrix::from_fn(points.len(), 2, |row, column| {
+            let [x, y] = [points[row].coordinates[0], points[row].coordinates[1]];
+            if column == 0 {
+                3.0 * x - 2.0 * y + 1.0
+            } else {
+                x * y
+            }
+        });
+        let target = vec![vec![0.3, 1.7], vec![2.0, 2.0], vec![1.5, 0.5]];
+        let result = delaunay
+            .interpolate_values(&points, &values, &target)
+            .unwrap();
+        assert!(approx_eq(
+            &result.column(0).iter().copied().collect::<Vec<_>>(),
+            &[-1.5, 3.0, 4.5],
+            TOLERANCE
+        ));
+        assert!((result[(1, 1)] - 4.0).abs() < TOLERANCE);
+        // Inside the cell [1, 2] x [0, 1] the bilinear value lies between its corners.
+        assert!(result[(2, 1)] > 0.0 && result[(2, 1)] < 2.0);
+        let regression = Linear
+            .interpolate_values(&points, &values, &target)
+            .unwrap();
+        assert!((regression[(1, 1)] - 4.0).abs() > 0.1);
+
+        let outside = vec![vec![3.0, 1.0]];
+        let err = delaunay
+            .interpolate_values(&points, &values, &outside)
+            .unwrap_err();
+        assert!(format!("{err:#}").contains("outside the convex hull"));
+        let linear = Delaunay {
+            extrapolation: Extrapolation::Linear,
+        }
+        .interpolate_values(&points, &values, &outside)
+        .unwrap();
+        assert!((linear[(0, 0)] - 8.0).abs() < TOLERANCE);
+        let clamped = Delaunay {
+            extrapolation: Extrapolation::Clamp,
+        }
+        .interpolate_values(&points, &values, &outside)
+        .unwrap();
+        assert!((clamped[(0, 1)] - 2.0).abs() < TOLERANCE);
+
+        // Clamped targets move to the nearest point of the hull, on an edge or a vertex,
+        // which need not be a corner of the bounding box.
+        let triangle: Vec<Point> = [[0.0, 0.0], [1.0, 0.0], [0.0, 1.0]]
+            .iter()
+            .map(|c| Point::new(None, c.to_vec()))
+            .collect();
+        let sums = DMatrix::from_fn(3, 1, |row, _| triangle[row].coordinates.iter().sum());
+        let outside = vec![
+            vec![1.0, 1.0],
+            vec![-1.0, -2.0],
+            vec![3.0, -1.0],
+            vec![0.2, 0.3],
+        ];
+        let clamped = Delaunay {
+            extrapolation: Extrapolation::Clamp,
+        }
+        .interpolate_values(&triangle, &sums, &outside)
+        .unwrap();
+        assert!(approx_eq(
+            &clamped.column(0).iter().copied().collect::<Vec<_>>(),
+            &[1.0, 0.0, 1.0, 0.5],
+            TOLERANCE
+        ));
+
+        let collinear: Vec<Point> = (0..3)
+            .map(|i| Point::new(None, vec![i as f64, 2.0 * i as f64]))
+            .collect();
+        let Err(err) = delaunay.prepare(&collinear) else {
+            panic!("collinear points must not be triangulated");
+        };
+        assert!(format!("{err:#}").contains("must span 2 dimensions"));
+        let mut duplicate = points.clone();
+        duplicate.push(points[4].clone());
+        let Err(err) = delaunay.prepare(&duplicate) else {
+            panic!("duplicate points must not be triangulated");
+        };
+        assert!(format!("{err:#}").contains("is defined twice"));
+    }
+
+    #[test]
+    fn test_delaunay_walk_finds_enclosing_simplex() {
+        use rand::{rngs::StdRng, Rng, SeedableRng};
+        let mut rng = StdRng::seed_from_u64(43);
+        let mut points = grid_points(2, 3);
+        points.extend(
+            (0..300).map(|_| Point::new(None, (0..3).map(|_| rng.gen_range(0.01..0.99)).collect())),
+        );
+        // Targets scattered at random, so consecutive walks cross many simplices.
+        let target: Vec<Vec<f64>> = (0..500)
+            .map(|_| (0..3).map(|_| rng.gen_range(0.0..1.0)).collect())
+            .collect();
+        let weights = Delaunay {
+            extrapolation: Extrapolation::Error,
+        }
+        .prepare(&points)
+        .unwrap()
+        .weights(&target)
+        .unwrap();
+        for (row, target) in weights.row_iter().zip(target.iter()) {
+            assert!(row.iter().all(|&w| w >= -1e-9), "target {target:?}");
+            assert!((row.sum() - 1.0).abs() < 1e-9);
+            // Barycentric weights reproduce the target from the points.
+            let coordinates: Vec<f64> = (0..3)
+                .map(|axis| {
+                    row.iter()
+                        .zip(points.iter())
+                        .map(|(w, p)| w * p.coordinates[axis])
+                        .sum()
+                })
+                .collect();
+            assert!(approx_eq(&coordinates, target, 1e-9), "target {target:?}");
+        }
+    }
+
+    fn grid(order: GridOrder, extrapolation: Extrapolation) -> Grid {
+        Grid {
+            order,
+            extrapolation,
+            axes: None,
+        }
+    }
+
+    #[test]
+    fn test_grid_interpolation() {
+        // Points of a 3 x 3 grid in reverse order, with the values x * y and 2x - y.
+        let mut points = grid_points(3, 2);
+        points.reverse();
+        let values = DMatrix::from_fn(points.len(), 2, |row, column| {
+            let [x, y] = [points[row].coordinates[0], points[row].coordinates[1]];
+            if column == 0 {
+                x * y
+            } else {
+                2.0 * x - y
+            }
+        });
+        let target = vec![vec![0.5, 1.5], vec![1.25, 0.75], vec![2.0, 2.0]];
+        for order in [GridOrder::Linear, GridOrder::Cubic] {
+            let result = grid(order, Extrapolation::Error)
+                .interpolate_values(&points, &values, &target)
+                .unwrap();
+            assert!(approx_eq(
+                result.as_slice(),
+                &[0.75, 0.9375, 4.0, -0.5, 1.75, 2.0],
+                TOLERANCE
+            ));
+        }
+
+        let outside = vec![vec![3.0, 1.0]];
+        let err = grid(GridOrder::Linear, Extrapolation::Error)
+            .interpolate_values(&points, &values, &outside)
+            .unwrap_err();
+        assert!(format!("{err:#}").contains("coordinate 3 of axis 0 is outside the grid [0, 2]"));
+        for (extrapolation, expected) in [
+            (Extrapolation::Clamp, [2.0, 3.0]),
+            (Extrapolation::Linear, [3.0, 5.0]),
+        ] {
+            for order in [GridOrder::Linear, GridOrder::Cubic] {
+                let result = grid(order, extrapolation)
+                    .interpolate_values(&points, &values, &outside)
+                    .unwrap();
+                assert!(approx_eq(result.as_slice(), &expected, TOLERANCE));
+            }
+        }
+    }
+
+    #[test]
+    fn test_grid_cubic_spline() {
+        // A natural spline is closer to a smooth curve than the piecewise-linear interpolant.
+        let points: Vec<Point> = (0..7).map(|i| Point::new(None, vec![i as f64])).collect();
+        let values = DMatrix::from_fn(7, 1, |row, _| (row as f64 / 2.0).sin());
+        let target = vec![vec![2.5], vec![0.0], vec![6.0]];
+        let linear = grid(GridOrder::Linear, Extrapolation::Error)
+            .interpolate_values(&points, &values, &target)
+            .unwrap();
+        let cubic = grid(GridOrder::Cubic, Extrapolation::Error)
+            .interpolate_values(&points, &values, &target)
+            .unwrap();
+        let exact = 1.25f64.sin();
+        assert!((cubic[(0, 0)] - exact).abs() < 0.1 * (linear[(0, 0)] - exact).abs());
+        assert!(approx_eq(
+            &[cubic[(1, 0)], cubic[(2, 0)]],
+            &[0.0, 3.0f64.sin()],
+            TOLERANCE
+        ));
+    }
+
+    #[test]
+    fn test_grid_errors() {
+        let strategy = grid(GridOrder::Linear, Extrapolation::Error);
+        let mut points = grid_points(3, 2);
+        points.remove(7);
+        let Err(err) = strategy.prepare(&points) else {
+            panic!("an incomplete grid must not be prepared");
+        };
+        assert!(format!("{err:#}").contains("the grid is missing 1 of 9 nodes, e.g. [1.0, 2.0]"));
+
+        let explicit = Grid {
+            axes: Some(vec![vec![0.0, 2.0], vec![0.0, 1.0, 2.0]]),
+            ..strategy
+        };
+        let Err(err) = explicit.prepare(&grid_points(3, 2)) else {
+            panic!("points off the grid must not be prepared");
+        };
+        assert!(format!("{err:#}").contains("point [1.0, 0.0] is not on the grid"));
+
+        let line: Vec<Point> = (0..3)
+            .map(|i| Point::new(None, vec![i as f64, 1.0]))
+            .collect();
+        let Err(err) = explicit.prepare(&line) else {
+            panic!("points off the grid must not be prepared");
+        };
+        assert!(format!("{err:#}").contains("is not on the grid"));
+        let Err(err) = grid(GridOrder::Cubic, Extrapolation::Error).prepare(&line) else {
+            panic!("a grid with a single value on an axis must not be prepared");
+        };
+        assert!(format!("{err:#}").contains("axis 1 of the grid needs at least 2 values"));
+    }
+
+    #[test]
+    fn test_rbf_interpolation() {
+        // Scattered points with a nonlinear and a linear component.
+        let coordinates = [
+            [0.0, 0.0],
+            [1.0, 0.2],
+            [0.3, 1.0],
+            [1.2, 1.1],
+            [0.6, 0.5],
+            [2.0, 0.7],
+        ];
+        let points: Vec<Point> = coordinates
+            .iter()
+            .map(|c| Point::new(None, c.to_vec()))
+            .collect();
+        let values = DMatrix::from_fn(points.len(), 2, |row, column| {
+            let [x, y] = coordinates[row];
+            if column == 0 {
+                (x * y).sin() + x * x
+            } else {
+                4.0 * x - 3.0 * y + 2.0
+            }
+        });
+        let at_points: Vec<Vec<f64>> = coordinates.iter().map(|c| c.to_vec()).collect();
+        let target = vec![vec![0.5, 0.8], vec![1.5, 0.3]];
+        for kernel in [
+            RbfKernel::ThinPlate,
+            RbfKernel::Multiquadric,
+            RbfKernel::Gaussian,
+        ] {
+            let strategy = Rbf {
+                kernel,
+                shape: 1.0,
+                polynomial: true,
+            };
+            let result = strategy
+                .interpolate_values(&points, &values, &at_points)
+                .unwrap();
+            assert!(approx_eq(result.as_slice(), values.as_slice(), 1e-8));
+            // The polynomial tail reproduces linear values anywhere.
+            let result = strategy
+                .interpolate_values(&points, &values, &target)
+                .unwrap();
+            assert!(approx_eq(
+                &result.column(1).iter().copied().collect::<Vec<_>>(),
+                &[1.6, 7.1],
+                1e-8
+            ));
+        }
+        let gaussian = Rbf {
+            kernel: RbfKernel::Gaussian,
+            shape: 2.0,
+            polynomial: false,
+        };
+        let result = gaussian
+            .interpolate_values(&points, &values, &at_points)
+            .unwrap();
+        assert!(approx_eq(result.as_slice(), values.as_slice(), 1e-8));
+
+        let thin_plate = Rbf {
+            kernel: RbfKernel::ThinPlate,
+            shape: 1.0,
+            polynomial: true,
+        };
+        let collinear: Vec<Point> = (0..4)
+            .map(|i| Point::new(None, vec![i as f64, i as f64]))
+            .collect();
+        let Err(err) = thin_plate.prepare(&collinear) else {
+            panic!("collinear points must not determine a planar polynomial");
+        };
+        assert!(format!("{err:#}").contains("the RBF system of the points is singular"));
+
+        // A wide Gaussian is nearly flat over the points, so its system is ill-conditioned
+        // long before it is singular.
+        let flat = Rbf {
+            kernel: RbfKernel::Gaussian,
+            shape: 0.005,
+            polynomial: false,
+        };
+        let Err(err) = flat.prepare(&points) else {
+            panic!("an ill-conditioned system must not be solved");
+        };
+        assert!(
+            format!("{err:#}").contains("the RBF system of the points is ill-conditioned"),
+            "{err:#}"
+        );
+    }
+
+    #[test]
+    fn test_inverse_distance_interpolation() {
+        let points: Vec<Point> = [0.0, 1.0, 3.0]
+            .iter()
+            .map(|x| Point::new(None, vec![*x]))
+            .collect();
+        let values = DMatrix::from_column_slice(3, 1, &[0.0, 2.0, 6.0]);
+        let target = vec![vec![1.0], vec![2.0], vec![10.0]];
+        let result = InverseDistance {
+            power: 2.0,
+            neighbors: None,
+        }
+        .interpolate_values(&points, &values, &target)
+        .unwrap();
+        // At x = 2 the weights are 1/4, 1 and 1 before normalization.
+        assert!(approx_eq(
+            &[result[(0, 0)], result[(1, 0)]],
+            &[2.0, 32.0 / 9.0],
+            TOLERANCE
+        ));
+        assert!(result[(2, 0)] > 2.0 && result[(2, 0)] < 6.0);
+        let weights = InverseDistance {
+            power: 1.0,
+            neighbors: None,
+        }
+        .prepare(&points)
+        .unwrap()
+        .weights(&target)
+        .unwrap();
+        for row in weights.row_iter() {
+            assert!((row.sum() - 1.0).abs() < TOLERANCE);
+        }
+    }
+
+    /// The `k` nearest points of a linear scan, equally distant ones by index.
+    fn scan(coordinates: &[Vec<f64>], target: &[f64], k: usize) -> Vec<usize> {
+        let mut distances: Vec<(f64, usize)> = coordinates
+            .iter()
+            .enumerate()
+            .map(|(index, point)| {
+                let distance2 = point.iter().zip(target).map(|(a, b)| (a - b).powi(2)).sum();
+                (distance2, index)
+            })
+            .collect();
+        distances.sort_by(|a, b| a.0.total_cmp(&b.0).then(a.1.cmp(&b.1)));
+        distances
+            .into_iter()
+            .take(k)
+            .map(|(_, index)| index)
+            .collect()
+    }
+
+    #[test]
+    fn test_kd_tree_matches_linear_scan() {
+        use rand::{rngs::StdRng, Rng, SeedableRng};
+        let mut rng = StdRng::seed_from_u64(46);
+        let random: Vec<Vec<f64>> = (0..500)
+            .map(|_| (0..3).map(|_| rng.gen_range(-10.0..10.0)).collect())
+            .collect();
+        // A grid has many equally distant points.
+        let grid: Vec<Vec<f64>> = grid_points(5, 3)
+            .into_iter()
+            .map(|p| p.coordinates)
+            .collect();
+        for coordinates in [random, grid] {
+            let tree = KdTree::new(coordinates.clone());
+            assert_eq!(tree.len(), coordinates.len());
+            for _ in 0..200 {
+                let target: Vec<f64> = (0..3)
+                    .map(|_| rng.gen_range(-1.0..5.0_f64).round())
+                    .collect();
+                for k in [1, 4, 9] {
+                    let nearest: Vec<usize> =
+                        tree.k_nearest(&target, k).iter().map(|n| n.0).collect();
+                    assert_eq!(nearest, scan(&coordinates, &target, k), "target {target:?}");
+                }
+                // Missing target coordinates are ignored.
+                let (index, _) = tree.nearest(&target[..2]).unwrap();
+                assert_eq!(index, scan(&coordinates, &target[..2], 1)[0]);
+            }
+        }
+        assert!(KdTree::new(Vec::new()).nearest(&[0.0]).is_none());
+    }
+
+    #[test]
+    fn test_inverse_distance_neighbors_and_cache() {
+        let strategy = InterpolationStrategyEnum::InverseDistance(InverseDistance {
+            power: 2.0,
+            neighbors: Some(2),
+        });
+        let mut interpolation = NDInterpolation::new(&strategy);
+        for (x, value) in [(0.0, 0.0), (1.0, 2.0), (3.0, 6.0)] {
+            interpolation.add_point(Point::new(None, vec![x]), value);
+        }
+        // Only the points at 1 and 3 are weighted at 2.
+        let result = interpolation.interpolate(&[vec![2.0]]).unwrap();
+        assert!(approx_eq(&result, &[4.0], TOLERANCE));
+        // Adding a point prepares the interpolation again.
+        interpolation.add_point(Point::new(None, vec![2.5]), 10.0);
+        let result = interpolation.interpolate(&[vec![2.0]]).unwrap();
+        assert!(approx_eq(&result, &[(2.0 + 40.0) / 5.0], TOLERANCE));
+        // A point sharing coordinates is an error by default.
+        interpolation.add_point(Point::new(None, vec![2.5]), 0.0);
+        let err = interpolation.interpolate(&[vec![2.0]]).unwrap_err();
+        assert!(format!("{err:#}").contains("share coordinates [2.5]"));
+    }
+
+    #[test]
+    fn test_diagnostics() {
+        // Exactly linear values: no residuals and no leave-one-out errors.
+        let points = grid_points(3, 2);
+        let values = DMatrix::from_fn(points.len(), 1, |row, _| {
+            2.0 * points[row].coordinates[0] - points[row].coordinates[1]
+        });
+        let diagnostics = Linear.diagnostics(&points, &values).unwrap();
+        assert!(diagnostics.max_residual() < 1e-9);
+        assert!(diagnostics.max_leave_one_out().unwrap() < 1e-9);
+        let conditioning = diagnostics.conditioning.unwrap();
+        assert_eq!((conditioning.rank, conditioning.columns), (3, 3));
+        assert!(conditioning.condition.is_finite());
+        assert!(diagnostics.duplicates.is_empty());
+
+        // A grid cannot be prepared without one of its nodes.
+        let grid = grid(GridOrder::Linear, Extrapolation::Error);
+        let diagnostics = grid.diagnostics(&points, &values).unwrap();
+        assert!(diagnostics.max_residual() < 1e-9);
+        assert!(diagnostics.leave_one_out.iter().all(Option::is_none));
+        assert!(diagnostics.max_leave_one_out().is_none());
+        assert!(diagnostics.conditioning.is_none());
+
+        // A repeated point cannot be fitted with both of its values.
+        let mut repeated = vec![points[0].clone(), points[1].clone(), points[3].clone()];
+        repeated.push(Point::new(Some("again".to_string()), vec![1.0, 0.0]));
+        let values = DMatrix::from_column_slice(4, 1, &[0.0, 1.0, 2.0, 3.0]);
+        let diagnostics = Linear.diagnostics(&repeated, &values).unwrap();
+        assert_eq!(diagnostics.duplicates.len(), 1);
+        assert_eq!(diagnostics.duplicates[0].1.file.as_deref(), Some("again"));
+        assert_eq!(diagnostics.conditioning.unwrap().rank, 3);
+        assert!((diagnostics.max_residual() - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_point_hash_matches_eq() {
+        use std::collections::hash_map::DefaultHasher;
+        let hash = |point: &Point| {
+            let mut hasher = DefaultHasher::new();
+            point.hash(&mut hasher);
+            hasher.finish()
+        };
+        for (a, b) in [
+            (0.0, -0.0),
+            (0.0, 0.000004),
+            (0.0000049, 0.0000051),
+            (1.0, 1.000004),
+            (1.0, 1.00002),
+        ] {
+            let a = Point::new(None, vec![a, 2.0]);
+            let b = Point::new(None, vec![b, 2.0]);
+            assert_eq!(a == b, hash(&a) == hash(&b), "{a:?} and {b:?}");
+        }
+        assert_ne!(
+            Point::new(None, vec![1.0]),
+            Point::new(None, vec![1.0, 0.0])
+        );
+    }
+
+    #[test]
+    fn test_duplicate_points() {
+        // Points within the tolerance of each other are grouped even if they round apart,
+        // and chains of them join the group of their first point.
+        let points: Vec<Point> = [0.0, 1.0, 0.0000049, 0.0000051, 1.0000099, 1.0000198]
+            .iter()
+            .map(|x| Point::new(Some(format!("{x}")), vec![*x, 0.0]))
+            .collect();
+        assert_eq!(duplicate_groups(&points), vec![0, 1, 0, 0, 1, 1]);
+
+        let values = DMatrix::from_column_slice(6, 1, &[0.0, 1.0, 2.0, 4.0, 3.0, 5.0]);
+        let err = merge_duplicates(&points, &values, DuplicatePoints::Error).unwrap_err();
+        assert!(format!("{err:#}").contains("points 0 and 0.0000049 share coordinates"));
+        for (duplicates, expected) in [
+            (DuplicatePoints::First, [0.0, 1.0]),
+            (DuplicatePoints::Last, [4.0, 5.0]),
+            (DuplicatePoints::Average, [2.0, 3.0]),
+        ] {
+            let merged = merge_duplicates(&points, &values, duplicates).unwrap();
+            assert_eq!(merged.points.len(), 2);
+            assert_eq!(merged.points[1].file.as_deref(), Some("1"));
+            assert!(approx_eq(merged.values.as_slice(), &expected, TOLERANCE));
+            assert_eq!(merged.duplicates.len(), 4);
+        }
+
+        // Interpolations merge points by their policy when interpolating.
+        let strategy = InterpolationStrategyEnum::Linear(Linear);
+        let mut interpolation =
+            NDInterpolation::with_duplicates(&strategy, DuplicatePoints::Average);
+        for (point, value) in points.iter().zip(values.iter()) {
+            interpolation.add_point(Point::new(None, vec![point.coordinates[0]]), *value);
+        }
+        let result = interpolation.interpolate(&[vec![0.5]]).unwrap();
+        assert!(approx_eq(&result, &[2.5], TOLERANCE));
+        assert_eq!(interpolation.diagnostics().unwrap().duplicates.len(), 4);
+    }
+
+    #[test]
+    fn test_affine_span() {
+        let line: Vec<Point> = (0..4)
+            .map(|i| Point::new(None, vec![i as f64, 2.0 * i as f64, 1.0]))
+            .collect();
+        assert_eq!(affine_span(&line), 1);
+        assert_eq!(affine_span(&line[..1]), 0);
+        assert_eq!(affine_span(&[]), 0);
+        assert_eq!(affine_span(&grid_points(3, 2)), 2);
+        let mut plane = grid_points(2, 3);
+        plane.retain(|p| p.coordinates[2] == 0.0);
+        assert_eq!(affine_span(&plane), 2);
+    }
+
+    #[test]
+    fn test_kd_tree_within() {
+        let points: Vec<Vec<f64>> = (0..100)
+            .map(|i| vec![(i % 10) as f64, (i / 10) as f64])
+            .collect();
+        let tree = KdTree::new(points.clone());
+        for (target, radius) in [
+            (vec![4.5, 4.5], 1.0),
+            (vec![0.0, 0.0], 2.0),
+            (vec![20.0, 0.0], 1.0),
+        ] {
+            let mut expected: Vec<usize> = (0..points.len())
+                .filter(|i| {
+                    let distance2: f64 = points[*i]
+                        .iter()
+                        .zip(&target)
+                        .map(|(a, b)| (a - b).powi(2))
+                        .sum();
+                    distance2 <= radius * radius
+                })
+                .collect();
+            let mut found: Vec<usize> = tree.within(&target, radius).iter().map(|n| n.0).collect();
+            expected.sort();
+            found.sort();
+            assert_eq!(found, expected);
+        }
+    }
+
+    #[test]
+    fn test_bounding_box() {
+        let bounds = BoundingBox::new(&grid_points(3, 2)).unwrap();
+        assert_eq!(
+            (bounds.min.clone(), bounds.max.clone()),
+            (vec![0.0; 2], vec![2.0; 2])
+        );
+        assert!(bounds.contains(&[2.000001, 0.0]));
+        assert!(!bounds.contains(&[2.0001, 0.0]));
+        let targets = vec![vec![1.0, 1.0], vec![-1.0, 3.0], vec![2.5, 0.5]];
+        for outside in [Outside::Allow, Outside::Warn] {
+            let mut restricted = targets.clone();
+            assert_eq!(bounds.restrict(&mut restricted, outside).unwrap(), 2);
+            assert_eq!(restricted, targets);
+        }
+        let mut clamped = targets.clone();
+        assert_eq!(bounds.restrict(&mut clamped, Outside::Clamp).unwrap(), 2);
+        assert_eq!(
+            clamped,
+            vec![vec![1.0, 1.0], vec![0.0, 2.0], vec![2.0, 0.5]]
+        );
+        let err = bounds
+            .restrict(&mut targets.clone(), Outside::Error)
+            .unwrap_err();
+        assert!(format!("{err:#}").contains("target 1 at [-1.0, 3.0] is outside"));
+        assert!(BoundingBox::new(&[]).is_err());
+    }
 }
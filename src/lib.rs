@@ -4,13 +4,25 @@ pub mod interpolate;
 pub mod rainflow;
 pub use interpolate::{InterpolationStrategy, Linear, NDInterpolation};
 #[cfg(feature = "cli")]
+pub mod assessment;
+#[cfg(feature = "cli")]
 pub mod config;
 #[cfg(feature = "cli")]
+pub mod expression;
+#[cfg(feature = "cli")]
 pub mod material;
 #[cfg(feature = "cli")]
+pub mod notch;
+#[cfg(feature = "cli")]
+pub mod report;
+#[cfg(feature = "cli")]
+pub mod spectral;
+#[cfg(feature = "cli")]
 pub mod stress;
 #[cfg(feature = "cli")]
 pub mod timeseries;
+#[cfg(feature = "cli")]
+pub mod units;
 
 #[cfg(feature = "wasm")]
 use wasm_bindgen::prelude::*;
@@ -19,9 +31,12 @@ use wasm_bindgen::prelude::*;
 #[cfg(feature = "wasm")]
 #[wasm_bindgen]
 pub fn run_rainflow(stress: &[f64]) -> Vec<f64> {
-    let (means, ranges) = rainflow::rainflow(stress);
+    let cycles = rainflow::rainflow(stress);
+    // Half cycles report half their range, so ranges sum to full-cycle equivalents.
+    let means = cycles.iter().map(|c| c.mean);
+    let ranges = cycles.iter().map(|c| c.range * c.count);
     // Combine the means and ranges into a single Vec to return.
     // This is just one way to handle the return; you might choose a different method
     // depending on how you want to process the data on the JavaScript side.
-    means.into_iter().chain(ranges).collect()
+    means.chain(ranges).collect()
 }
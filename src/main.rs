@@ -2,6 +2,8 @@
 #[cfg(feature = "cli")]
 mod app_logic;
 #[cfg(feature = "cli")]
+pub mod assessment;
+#[cfg(feature = "cli")]
 pub mod config;
 #[cfg(any(feature = "cli", feature = "wasm"))]
 pub mod interpolate;
@@ -11,11 +13,21 @@ pub mod rainflow;
 pub mod stress;
 pub use interpolate::{InterpolationStrategy, Linear, NDInterpolation};
 
+#[cfg(feature = "cli")]
+pub mod expression;
 #[cfg(feature = "cli")]
 pub mod material;
 #[cfg(feature = "cli")]
+pub mod notch;
+#[cfg(feature = "cli")]
+pub mod report;
+#[cfg(feature = "cli")]
+pub mod spectral;
+#[cfg(feature = "cli")]
 pub mod timeseries;
 #[cfg(feature = "cli")]
+pub mod units;
+#[cfg(feature = "cli")]
 use clap::{Arg, Command};
 
 #[cfg(feature = "cli")]
@@ -2,6 +2,11 @@
 
 use anyhow::{anyhow, Result};
 use serde::Deserialize;
+
+/// The natural logarithm of the most reversals to failure `StrainLife` resolves, beyond
+/// which a strain range does no damage.
+const MAX_LOG_REVERSALS: f64 = 100.0;
+
 /// Represents material properties used in structural analysis.
 ///
 /// Includes material's mechanical properties such as Young's modulus, Poisson's ratio,
@@ -21,6 +26,12 @@ pub struct Material {
     pub ultimate_stress: f64,
     /// Fatigue characteristics of the material.
     pub fatigue: Fatigue,
+    /// Optional cyclic stress-strain curve used for notch plasticity correction.
+    pub cyclic: Option<CyclicCurve>,
+    /// Optional strain-life curve used for the local strains of "STRAIN" mode.
+    pub strain_life: Option<StrainLife>,
+    /// Optional thickness correction of the S-N curve for node sets with a `thickness`.
+    pub thickness: Option<ThicknessCorrection>,
 }
 
 impl Material {
@@ -60,10 +71,242 @@ impl Material {
             ));
         }
         self.fatigue.validate()?;
+        if let Some(cyclic) = &self.cyclic {
+            cyclic.validate()?;
+        }
+        if let Some(strain_life) = &self.strain_life {
+            strain_life.validate()?;
+        }
+        if let Some(thickness) = &self.thickness {
+            thickness.validate()?;
+        }
         Ok(())
     }
 }
 
+/// Represents the thickness correction of an S-N curve, as for welded joints.
+///
+/// Stress ranges of members thicker than the reference thickness are scaled up by
+/// `(t / reference)^exponent`; thinner members are not corrected.
+#[derive(Debug, Deserialize)]
+pub struct ThicknessCorrection {
+    /// The reference thickness of the S-N curve, in the same unit as the set thickness.
+    pub reference: f64,
+    /// The thickness exponent, typically between 0.1 and 0.3.
+    pub exponent: f64,
+}
+
+impl ThicknessCorrection {
+    /// Validates that `reference` is greater than 0.0 and `exponent` is between 0.0 and 1.0.
+    pub fn validate(&self) -> Result<()> {
+        if self.reference <= 0.0 {
+            return Err(anyhow!(
+                "reference must be greater than 0.0, got {}",
+                self.reference
+            ));
+        }
+        if !(0.0..=1.0).contains(&self.exponent) {
+            return Err(anyhow!(
+                "exponent must be between 0.0 and 1.0, got {}",
+                self.exponent
+            ));
+        }
+        Ok(())
+    }
+
+    /// Returns the factor applied to the stress ranges of a member of the given thickness.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fatigue::material::ThicknessCorrection;
+    ///
+    /// let correction = ThicknessCorrection { reference: 25.0, exponent: 0.2 };
+    /// assert_eq!(correction.factor(10.0), 1.0);
+    /// assert!((correction.factor(50.0) - 2.0_f64.powf(0.2)).abs() < 1e-12);
+    /// ```
+    pub fn factor(&self, thickness: f64) -> f64 {
+        if thickness > self.reference {
+            (thickness / self.reference).powf(self.exponent)
+        } else {
+            1.0
+        }
+    }
+}
+
+/// Represents the Ramberg–Osgood cyclic stress-strain curve of a material.
+///
+/// The stabilized cyclic curve is `ε = σ/E + (σ/K')^(1/n')`. Hysteresis branches
+/// follow Masing's hypothesis, i.e. the cyclic curve scaled by a factor of two.
+#[derive(Debug, Deserialize)]
+pub struct CyclicCurve {
+    /// The cyclic strength coefficient `K'` in the same units as the stresses.
+    pub k_prime: f64,
+    /// The cyclic strain hardening exponent `n'`.
+    pub n_prime: f64,
+}
+
+impl CyclicCurve {
+    /// Validates the `CyclicCurve` struct's fields based on custom logic.
+    ///
+    /// Ensures that both `k_prime` and `n_prime` are greater than 0.0.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Ok(())` if both `k_prime` and `n_prime` are valid. Otherwise, returns a `ValidationError`
+    /// with a detailed message about the validation failure.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fatigue::material::CyclicCurve;
+    ///
+    /// let curve = CyclicCurve { k_prime: 1100.0, n_prime: 0.15 };
+    /// assert!(curve.validate().is_ok());
+    ///
+    /// let invalid_curve = CyclicCurve { k_prime: 1100.0, n_prime: 0.0 };
+    /// assert!(invalid_curve.validate().is_err());
+    /// ```
+    pub fn validate(&self) -> Result<()> {
+        if self.k_prime <= 0.0 {
+            return Err(anyhow!(
+                "k_prime must be greater than 0.0, got {}",
+                self.k_prime
+            ));
+        }
+        if self.n_prime <= 0.0 {
+            return Err(anyhow!(
+                "n_prime must be greater than 0.0, got {}",
+                self.n_prime
+            ));
+        }
+        Ok(())
+    }
+
+    /// Returns the total strain on the cyclic curve for a given stress.
+    ///
+    /// The curve is antisymmetric, so negative stresses yield negative strains.
+    pub fn strain(&self, stress: f64, youngs_modulus: f64) -> f64 {
+        let plastic = (stress.abs() / self.k_prime).powf(1.0 / self.n_prime);
+        stress / youngs_modulus + plastic.copysign(stress)
+    }
+
+    /// Returns the total strain range of a Masing hysteresis branch for a given stress range.
+    pub fn branch_strain(&self, stress_range: f64, youngs_modulus: f64) -> f64 {
+        2.0 * self.strain(stress_range / 2.0, youngs_modulus)
+    }
+}
+
+/// Represents the strain-life curve of a material, Basquin's elastic and Coffin–Manson's
+/// plastic line.
+///
+/// The strain amplitude at `2N` reversals to failure is
+/// `εa = σf'/E (2N)^b + εf' (2N)^c`. Mean stresses are not corrected.
+#[derive(Debug, Deserialize)]
+pub struct StrainLife {
+    /// The fatigue strength coefficient `σf'` in the same units as the stresses.
+    pub sigma_f: f64,
+    /// The fatigue strength exponent `b`.
+    pub b: f64,
+    /// The fatigue ductility coefficient `εf'`.
+    pub epsilon_f: f64,
+    /// The fatigue ductility exponent `c`.
+    pub c: f64,
+}
+
+impl StrainLife {
+    /// Validates that `sigma_f` and `epsilon_f` are greater than 0.0 and `b` and `c` are less
+    /// than 0.0.
+    pub fn validate(&self) -> Result<()> {
+        if !self.sigma_f.is_finite() || self.sigma_f <= 0.0 {
+            return Err(anyhow!(
+                "sigma_f must be greater than 0.0, got {}",
+                self.sigma_f
+            ));
+        }
+        if !self.epsilon_f.is_finite() || self.epsilon_f <= 0.0 {
+            return Err(anyhow!(
+                "epsilon_f must be greater than 0.0, got {}",
+                self.epsilon_f
+            ));
+        }
+        if !self.b.is_finite() || self.b >= 0.0 {
+            return Err(anyhow!("b must be less than 0.0, got {}", self.b));
+        }
+        if !self.c.is_finite() || self.c >= 0.0 {
+            return Err(anyhow!("c must be less than 0.0, got {}", self.c));
+        }
+        Ok(())
+    }
+
+    /// Returns the strain amplitude at the given number of reversals to failure `2N`.
+    pub fn strain_amplitude(&self, reversals: f64, youngs_modulus: f64) -> f64 {
+        self.sigma_f / youngs_modulus * reversals.powf(self.b)
+            + self.epsilon_f * reversals.powf(self.c)
+    }
+
+    /// Returns the number of cycles to failure for a given total strain range.
+    ///
+    /// Strain amplitudes beyond the curve at the first reversal fail in half a cycle; a
+    /// zero range does no damage and returns `f64::INFINITY`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fatigue::material::StrainLife;
+    ///
+    /// let curve = StrainLife { sigma_f: 900.0, b: -0.09, epsilon_f: 0.3, c: -0.55 };
+    /// let amplitude = curve.strain_amplitude(2.0e5, 210000.0);
+    /// let cycles = curve.cycles_to_failure(2.0 * amplitude, 210000.0);
+    /// assert!((cycles / 1.0e5 - 1.0).abs() < 1e-9);
+    /// assert!(curve.cycles_to_failure(0.0, 210000.0).is_infinite());
+    /// ```
+    pub fn cycles_to_failure(&self, strain_range: f64, youngs_modulus: f64) -> f64 {
+        let amplitude = strain_range / 2.0;
+        if amplitude <= 0.0 {
+            return f64::INFINITY;
+        }
+        // The amplitude decreases with the reversals, so bisect on their logarithm.
+        let excess = |log: f64| self.strain_amplitude(log.exp(), youngs_modulus) - amplitude;
+        let (mut low, mut high) = (0.0, MAX_LOG_REVERSALS);
+        if excess(low) <= 0.0 {
+            return 0.5;
+        }
+        if excess(high) > 0.0 {
+            return f64::INFINITY;
+        }
+        while high - low > 1e-12 * MAX_LOG_REVERSALS {
+            let mid = 0.5 * (low + high);
+            if excess(mid) > 0.0 {
+                low = mid;
+            } else {
+                high = mid;
+            }
+        }
+        (0.5 * (low + high)).exp() / 2.0
+    }
+
+    /// Returns the Palmgren-Miner damage sum of the given strain ranges and cycle counts.
+    ///
+    /// # Returns
+    ///
+    /// Returns an error if `ranges` and `counts` differ in length.
+    pub fn damage(&self, ranges: &[f64], counts: &[f64], youngs_modulus: f64) -> Result<f64> {
+        if ranges.len() != counts.len() {
+            return Err(anyhow!(
+                "ranges and counts must have the same length, got {} and {}",
+                ranges.len(),
+                counts.len()
+            ));
+        }
+        Ok(ranges
+            .iter()
+            .zip(counts)
+            .map(|(&range, &count)| count / self.cycles_to_failure(range, youngs_modulus))
+            .sum())
+    }
+}
+
 /// Represents the fatigue parameters of a material in a structural analysis application.
 ///
 /// Includes parameters for the slope of the S-N curve, the knee point of the curve, and the cutoff limits.
@@ -92,6 +335,57 @@ impl Fatigue {
         self.cutoff.validate()?;
         Ok(())
     }
+
+    /// Returns the number of cycles to failure for a given stress range.
+    ///
+    /// The S-N curve passes through the knee point with slope `m1` above and `m2` below
+    /// the knee stress. Stress ranges below `cutoff.min` do not cause damage and return
+    /// `f64::INFINITY`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fatigue::material::{Cutoff, Fatigue, Knee, Slope};
+    ///
+    /// let fatigue = Fatigue {
+    ///     slope: Slope { m1: 3, m2: 5 },
+    ///     knee: Knee { cycle: 5000000, stress: 52.0 },
+    ///     cutoff: Cutoff { max: 440.0, min: 1.0 },
+    /// };
+    /// assert!((fatigue.cycles_to_failure(104.0) - 625000.0).abs() < 1e-6);
+    /// assert!(fatigue.cycles_to_failure(0.5).is_infinite());
+    /// ```
+    pub fn cycles_to_failure(&self, stress_range: f64) -> f64 {
+        if stress_range <= 0.0 || stress_range < self.cutoff.min {
+            return f64::INFINITY;
+        }
+        let slope = if stress_range >= self.knee.stress {
+            self.slope.m1
+        } else {
+            self.slope.m2
+        };
+        self.knee.cycle as f64 * (self.knee.stress / stress_range).powi(slope)
+    }
+
+    /// Returns the Palmgren-Miner damage sum of the given stress ranges and cycle counts.
+    ///
+    /// # Returns
+    ///
+    /// Returns an error if `ranges` and `counts` differ in length.
+    pub fn damage(&self, ranges: &[f64], counts: &[f64]) -> Result<f64> {
+        if ranges.len() != counts.len() {
+            return Err(anyhow!(
+                "ranges and counts must have the same length, got {} and {}",
+                ranges.len(),
+                counts.len()
+            ));
+        }
+        Ok(ranges
+            .iter()
+            .zip(counts)
+            .map(|(&range, &count)| count / self.cycles_to_failure(range))
+            .sum())
+    }
 }
 
 /// Represents the slope parameters of the S-N curve for fatigue analysis.
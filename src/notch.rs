@@ -0,0 +1,366 @@
+//! A module for notch plasticity correction of linear-elastic stress histories.
+//!
+//! Linear-elastic FE stresses overestimate the local stress at notches once the
+//! material yields. The rules in this module estimate the local elastic-plastic
+//! stress and strain at the notch root from the elastic (pseudo) stress using the
+//! material's Ramberg–Osgood cyclic curve, Masing hysteresis branches and the
+//! material memory effect, so the resulting local strain cycles can feed a
+//! strain-life assessment.
+use crate::material::CyclicCurve;
+use crate::rainflow::reversals;
+use anyhow::{anyhow, Result};
+
+const MAX_ITERATIONS: usize = 200;
+const RELATIVE_TOLERANCE: f64 = 1e-12;
+
+/// The rule used to map an elastic notch stress onto the cyclic stress-strain curve.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NotchRule {
+    /// Neuber's rule: the product of local stress and strain equals the elastic one.
+    Neuber,
+    /// Glinka's equivalent strain energy density (ESED) rule.
+    Glinka,
+}
+
+impl NotchRule {
+    /// Parses the notch rule from its configuration name, `NEUBER` or `GLINKA`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fatigue::notch::NotchRule;
+    ///
+    /// assert_eq!(NotchRule::from_name("GLINKA").unwrap(), NotchRule::Glinka);
+    /// assert!(NotchRule::from_name("NONE").is_err());
+    /// ```
+    pub fn from_name(name: &str) -> Result<Self> {
+        match name {
+            "NEUBER" => Ok(NotchRule::Neuber),
+            "GLINKA" => Ok(NotchRule::Glinka),
+            _ => Err(anyhow!("notch rule must be NEUBER or GLINKA, got {}", name)),
+        }
+    }
+}
+
+/// The local stress and strain at the notch root at one reversal of the elastic history.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct LocalState {
+    /// The linear-elastic (pseudo) stress at the reversal.
+    pub elastic: f64,
+    /// The estimated local elastic-plastic stress.
+    pub stress: f64,
+    /// The estimated local total strain.
+    pub strain: f64,
+}
+
+/// A local hysteresis loop counted from the notch-root response.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LocalCycle {
+    /// The local stress range of the loop.
+    pub stress_range: f64,
+    /// The local total strain range of the loop.
+    pub strain_range: f64,
+    /// The local mean stress of the loop.
+    pub mean_stress: f64,
+    /// The cycle count, `1.0` for closed loops and `0.5` for residual half cycles.
+    pub count: f64,
+}
+
+impl LocalCycle {
+    fn between(from: &LocalState, to: &LocalState, count: f64) -> Self {
+        LocalCycle {
+            stress_range: (to.stress - from.stress).abs(),
+            strain_range: (to.strain - from.strain).abs(),
+            mean_stress: (to.stress + from.stress) / 2.0,
+            count,
+        }
+    }
+}
+
+/// The local notch-root response of an elastic stress history.
+#[derive(Debug, Clone, Default)]
+pub struct LocalResponse {
+    /// The local state at every reversal of the elastic history, in order.
+    pub states: Vec<LocalState>,
+    /// The closed hysteresis loops followed by the residual half cycles.
+    pub cycles: Vec<LocalCycle>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct OpenReversal {
+    state: LocalState,
+    on_envelope: bool,
+}
+
+/// Estimates local notch stresses and strains from linear-elastic stresses.
+#[derive(Debug)]
+pub struct NotchCorrection<'a> {
+    curve: &'a CyclicCurve,
+    youngs_modulus: f64,
+    rule: NotchRule,
+}
+
+impl<'a> NotchCorrection<'a> {
+    /// Creates a notch correction for the given cyclic curve, Young's modulus and rule.
+    ///
+    /// # Returns
+    ///
+    /// Returns an error if the Young's modulus is not positive or the cyclic curve is invalid.
+    pub fn new(curve: &'a CyclicCurve, youngs_modulus: f64, rule: NotchRule) -> Result<Self> {
+        if youngs_modulus <= 0.0 {
+            return Err(anyhow!(
+                "youngs_modulus must be greater than 0.0, got {}",
+                youngs_modulus
+            ));
+        }
+        curve.validate()?;
+        Ok(NotchCorrection {
+            curve,
+            youngs_modulus,
+            rule,
+        })
+    }
+
+    /// Returns the local `(stress, strain)` on the cyclic curve for a first loading
+    /// from the unloaded state to the given elastic stress.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fatigue::material::CyclicCurve;
+    /// use fatigue::notch::{NotchCorrection, NotchRule};
+    ///
+    /// let curve = CyclicCurve { k_prime: 1100.0, n_prime: 0.15 };
+    /// let neuber = NotchCorrection::new(&curve, 210000.0, NotchRule::Neuber).unwrap();
+    /// let (stress, strain) = neuber.monotonic(600.0).unwrap();
+    /// assert!(stress < 600.0);
+    /// assert!((stress * strain - 600.0 * 600.0 / 210000.0).abs() < 1e-9);
+    /// ```
+    pub fn monotonic(&self, elastic: f64) -> Result<(f64, f64)> {
+        if !elastic.is_finite() {
+            return Err(anyhow!("elastic stress must be finite, got {}", elastic));
+        }
+        let stress = self.solve(elastic.abs())?.copysign(elastic);
+        Ok((stress, self.curve.strain(stress, self.youngs_modulus)))
+    }
+
+    /// Returns the local `(stress range, strain range)` of a Masing hysteresis branch
+    /// for the given elastic stress range.
+    pub fn branch(&self, elastic_range: f64) -> Result<(f64, f64)> {
+        if !elastic_range.is_finite() || elastic_range < 0.0 {
+            return Err(anyhow!(
+                "elastic stress range must be finite and non-negative, got {}",
+                elastic_range
+            ));
+        }
+        let (stress, _) = self.monotonic(elastic_range / 2.0)?;
+        let range = 2.0 * stress;
+        Ok((range, self.curve.branch_strain(range, self.youngs_modulus)))
+    }
+
+    /// Computes the local notch-root response of a linear-elastic stress history.
+    ///
+    /// The history is reduced to its reversals and processed in order. The first
+    /// loading and every excursion beyond the largest previous elastic stress follow
+    /// the cyclic curve; all other excursions follow Masing branches from the last
+    /// open reversal. When a branch passes the reversal it started from, the enclosed
+    /// hysteresis loop is closed and counted, and the branch continues from the older
+    /// reversal as if never interrupted (memory effect). Reversals left open at the
+    /// end of the history are reported as residual half cycles; zero-range half
+    /// cycles, e.g. from a history starting at zero, are dropped.
+    ///
+    /// # Returns
+    ///
+    /// Returns the local state at each reversal and the counted local cycles, or an
+    /// error if the history contains non-finite values.
+    pub fn local_response(&self, elastic: &[f64]) -> Result<LocalResponse> {
+        let points = reversals(elastic);
+        let mut response = LocalResponse {
+            states: Vec::with_capacity(points.len()),
+            cycles: Vec::new(),
+        };
+        let mut open = vec![OpenReversal {
+            state: LocalState::default(),
+            on_envelope: true,
+        }];
+        let mut envelope = 0.0_f64;
+
+        for &point in &points {
+            while open.len() >= 2 {
+                let top = open[open.len() - 1];
+                let previous = open[open.len() - 2];
+                let branch_range = (top.state.elastic - previous.state.elastic).abs();
+                if (point - top.state.elastic).abs() < branch_range
+                    || (top.on_envelope && previous.on_envelope)
+                {
+                    break;
+                }
+                response
+                    .cycles
+                    .push(LocalCycle::between(&previous.state, &top.state, 1.0));
+                open.truncate(open.len() - 2);
+            }
+
+            let origin = open[open.len() - 1].state;
+            let on_envelope = open.len() == 1 || point.abs() >= envelope;
+            let state = if on_envelope {
+                envelope = envelope.max(point.abs());
+                let (stress, strain) = self.monotonic(point)?;
+                LocalState {
+                    elastic: point,
+                    stress,
+                    strain,
+                }
+            } else {
+                let range = point - origin.elastic;
+                let (stress_range, strain_range) = self.branch(range.abs())?;
+                LocalState {
+                    elastic: point,
+                    stress: origin.stress + stress_range.copysign(range),
+                    strain: origin.strain + strain_range.copysign(range),
+                }
+            };
+            open.push(OpenReversal { state, on_envelope });
+            response.states.push(state);
+        }
+
+        for pair in open[1..].windows(2) {
+            if pair[0].state.elastic == pair[1].state.elastic {
+                continue;
+            }
+            response
+                .cycles
+                .push(LocalCycle::between(&pair[0].state, &pair[1].state, 0.5));
+        }
+        Ok(response)
+    }
+
+    /// Solves the notch rule for the local stress magnitude given an elastic stress magnitude.
+    fn solve(&self, elastic: f64) -> Result<f64> {
+        if elastic == 0.0 {
+            return Ok(0.0);
+        }
+        let e = self.youngs_modulus;
+        let residual = |stress: f64| match self.rule {
+            NotchRule::Neuber => stress * self.curve.strain(stress, e) - elastic * elastic / e,
+            NotchRule::Glinka => {
+                let plastic = (stress / self.curve.k_prime).powf(1.0 / self.curve.n_prime);
+                stress * stress / (2.0 * e) + stress * plastic / (self.curve.n_prime + 1.0)
+                    - elastic * elastic / (2.0 * e)
+            }
+        };
+
+        // Both rules are monotonic in the local stress, which never exceeds the elastic one.
+        let (mut low, mut high) = (0.0, elastic);
+        for _ in 0..MAX_ITERATIONS {
+            let mid = 0.5 * (low + high);
+            if residual(mid) > 0.0 {
+                high = mid;
+            } else {
+                low = mid;
+            }
+            if high - low <= RELATIVE_TOLERANCE * elastic {
+                return Ok(0.5 * (low + high));
+            }
+        }
+        Err(anyhow!(
+            "notch rule did not converge for elastic stress {}",
+            elastic
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    const E: f64 = 210000.0;
+
+    fn steel() -> CyclicCurve {
+        CyclicCurve {
+            k_prime: 1100.0,
+            n_prime: 0.15,
+        }
+    }
+
+    #[test]
+    fn test_neuber_energy_product() {
+        let curve = steel();
+        let neuber = NotchCorrection::new(&curve, E, NotchRule::Neuber).unwrap();
+        let (stress, strain) = neuber.monotonic(-700.0).unwrap();
+        assert!(stress < 0.0 && stress > -700.0);
+        assert_relative_eq!(stress * strain, 700.0 * 700.0 / E, max_relative = 1e-9);
+        assert_relative_eq!(strain, curve.strain(stress, E), max_relative = 1e-12);
+    }
+
+    #[test]
+    fn test_glinka_below_neuber() {
+        let curve = steel();
+        let neuber = NotchCorrection::new(&curve, E, NotchRule::Neuber).unwrap();
+        let glinka = NotchCorrection::new(&curve, E, NotchRule::Glinka).unwrap();
+        let (_, neuber_strain) = neuber.monotonic(800.0).unwrap();
+        let (stress, glinka_strain) = glinka.monotonic(800.0).unwrap();
+        // ESED predicts smaller local strains than Neuber.
+        assert!(glinka_strain < neuber_strain);
+        let plastic = (stress / curve.k_prime).powf(1.0 / curve.n_prime);
+        let energy = stress * stress / (2.0 * E) + stress * plastic / (curve.n_prime + 1.0);
+        assert_relative_eq!(energy, 800.0 * 800.0 / (2.0 * E), max_relative = 1e-9);
+    }
+
+    #[test]
+    fn test_elastic_limit() {
+        let curve = CyclicCurve {
+            k_prime: 1.0e9,
+            n_prime: 0.1,
+        };
+        let neuber = NotchCorrection::new(&curve, E, NotchRule::Neuber).unwrap();
+        let (stress, strain) = neuber.monotonic(100.0).unwrap();
+        assert_relative_eq!(stress, 100.0, max_relative = 1e-9);
+        assert_relative_eq!(strain, 100.0 / E, max_relative = 1e-9);
+    }
+
+    #[test]
+    fn test_memory_effect_closes_inner_loop() {
+        let curve = steel();
+        let neuber = NotchCorrection::new(&curve, E, NotchRule::Neuber).unwrap();
+        let response = neuber
+            .local_response(&[0.0, 400.0, 100.0, 300.0, -400.0])
+            .unwrap();
+        assert_eq!(response.states.len(), 5);
+
+        let closed: Vec<_> = response.cycles.iter().filter(|c| c.count == 1.0).collect();
+        assert_eq!(closed.len(), 1);
+        let (stress_range, strain_range) = neuber.branch(200.0).unwrap();
+        assert_relative_eq!(closed[0].stress_range, stress_range, max_relative = 1e-9);
+        assert_relative_eq!(closed[0].strain_range, strain_range, max_relative = 1e-9);
+
+        // After the inner loop closes, the material returns to the mirrored cyclic curve.
+        let peak = response.states[1];
+        let valley = response.states[4];
+        assert_relative_eq!(valley.stress, -peak.stress, max_relative = 1e-9);
+        assert_relative_eq!(valley.strain, -peak.strain, max_relative = 1e-9);
+    }
+
+    #[test]
+    fn test_history_from_zero_has_no_zero_range_half_cycles() {
+        let curve = steel();
+        let neuber = NotchCorrection::new(&curve, E, NotchRule::Neuber).unwrap();
+        let response = neuber.local_response(&[0.0, 200.0, -200.0, 200.0]).unwrap();
+        assert_eq!(response.cycles.len(), 3);
+        assert!(response
+            .cycles
+            .iter()
+            .all(|c| c.count == 0.5 && c.stress_range > 0.0 && c.strain_range > 0.0));
+    }
+
+    #[test]
+    fn test_invalid_input() {
+        let curve = steel();
+        let err = NotchCorrection::new(&curve, 0.0, NotchRule::Glinka).unwrap_err();
+        assert!(format!("{err:#}").contains("youngs_modulus"));
+        let neuber = NotchCorrection::new(&curve, E, NotchRule::Neuber).unwrap();
+        let err = neuber.local_response(&[0.0, f64::NAN]).unwrap_err();
+        assert!(format!("{err:#}").contains("finite"));
+    }
+}
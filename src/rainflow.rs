@@ -1,87 +1,117 @@
 //! A module for rainflow counting algorithm
-use std::collections::VecDeque;
 
-/// Rainflow counting algorithm
+/// Extracts the turning points (peaks and valleys) of a load history
+///
+/// The first and last samples are always kept, plateaus collapse to a single
+/// point and intermediate samples on monotonic ramps are dropped, so the
+/// returned sequence strictly alternates in direction.
 #[cfg(any(feature = "cli", feature = "wasm"))]
-pub fn rainflow(stress: &[f64]) -> (Vec<f64>, Vec<f64>) {
-    let mut reversals = VecDeque::new();
-    let mut outmean = Vec::new();
-    let mut outrange = Vec::new();
-
-    // Identify reversals in the stress history
-    for i in 1..stress.len() {
-        if stress[i] != stress[i - 1] {
-            reversals.push_back(stress[i]);
+pub fn reversals(history: &[f64]) -> Vec<f64> {
+    let mut points: Vec<f64> = Vec::new();
+    for &value in history {
+        let len = points.len();
+        if len > 0 && value == points[len - 1] {
+            continue;
+        }
+        if len >= 2 {
+            let rising = points[len - 1] > points[len - 2];
+            let still_rising = value > points[len - 1];
+            if rising == still_rising {
+                points[len - 1] = value;
+                continue;
+            }
         }
+        points.push(value);
     }
+    points
+}
 
-    // Rainflow counting algorithm
-    while reversals.len() >= 3 {
-        let z = reversals[0];
-        let y = reversals[1];
-        let x = reversals[2];
-
-        let r_x = (x - z).abs();
-        let r_y = (y - z).abs();
-
-        if r_x < r_y {
-            // Count Y as 1 cycle
-            let mean = (y + z) / 2.0;
-            let range = r_y;
-            outmean.push(mean);
-            outrange.push(range);
-
-            // Discard both points of Y
-            reversals.pop_front();
-            reversals.pop_front();
-        } else {
-            // Check if Y includes Z
-            if (z < y && y < x) || (z > y && y > x) {
-                // Count Y as 1/2 cycle
-                let mean = (y + z) / 2.0;
-                let range = r_y;
-                outmean.push(mean);
-                outrange.push(range / 2.0);
-
-                // Discard the first reversal of Y
-                reversals.pop_front();
+/// A counted rainflow cycle.
+#[cfg(any(feature = "cli", feature = "wasm"))]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Cycle {
+    /// The range between the two reversals of the cycle.
+    pub range: f64,
+    /// The mean of the two reversals of the cycle.
+    pub mean: f64,
+    /// 1.0 for a closed cycle, 0.5 for a half cycle of the residue.
+    pub count: f64,
+}
 
-                // Set Z to the second reversal of Y
-                reversals[0] = x;
-            } else {
-                // Not enough reversals to form a cycle, read more reversals
+/// Counts the rainflow cycles of a load history with the four-point method.
+///
+/// The history is reduced to its `reversals` first. Every cycle keeps its full range
+/// and half cycles of the residue are marked by a `count` of 0.5, as needed for
+/// Palmgren-Miner damage sums.
+///
+/// # Examples
+///
+/// ```
+/// use fatigue::rainflow::rainflow;
+///
+/// let cycles = rainflow(&[0.0, 4.0, 1.0, 3.0, -2.0]);
+/// assert_eq!((cycles[0].range, cycles[0].count), (2.0, 1.0));
+/// let half: f64 = cycles.iter().filter(|c| c.count == 0.5).map(|c| c.range).sum();
+/// assert_eq!(half, 4.0 + 6.0);
+/// ```
+#[cfg(any(feature = "cli", feature = "wasm"))]
+pub fn rainflow(history: &[f64]) -> Vec<Cycle> {
+    let cycle = |a: f64, b: f64, count: f64| Cycle {
+        range: (b - a).abs(),
+        mean: (a + b) / 2.0,
+        count,
+    };
+    let mut cycles = Vec::new();
+    let mut stack: Vec<f64> = Vec::new();
+    for point in reversals(history) {
+        stack.push(point);
+        while stack.len() >= 4 {
+            let n = stack.len();
+            let inner = (stack[n - 2] - stack[n - 3]).abs();
+            if inner > (stack[n - 3] - stack[n - 4]).abs()
+                || inner > (stack[n - 1] - stack[n - 2]).abs()
+            {
                 break;
             }
+            cycles.push(cycle(stack[n - 3], stack[n - 2], 1.0));
+            stack.drain(n - 3..n - 1);
         }
     }
-
-    // Handle the remaining reversals as half cycles
-    while let Some(rev) = reversals.pop_front() {
-        if let Some(next_rev) = reversals.front() {
-            let mean = (rev + next_rev) / 2.0;
-            let range = (next_rev - rev).abs();
-            outmean.push(mean);
-            outrange.push(range / 2.0);
-        }
-    }
-
-    (outmean, outrange)
+    cycles.extend(stack.windows(2).map(|pair| cycle(pair[0], pair[1], 0.5)));
+    cycles
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use approx::assert_relative_eq;
 
     #[test]
-    fn test_rainflow() {
-        let stress_sequence = vec![
-            -2.0, 1.0, -3.0, 5.0, -1.0, 3.0, -4.0, 4.0, -3.0, 1.0, -2.0, 3.0, 6.0,
-        ];
-        let (means, ranges) = rainflow(&stress_sequence);
+    fn test_reversals() {
+        let history = vec![0.0, 1.0, 2.0, 2.0, 1.0, -1.0, -1.0, 0.5, 3.0];
+        assert_eq!(reversals(&history), vec![0.0, 2.0, -1.0, 3.0]);
+        assert!(reversals(&[]).is_empty());
+        assert_eq!(reversals(&[4.0, 4.0, 4.0]), vec![4.0]);
+    }
 
-        // Output the results
-        for (mean, range) in means.iter().zip(ranges.iter()) {
-            println!("{:.4}, {:.4}", mean, range);
+    #[test]
+    fn test_rainflow() {
+        // ASTM E1049 history: the four-point method closes one cycle and leaves the
+        // range-8 pair in the residue instead of closing it as the three-point method does.
+        let history = vec![-2.0, 1.0, -3.0, 5.0, -1.0, 3.0, -4.0, 4.0, -2.0];
+        let cycles = rainflow(&history);
+        let (closed, half): (Vec<&Cycle>, Vec<&Cycle>) =
+            cycles.iter().partition(|c| c.count > 0.75);
+        assert_eq!(closed.len(), 1);
+        assert_relative_eq!(closed[0].count, 1.0);
+        assert_relative_eq!(closed[0].range, 4.0);
+        assert_relative_eq!(closed[0].mean, 1.0);
+        let ranges = [3.0, 4.0, 8.0, 9.0, 8.0, 6.0];
+        assert_eq!(half.len(), ranges.len());
+        for (cycle, range) in half.iter().zip(ranges) {
+            assert_relative_eq!(cycle.count, 0.5);
+            assert_relative_eq!(cycle.range, range);
         }
+        assert!(rainflow(&[1.0, 1.0]).is_empty());
     }
 }
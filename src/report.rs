@@ -0,0 +1,424 @@
+//! A module for the JSON report of a fatigue assessment, written for `Solution.output: JSON`.
+//!
+//! The report is a versioned, self-describing serde schema: downstream tools deserialize it
+//! with `Report::read` or their own copy of these types. Fields are only added in a new
+//! `REPORT_VERSION`; existing fields keep their names and meaning.
+use crate::assessment::{Assessment, OutsideSamples};
+use crate::config::Config;
+use crate::timeseries::ChannelPsd;
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// The version of the report schema written by `Report::write`.
+pub const REPORT_VERSION: u32 = 2;
+
+/// The report of an assessment.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Report {
+    /// The version of the schema, `REPORT_VERSION` when written by this crate.
+    pub version: u32,
+    /// What was run and with which settings.
+    pub metadata: Metadata,
+    /// The damage of the nodes per node set, in the order of `node_sets`.
+    pub sets: Vec<SetReport>,
+    /// Statistics over the nodes of all sets.
+    pub summary: Summary,
+    /// The load-case samples outside the points of each interpolation.
+    pub outside: Vec<OutsideSamples>,
+    /// The PSDs of the load-case channels of `TimeSeries.psd`, empty without it. Added in
+    /// version 2.
+    #[serde(default)]
+    pub psd: Vec<PsdReport>,
+}
+
+/// The run metadata of a report.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Metadata {
+    /// The name of the tool that wrote the report.
+    pub tool: String,
+    /// The version of the tool that wrote the report.
+    pub tool_version: String,
+    /// The path of the configuration file.
+    pub config: String,
+    /// `Solution.run_type`.
+    pub run_type: String,
+    /// `Solution.mode`.
+    pub mode: String,
+    /// The method of the global `Solution.stress_criteria`.
+    pub stress_criterion: String,
+    /// `Solution.mean.mean`.
+    pub mean_stress_correction: String,
+    /// The allowable damage `Damage.dadm` utilizations refer to.
+    pub allowable_damage: f64,
+    /// The load cases in the order of `TimeSeries.loadcases`.
+    pub loadcases: Vec<LoadCaseReport>,
+    /// The names of the interpolations.
+    pub interpolations: Vec<String>,
+}
+
+/// A load case of the report metadata.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LoadCaseReport {
+    /// The file of the load case.
+    pub file: String,
+    /// The load-case family `LoadCase.fam`.
+    pub family: usize,
+    /// The number of occurrences of the load case its damage is weighted with.
+    pub frequency: f64,
+}
+
+/// The damage of the nodes of a node set.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SetReport {
+    /// The name of the node set.
+    pub name: String,
+    /// The name of the material the set is assessed with.
+    pub material: String,
+    /// The assessed nodes in ascending node order.
+    pub nodes: Vec<NodeReport>,
+    /// Statistics over the nodes of the set.
+    pub summary: Summary,
+}
+
+/// The damage of a node.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NodeReport {
+    /// The node number.
+    pub node: usize,
+    /// The Palmgren-Miner damage summed over the load cases, weighted by their frequency.
+    pub damage: f64,
+    /// The number of repetitions of the load cases to failure, `1 / damage`, or `None`
+    /// without damage.
+    pub life: Option<f64>,
+    /// The damage relative to the allowable damage, or `None` if the allowable damage is 0.
+    pub utilization: Option<f64>,
+    /// The file of the load case with the largest damage, or `None` without damage.
+    pub loadcase: Option<String>,
+    /// The load-case family whose load cases sum to the largest damage, or `None` without
+    /// damage.
+    pub family: Option<usize>,
+    /// The critical plane of multi-plane stress criteria, `None` for single-plane ones.
+    pub plane: Option<usize>,
+}
+
+/// The Welch PSD of a load-case channel.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PsdReport {
+    /// The file of the load case.
+    pub loadcase: String,
+    /// The channel name.
+    pub channel: String,
+    /// The frequencies in Hz.
+    pub frequencies: Vec<f64>,
+    /// The one-sided spectral density at each frequency.
+    pub density: Vec<f64>,
+    /// The variance of the channel, the zeroth moment of the PSD.
+    pub variance: f64,
+}
+
+/// Statistics over the damage of nodes.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Summary {
+    /// The number of nodes.
+    pub nodes: usize,
+    /// The node with the largest damage, or `None` without nodes.
+    pub max_node: Option<usize>,
+    /// The largest damage, 0 without nodes.
+    pub max_damage: f64,
+    /// The mean damage, 0 without nodes.
+    pub mean_damage: f64,
+    /// The shortest life, or `None` if no node has damage.
+    pub min_life: Option<f64>,
+    /// The largest utilization, or `None` without nodes or if the allowable damage is 0.
+    pub max_utilization: Option<f64>,
+    /// The number of nodes whose damage exceeds the allowable damage.
+    pub exceeding: usize,
+}
+
+impl Summary {
+    /// Returns the statistics of the nodes against the allowable damage `dadm`.
+    pub fn new<'a>(nodes: impl IntoIterator<Item = &'a NodeReport>, dadm: f64) -> Self {
+        let mut summary = Summary {
+            nodes: 0,
+            max_node: None,
+            max_damage: 0.0,
+            mean_damage: 0.0,
+            min_life: None,
+            max_utilization: None,
+            exceeding: 0,
+        };
+        let mut total = 0.0;
+        for node in nodes {
+            summary.nodes += 1;
+            total += node.damage;
+            if summary.max_node.is_none() || node.damage > summary.max_damage {
+                summary.max_node = Some(node.node);
+                summary.max_damage = node.damage;
+            }
+            if node.damage > dadm {
+                summary.exceeding += 1;
+            }
+        }
+        if summary.nodes > 0 {
+            summary.mean_damage = total / summary.nodes as f64;
+            summary.max_utilization = (dadm > 0.0).then(|| summary.max_damage / dadm);
+        }
+        summary.min_life = (summary.max_damage > 0.0).then(|| 1.0 / summary.max_damage);
+        summary
+    }
+}
+
+impl Report {
+    /// Returns the report of an assessment of the configuration read from `config_path`,
+    /// with the load-case channel PSDs of `TimeSeries::loadcase_psds`.
+    pub fn new(
+        config: &Config,
+        config_path: &str,
+        assessment: &Assessment,
+        psds: &[ChannelPsd],
+    ) -> Self {
+        let dadm = config.solution.damage.dadm;
+        let loadcases = &config.timeseries.loadcases;
+        let sets: Vec<SetReport> = assessment
+            .sets
+            .iter()
+            .map(|set| {
+                let nodes: Vec<NodeReport> = set
+                    .nodes
+                    .iter()
+                    .map(|node| {
+                        let loadcase = node.loadcase.and_then(|index| loadcases.get(index));
+                        NodeReport {
+                            node: node.node,
+                            damage: node.damage,
+                            life: (node.damage > 0.0).then(|| 1.0 / node.damage),
+                            utilization: (dadm > 0.0).then(|| node.damage / dadm),
+                            loadcase: loadcase.map(|lc| lc.file.clone()),
+                            family: node.family,
+                            plane: (set.planes > 1).then_some(node.plane),
+                        }
+                    })
+                    .collect();
+                SetReport {
+                    name: set.name.clone(),
+                    material: set.material.clone(),
+                    summary: Summary::new(&nodes, dadm),
+                    nodes,
+                }
+            })
+            .collect();
+        let summary = Summary::new(sets.iter().flat_map(|set| &set.nodes), dadm);
+        Report {
+            version: REPORT_VERSION,
+            metadata: Metadata {
+                tool: env!("CARGO_PKG_NAME").to_string(),
+                tool_version: env!("CARGO_PKG_VERSION").to_string(),
+                config: config_path.to_string(),
+                run_type: config.solution.run_type.clone(),
+                mode: config.solution.mode.clone(),
+                stress_criterion: config.solution.stress_criteria.method.clone(),
+                mean_stress_correction: config.solution.mean.mean.clone(),
+                allowable_damage: dadm,
+                loadcases: loadcases
+                    .iter()
+                    .map(|lc| LoadCaseReport {
+                        file: lc.file.clone(),
+                        family: lc.fam,
+                        frequency: lc.frequency,
+                    })
+                    .collect(),
+                interpolations: config
+                    .timeseries
+                    .interpolations
+                    .iter()
+                    .map(|interpolation| interpolation.name.clone())
+                    .collect(),
+            },
+            sets,
+            summary,
+            outside: assessment.outside.clone(),
+            psd: psds
+                .iter()
+                .map(|channel| PsdReport {
+                    loadcase: channel.loadcase.clone(),
+                    channel: channel.channel.clone(),
+                    frequencies: channel.psd.frequencies.clone(),
+                    density: channel.psd.density.clone(),
+                    variance: channel.psd.moment(0),
+                })
+                .collect(),
+        }
+    }
+
+    /// Writes the report as pretty-printed JSON.
+    pub fn write<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let path = path.as_ref();
+        let json = serde_json::to_string_pretty(self)?;
+        fs::write(path, json)
+            .map_err(|e| anyhow!("Failed to write report {}: {}", path.display(), e))
+    }
+
+    /// Reads a report written by `write`.
+    ///
+    /// # Returns
+    ///
+    /// Returns an error if the file cannot be read or parsed, or was written with a newer
+    /// schema than `REPORT_VERSION`.
+    pub fn read<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let json = fs::read_to_string(path)
+            .map_err(|e| anyhow!("Failed to read report {}: {}", path.display(), e))?;
+        let report: Report = serde_json::from_str(&json)
+            .map_err(|e| anyhow!("Failed to parse report {}: {}", path.display(), e))?;
+        if report.version > REPORT_VERSION {
+            return Err(anyhow!(
+                "report {} has version {}, expected at most {}",
+                path.display(),
+                report.version,
+                REPORT_VERSION
+            ));
+        }
+        Ok(report)
+    }
+}
+
+/// Returns the path of the report of the configuration read from `config_path`:
+/// `Solution.report`, or `config_path` with the extension `report.json`.
+pub fn report_path(config: &Config, config_path: &str) -> PathBuf {
+    match &config.solution.report {
+        Some(path) => PathBuf::from(path),
+        None => Path::new(config_path).with_extension("report.json"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assessment::assess;
+    use crate::config::load_config;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn test_report() {
+        let path = "tests/config_node_sets.yaml";
+        let mut config = load_config(path).expect("Failed to load config");
+        config.solution.node.to = 22;
+        config.timeseries.psd = load_config("tests/config.yaml").unwrap().timeseries.psd;
+        let assessment = assess(&config).unwrap();
+        let psds = config.timeseries.loadcase_psds(&config.units).unwrap();
+        let report = Report::new(&config, path, &assessment, &psds);
+        assert_eq!(report.version, REPORT_VERSION);
+        assert_eq!(report.metadata.config, path);
+        assert_eq!(
+            report.metadata.loadcases.len(),
+            config.timeseries.loadcases.len()
+        );
+        assert_eq!(report.metadata.interpolations, vec!["StressTimeseries"]);
+        assert_eq!(report.outside, assessment.outside);
+
+        // One PSD of the configured channel per load case, with its variance.
+        assert_eq!(report.psd.len(), config.timeseries.loadcases.len());
+        for (psd, channel) in report.psd.iter().zip(&psds) {
+            assert_eq!(
+                (psd.loadcase.as_str(), psd.channel.as_str()),
+                (channel.loadcase.as_str(), "Fx")
+            );
+            assert_eq!(psd.frequencies.len(), psd.density.len());
+            assert!(psd.variance > 0.0);
+            assert_relative_eq!(psd.variance, channel.psd.moment(0));
+        }
+
+        // The base set has the multi-plane global criterion, the weld set a single plane.
+        let dadm = config.solution.damage.dadm;
+        let (base, weld) = (&report.sets[0], &report.sets[1]);
+        assert_eq!((base.summary.nodes, weld.summary.nodes), (20, 2));
+        assert!(base.nodes.iter().all(|node| node.plane.is_some()));
+        assert!(weld.nodes.iter().all(|node| node.plane.is_none()));
+        let assessed = assessment.sets.iter().flat_map(|set| &set.nodes);
+        for (node, assessed) in base.nodes.iter().chain(&weld.nodes).zip(assessed) {
+            assert_relative_eq!(node.life.unwrap() * node.damage, 1.0);
+            assert_relative_eq!(node.utilization.unwrap() * dadm, node.damage);
+            let loadcase = &config.timeseries.loadcases[assessed.loadcase.unwrap()];
+            assert_eq!(node.loadcase.as_deref(), Some(loadcase.file.as_str()));
+            assert_eq!(node.family, assessed.family);
+        }
+        let max = assessment.sets[0].max().unwrap();
+        assert_eq!(base.summary.max_node, Some(max.node));
+        assert_relative_eq!(base.summary.max_damage, max.damage);
+        let mean = base.nodes.iter().map(|node| node.damage).sum::<f64>() / 20.0;
+        assert_relative_eq!(base.summary.mean_damage, mean);
+        assert_eq!(report.summary.nodes, 22);
+        assert_relative_eq!(
+            report.summary.max_damage,
+            base.summary.max_damage.max(weld.summary.max_damage)
+        );
+        assert_relative_eq!(
+            report.summary.min_life.unwrap() * report.summary.max_damage,
+            1.0
+        );
+
+        // The report reads back as written, version 1 reports without PSDs still read, and
+        // newer schemas are refused.
+        let dir: PathBuf = ["tests", &format!("report-{}", std::process::id())]
+            .iter()
+            .collect();
+        fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("report.json");
+        report.write(&file).unwrap();
+        assert_eq!(Report::read(&file).unwrap(), report);
+        let mut json = serde_json::to_value(&report).unwrap();
+        json["version"] = 1.into();
+        json.as_object_mut().unwrap().remove("psd");
+        fs::write(&file, json.to_string()).unwrap();
+        let old = Report::read(&file).unwrap();
+        assert_eq!((old.version, old.psd.len()), (1, 0));
+        assert_eq!(old.sets, report.sets);
+        let newer = Report {
+            version: REPORT_VERSION + 1,
+            ..report
+        };
+        newer.write(&file).unwrap();
+        let err = Report::read(&file).unwrap_err();
+        assert!(format!("{err:#}").contains("has version 3, expected at most 2"));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_summary_without_damage() {
+        let node = NodeReport {
+            node: 7,
+            damage: 0.0,
+            life: None,
+            utilization: Some(0.0),
+            loadcase: None,
+            family: None,
+            plane: None,
+        };
+        let summary = Summary::new([&node], 0.5);
+        assert_eq!(summary.max_node, Some(7));
+        assert_eq!(summary.min_life, None);
+        assert_relative_eq!(summary.max_utilization.unwrap(), 0.0);
+        assert_eq!(summary.exceeding, 0);
+        let empty = Summary::new([], 0.5);
+        assert_eq!(
+            (empty.nodes, empty.max_node, empty.max_utilization),
+            (0, None, None)
+        );
+    }
+
+    #[test]
+    fn test_report_path() {
+        let mut config = load_config("tests/config.yaml").expect("Failed to load config");
+        assert_eq!(
+            report_path(&config, "tests/config.yaml"),
+            PathBuf::from("tests/config.report.json")
+        );
+        config.solution.report = Some("out/damage.json".to_string());
+        assert_eq!(
+            report_path(&config, "tests/config.yaml"),
+            PathBuf::from("out/damage.json")
+        );
+    }
+}
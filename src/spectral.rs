@@ -0,0 +1,716 @@
+//! A module for spectral (frequency-domain) fatigue assessment.
+//!
+//! Random vibration load cases are often described by a one-sided power spectral
+//! density (PSD) of the stress instead of a time series. The methods in this module
+//! estimate the fatigue damage rate directly from the spectral moments of the PSD,
+//! using the same S-N curve (`material::Fatigue`) as the time-domain rainflow path so
+//! the two can be compared.
+use crate::material::Fatigue;
+use anyhow::{anyhow, Result};
+use nalgebra::Complex;
+use std::f64::consts::PI;
+
+/// Number of Simpson intervals used to integrate the stress range distributions.
+const INTEGRATION_INTERVALS: usize = 20000;
+/// Upper integration limit in multiples of the standard deviation of the stress.
+const INTEGRATION_SIGMAS: f64 = 60.0;
+
+/// A one-sided power spectral density of a stress signal.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Psd {
+    /// Frequencies in Hz, strictly increasing and non-negative.
+    pub frequencies: Vec<f64>,
+    /// Spectral density in stress squared per Hz at each frequency.
+    pub density: Vec<f64>,
+}
+
+impl Psd {
+    /// Creates a PSD from frequencies and densities.
+    ///
+    /// # Returns
+    ///
+    /// Returns an error if the vectors differ in length, contain fewer than two values,
+    /// the frequencies are negative or not strictly increasing, or the densities are
+    /// negative or not finite.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fatigue::spectral::Psd;
+    ///
+    /// let psd = Psd::new(vec![0.0, 1.0, 2.0], vec![0.0, 1.0, 0.0]).unwrap();
+    /// assert!((psd.moments().m0 - 1.0).abs() < 1e-12);
+    /// assert!(Psd::new(vec![0.0, 0.0], vec![1.0, 1.0]).is_err());
+    /// ```
+    pub fn new(frequencies: Vec<f64>, density: Vec<f64>) -> Result<Self> {
+        if frequencies.len() != density.len() {
+            return Err(anyhow!(
+                "frequencies and density must have the same length, got {} and {}",
+                frequencies.len(),
+                density.len()
+            ));
+        }
+        if frequencies.len() < 2 {
+            return Err(anyhow!(
+                "PSD must contain at least 2 values, got {}",
+                frequencies.len()
+            ));
+        }
+        if frequencies.iter().any(|f| !f.is_finite() || *f < 0.0) {
+            return Err(anyhow!("frequencies must be finite and non-negative"));
+        }
+        if frequencies.windows(2).any(|w| w[1] <= w[0]) {
+            return Err(anyhow!("frequencies must be strictly increasing"));
+        }
+        if density.iter().any(|g| !g.is_finite() || *g < 0.0) {
+            return Err(anyhow!("density must be finite and non-negative"));
+        }
+        Ok(Psd {
+            frequencies,
+            density,
+        })
+    }
+
+    /// Returns the spectral moment `m_k = ∫ f^k G(f) df` using trapezoidal integration.
+    pub fn moment(&self, order: i32) -> f64 {
+        let weighted: Vec<f64> = self
+            .frequencies
+            .iter()
+            .zip(&self.density)
+            .map(|(f, g)| f.powi(order) * g)
+            .collect();
+        self.frequencies
+            .windows(2)
+            .zip(weighted.windows(2))
+            .map(|(f, w)| 0.5 * (f[1] - f[0]) * (w[0] + w[1]))
+            .sum()
+    }
+
+    /// Returns the spectral moments used by the spectral fatigue methods.
+    pub fn moments(&self) -> SpectralMoments {
+        SpectralMoments {
+            m0: self.moment(0),
+            m1: self.moment(1),
+            m2: self.moment(2),
+            m4: self.moment(4),
+        }
+    }
+}
+
+/// The spectral moments `m0`, `m1`, `m2` and `m4` of a one-sided PSD.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SpectralMoments {
+    /// Zeroth moment, the variance of the signal.
+    pub m0: f64,
+    /// First moment.
+    pub m1: f64,
+    /// Second moment.
+    pub m2: f64,
+    /// Fourth moment.
+    pub m4: f64,
+}
+
+impl SpectralMoments {
+    /// Validates that the moments describe a non-degenerate random process.
+    pub fn validate(&self) -> Result<()> {
+        if !(self.m0 > 0.0 && self.m1 > 0.0 && self.m2 > 0.0 && self.m4 > 0.0) {
+            return Err(anyhow!(
+                "spectral moments must be greater than 0.0, got m0={}, m1={}, m2={}, m4={}",
+                self.m0,
+                self.m1,
+                self.m2,
+                self.m4
+            ));
+        }
+        Ok(())
+    }
+
+    /// Returns the standard deviation of the signal, `sqrt(m0)`.
+    pub fn std_dev(&self) -> f64 {
+        self.m0.sqrt()
+    }
+
+    /// Returns the expected rate of zero up-crossings per second, `sqrt(m2 / m0)`.
+    pub fn zero_crossing_rate(&self) -> f64 {
+        (self.m2 / self.m0).sqrt()
+    }
+
+    /// Returns the expected rate of peaks per second, `sqrt(m4 / m2)`.
+    pub fn peak_rate(&self) -> f64 {
+        (self.m4 / self.m2).sqrt()
+    }
+
+    /// Returns the bandwidth parameter `α1 = m1 / sqrt(m0 m2)`.
+    pub fn alpha1(&self) -> f64 {
+        self.m1 / (self.m0 * self.m2).sqrt()
+    }
+
+    /// Returns the irregularity factor `α2 = m2 / sqrt(m0 m4)`.
+    pub fn alpha2(&self) -> f64 {
+        self.m2 / (self.m0 * self.m4).sqrt()
+    }
+}
+
+/// The spectral fatigue damage estimation methods.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpectralMethod {
+    /// Narrow-band approximation with Rayleigh distributed amplitudes.
+    NarrowBand,
+    /// Wirsching–Light correction of the narrow-band damage.
+    WirschingLight,
+    /// Dirlik's empirical rainflow range distribution.
+    Dirlik,
+    /// Tovo–Benasciutti interpolation between narrow-band and range counting.
+    TovoBenasciutti,
+    /// Zhao–Baker Weibull-Rayleigh amplitude distribution.
+    ZhaoBaker,
+}
+
+impl SpectralMethod {
+    /// Parses the method from its configuration name.
+    ///
+    /// Valid names are "NARROWBAND", "WIRSCHINGLIGHT", "DIRLIK", "TOVOBENASCIUTTI" and "ZHAOBAKER".
+    pub fn from_name(name: &str) -> Result<Self> {
+        match name {
+            "NARROWBAND" => Ok(SpectralMethod::NarrowBand),
+            "WIRSCHINGLIGHT" => Ok(SpectralMethod::WirschingLight),
+            "DIRLIK" => Ok(SpectralMethod::Dirlik),
+            "TOVOBENASCIUTTI" => Ok(SpectralMethod::TovoBenasciutti),
+            "ZHAOBAKER" => Ok(SpectralMethod::ZhaoBaker),
+            _ => Err(anyhow!(
+                "spectral method must be NARROWBAND, WIRSCHINGLIGHT, DIRLIK, TOVOBENASCIUTTI, or ZHAOBAKER, got {}",
+                name
+            )),
+        }
+    }
+}
+
+/// Estimates the fatigue damage per second of a stationary Gaussian stress process.
+///
+/// The damage is `ν ∫ p(S) / N(S) dS` where `p(S)` is the stress range distribution
+/// predicted by the method, `ν` the associated cycle rate and `N(S)` the S-N curve of
+/// `fatigue`. Methods defined by a single S-N slope (Wirsching–Light) use `m1`.
+///
+/// # Returns
+///
+/// Returns an error if the spectral moments are degenerate.
+///
+/// # Examples
+///
+/// ```
+/// use fatigue::material::{Cutoff, Fatigue, Knee, Slope};
+/// use fatigue::spectral::{damage_rate, Psd, SpectralMethod};
+///
+/// let fatigue = Fatigue {
+///     slope: Slope { m1: 3, m2: 5 },
+///     knee: Knee { cycle: 5000000, stress: 52.0 },
+///     cutoff: Cutoff { max: 440.0, min: 1.0 },
+/// };
+/// let psd = Psd::new(vec![1.0, 2.0, 5.0, 10.0], vec![0.0, 100.0, 100.0, 0.0]).unwrap();
+/// let dirlik = damage_rate(&psd.moments(), SpectralMethod::Dirlik, &fatigue).unwrap();
+/// let narrow = damage_rate(&psd.moments(), SpectralMethod::NarrowBand, &fatigue).unwrap();
+/// assert!(dirlik > 0.0 && dirlik < narrow);
+/// ```
+pub fn damage_rate(
+    moments: &SpectralMoments,
+    method: SpectralMethod,
+    fatigue: &Fatigue,
+) -> Result<f64> {
+    moments.validate()?;
+    let sigma = moments.std_dev();
+    let nu0 = moments.zero_crossing_rate();
+    let nup = moments.peak_rate();
+    let alpha1 = moments.alpha1();
+    let alpha2 = moments.alpha2().min(1.0);
+
+    let damage = match method {
+        SpectralMethod::NarrowBand => nu0 * integrate(fatigue, sigma, |s| rayleigh(s, sigma)),
+        SpectralMethod::WirschingLight => {
+            let m = fatigue.slope.m1 as f64;
+            let a = 0.926 - 0.033 * m;
+            let b = 1.587 * m - 2.323;
+            let epsilon = (1.0 - alpha2 * alpha2).sqrt();
+            let correction = a + (1.0 - a) * (1.0 - epsilon).powf(b);
+            correction * nu0 * integrate(fatigue, sigma, |s| rayleigh(s, sigma))
+        }
+        SpectralMethod::Dirlik => nup * integrate(fatigue, sigma, |s| dirlik(s, moments)),
+        SpectralMethod::TovoBenasciutti => {
+            let narrow = nu0 * integrate(fatigue, sigma, |s| rayleigh(s, sigma));
+            if alpha2 >= 1.0 {
+                narrow
+            } else {
+                let b = (alpha1 - alpha2)
+                    * (1.112 * (1.0 + alpha1 * alpha2 - (alpha1 + alpha2)) * (2.11 * alpha2).exp()
+                        + (alpha1 - alpha2))
+                    / (alpha2 - 1.0).powi(2);
+                let b = b.clamp(0.0, 1.0);
+                // Range counting: Rayleigh ranges scaled by α2 occurring at the peak rate.
+                let range_counting =
+                    nup * integrate(fatigue, sigma, |s| rayleigh(s, alpha2 * sigma));
+                b * narrow + (1.0 - b) * range_counting
+            }
+        }
+        SpectralMethod::ZhaoBaker => {
+            nup * integrate(fatigue, sigma, |s| zhao_baker(s, alpha2, sigma))
+        }
+    };
+    Ok(damage)
+}
+
+/// The taper applied to each segment before the FFT in Welch's method.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Window {
+    /// No taper.
+    Rectangular,
+    /// Periodic Hann window.
+    Hann,
+    /// Periodic Hamming window.
+    Hamming,
+}
+
+impl Window {
+    /// Parses the window from its configuration name, "RECTANGULAR", "HANN" or "HAMMING".
+    pub fn from_name(name: &str) -> Result<Self> {
+        match name {
+            "RECTANGULAR" => Ok(Window::Rectangular),
+            "HANN" => Ok(Window::Hann),
+            "HAMMING" => Ok(Window::Hamming),
+            _ => Err(anyhow!(
+                "window must be RECTANGULAR, HANN, or HAMMING, got {}",
+                name
+            )),
+        }
+    }
+
+    fn coefficients(&self, length: usize) -> Vec<f64> {
+        let phase = |i: usize| 2.0 * PI * i as f64 / length as f64;
+        (0..length)
+            .map(|i| match self {
+                Window::Rectangular => 1.0,
+                Window::Hann => 0.5 - 0.5 * phase(i).cos(),
+                Window::Hamming => 0.54 - 0.46 * phase(i).cos(),
+            })
+            .collect()
+    }
+}
+
+/// The trend removed from each segment before windowing in Welch's method.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Detrend {
+    /// Keep the segment as is.
+    None,
+    /// Remove the segment mean.
+    Constant,
+    /// Remove the least-squares straight line through the segment.
+    Linear,
+}
+
+impl Detrend {
+    /// Parses the detrending from its configuration name, "NONE", "CONSTANT" or "LINEAR".
+    pub fn from_name(name: &str) -> Result<Self> {
+        match name {
+            "NONE" => Ok(Detrend::None),
+            "CONSTANT" => Ok(Detrend::Constant),
+            "LINEAR" => Ok(Detrend::Linear),
+            _ => Err(anyhow!(
+                "detrend must be NONE, CONSTANT, or LINEAR, got {}",
+                name
+            )),
+        }
+    }
+
+    fn apply(&self, segment: &mut [f64]) {
+        let n = segment.len() as f64;
+        match self {
+            Detrend::None => (),
+            Detrend::Constant => {
+                let mean = segment.iter().sum::<f64>() / n;
+                segment.iter_mut().for_each(|x| *x -= mean);
+            }
+            Detrend::Linear => {
+                let t_mean = (n - 1.0) / 2.0;
+                let x_mean = segment.iter().sum::<f64>() / n;
+                let (covariance, variance) =
+                    segment
+                        .iter()
+                        .enumerate()
+                        .fold((0.0, 0.0), |(cov, var), (i, x)| {
+                            let dt = i as f64 - t_mean;
+                            (cov + dt * (x - x_mean), var + dt * dt)
+                        });
+                let slope = if variance > 0.0 {
+                    covariance / variance
+                } else {
+                    0.0
+                };
+                segment
+                    .iter_mut()
+                    .enumerate()
+                    .for_each(|(i, x)| *x -= x_mean + slope * (i as f64 - t_mean));
+            }
+        }
+    }
+}
+
+/// Estimates the one-sided PSD of a uniformly sampled signal with Welch's method.
+///
+/// The signal is split into segments of `segment_length` samples overlapping by the
+/// fraction `overlap`. Each segment is detrended, windowed and zero-padded to the next
+/// power of two before the FFT; the periodograms are averaged and scaled to a density
+/// so that integrating the PSD over frequency recovers the signal variance.
+///
+/// # Returns
+///
+/// Returns an error if the sample rate is not positive, the overlap is outside `[0, 1)`,
+/// the segment length is less than 2 or the signal is shorter than one segment.
+///
+/// # Examples
+///
+/// ```
+/// use fatigue::spectral::{welch, Detrend, Window};
+///
+/// let signal: Vec<f64> = (0..1024)
+///     .map(|i| (2.0 * std::f64::consts::PI * 8.0 * i as f64 / 64.0).sin())
+///     .collect();
+/// let psd = welch(&signal, 64.0, 256, 0.5, Window::Hann, Detrend::Constant).unwrap();
+/// let peak = psd
+///     .density
+///     .iter()
+///     .enumerate()
+///     .max_by(|a, b| a.1.total_cmp(b.1))
+///     .map(|(i, _)| psd.frequencies[i])
+///     .unwrap();
+/// assert!((peak - 8.0).abs() < 0.5);
+/// ```
+pub fn welch(
+    signal: &[f64],
+    sample_rate: f64,
+    segment_length: usize,
+    overlap: f64,
+    window: Window,
+    detrend: Detrend,
+) -> Result<Psd> {
+    if !(sample_rate > 0.0 && sample_rate.is_finite()) {
+        return Err(anyhow!(
+            "sample rate must be greater than 0.0, got {}",
+            sample_rate
+        ));
+    }
+    if !(0.0..1.0).contains(&overlap) {
+        return Err(anyhow!(
+            "overlap must be between 0.0 and 1.0 (exclusive), got {}",
+            overlap
+        ));
+    }
+    if segment_length < 2 {
+        return Err(anyhow!(
+            "segment length must be at least 2, got {}",
+            segment_length
+        ));
+    }
+    if signal.len() < segment_length {
+        return Err(anyhow!(
+            "signal has {} samples, fewer than the segment length {}",
+            signal.len(),
+            segment_length
+        ));
+    }
+
+    let step = segment_length - (overlap * segment_length as f64).round() as usize;
+    let step = step.max(1);
+    let nfft = segment_length.next_power_of_two();
+    let taper = window.coefficients(segment_length);
+    let power: f64 = taper.iter().map(|w| w * w).sum();
+    let bins = nfft / 2 + 1;
+
+    let mut density = vec![0.0; bins];
+    let mut segments = 0;
+    let mut buffer = vec![Complex::new(0.0, 0.0); nfft];
+    let mut segment = vec![0.0; segment_length];
+    for start in (0..=signal.len() - segment_length).step_by(step) {
+        segment.copy_from_slice(&signal[start..start + segment_length]);
+        detrend.apply(&mut segment);
+        buffer.iter_mut().for_each(|c| *c = Complex::new(0.0, 0.0));
+        for (c, (x, w)) in buffer.iter_mut().zip(segment.iter().zip(&taper)) {
+            c.re = x * w;
+        }
+        fft(&mut buffer);
+        for (k, d) in density.iter_mut().enumerate() {
+            *d += buffer[k].norm_sqr();
+        }
+        segments += 1;
+    }
+
+    let scale = 1.0 / (sample_rate * power * segments as f64);
+    for (k, d) in density.iter_mut().enumerate() {
+        // One-sided: fold negative frequencies except at DC and Nyquist.
+        let fold = if k == 0 || k == nfft / 2 { 1.0 } else { 2.0 };
+        *d *= fold * scale;
+    }
+    let frequencies = (0..bins)
+        .map(|k| k as f64 * sample_rate / nfft as f64)
+        .collect();
+    Psd::new(frequencies, density)
+}
+
+/// In-place iterative radix-2 FFT; `data.len()` must be a power of two.
+fn fft(data: &mut [Complex<f64>]) {
+    let n = data.len();
+    debug_assert!(n.is_power_of_two());
+    let mut j = 0;
+    for i in 1..n {
+        let mut bit = n >> 1;
+        while j & bit != 0 {
+            j ^= bit;
+            bit >>= 1;
+        }
+        j |= bit;
+        if i < j {
+            data.swap(i, j);
+        }
+    }
+    let mut length = 2;
+    while length <= n {
+        let angle = -2.0 * PI / length as f64;
+        let root = Complex::new(angle.cos(), angle.sin());
+        for start in (0..n).step_by(length) {
+            let mut twiddle = Complex::new(1.0, 0.0);
+            for k in 0..length / 2 {
+                let even = data[start + k];
+                let odd = data[start + k + length / 2] * twiddle;
+                data[start + k] = even + odd;
+                data[start + k + length / 2] = even - odd;
+                twiddle *= root;
+            }
+        }
+        length <<= 1;
+    }
+}
+
+/// Integrates `p(S) / N(S)` over the stress range with composite Simpson's rule.
+fn integrate<F: Fn(f64) -> f64>(fatigue: &Fatigue, sigma: f64, pdf: F) -> f64 {
+    let upper = INTEGRATION_SIGMAS * sigma;
+    let h = upper / INTEGRATION_INTERVALS as f64;
+    let integrand = |s: f64| {
+        let cycles = fatigue.cycles_to_failure(s);
+        if cycles.is_infinite() {
+            0.0
+        } else {
+            pdf(s) / cycles
+        }
+    };
+    let interior: f64 = (1..INTEGRATION_INTERVALS)
+        .map(|i| {
+            let weight = if i % 2 == 1 { 4.0 } else { 2.0 };
+            weight * integrand(i as f64 * h)
+        })
+        .sum();
+    h / 3.0 * (integrand(0.0) + interior + integrand(upper))
+}
+
+/// Rayleigh distribution of stress ranges `S = 2A` for amplitudes with standard deviation `sigma`.
+fn rayleigh(range: f64, sigma: f64) -> f64 {
+    let variance = sigma * sigma;
+    range / (4.0 * variance) * (-range * range / (8.0 * variance)).exp()
+}
+
+/// Dirlik's rainflow range distribution.
+fn dirlik(range: f64, moments: &SpectralMoments) -> f64 {
+    let sigma = moments.std_dev();
+    let gamma = moments.alpha2();
+    let xm = moments.m1 / moments.m0 * (moments.m2 / moments.m4).sqrt();
+    let d1 = 2.0 * (xm - gamma * gamma) / (1.0 + gamma * gamma);
+    let r = (gamma - xm - d1 * d1) / (1.0 - gamma - d1 + d1 * d1);
+    let d2 = (1.0 - gamma - d1 + d1 * d1) / (1.0 - r);
+    let d3 = 1.0 - d1 - d2;
+    let q = 1.25 * (gamma - d3 - d2 * r) / d1;
+    let z = range / (2.0 * sigma);
+    (d1 / q * (-z / q).exp()
+        + d2 * z / (r * r) * (-z * z / (2.0 * r * r)).exp()
+        + d3 * z * (-z * z / 2.0).exp())
+        / (2.0 * sigma)
+}
+
+/// Zhao–Baker mixed Weibull-Rayleigh distribution of stress ranges.
+fn zhao_baker(range: f64, alpha2: f64, sigma: f64) -> f64 {
+    let alpha = 8.0 - 7.0 * alpha2;
+    let beta = if alpha2 < 0.9 {
+        1.1
+    } else {
+        1.1 + 9.0 * (alpha2 - 0.9)
+    };
+    let w = (1.0 - alpha2)
+        / (1.0 - (2.0 / PI).sqrt() * gamma(1.0 + 1.0 / beta) * alpha.powf(-1.0 / beta));
+    let z = range / (2.0 * sigma);
+    let weibull = alpha * beta * z.powf(beta - 1.0) * (-alpha * z.powf(beta)).exp();
+    let rayleigh = z * (-z * z / 2.0).exp();
+    (w * weibull + (1.0 - w) * rayleigh) / (2.0 * sigma)
+}
+
+/// The gamma function for positive arguments via the Lanczos approximation.
+fn gamma(x: f64) -> f64 {
+    const COEFFICIENTS: [f64; 9] = [
+        0.999_999_999_999_809_9,
+        676.520_368_121_885_1,
+        -1_259.139_216_722_402_8,
+        771.323_428_777_653_1,
+        -176.615_029_162_140_6,
+        12.507_343_278_686_905,
+        -0.138_571_095_265_720_12,
+        9.984_369_578_019_572e-6,
+        1.505_632_735_149_311_6e-7,
+    ];
+    if x < 0.5 {
+        return PI / ((PI * x).sin() * gamma(1.0 - x));
+    }
+    let x = x - 1.0;
+    let t = x + 7.5;
+    let series = COEFFICIENTS[1..]
+        .iter()
+        .enumerate()
+        .fold(COEFFICIENTS[0], |acc, (i, c)| {
+            acc + c / (x + i as f64 + 1.0)
+        });
+    (2.0 * PI).sqrt() * t.powf(x + 0.5) * (-t).exp() * series
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::material::{Cutoff, Knee, Slope};
+    use approx::assert_relative_eq;
+
+    fn single_slope(m: i32) -> Fatigue {
+        Fatigue {
+            slope: Slope { m1: m, m2: m },
+            knee: Knee {
+                cycle: 2000000,
+                stress: 100.0,
+            },
+            cutoff: Cutoff {
+                max: 1000.0,
+                min: 0.0,
+            },
+        }
+    }
+
+    fn band(low: f64, high: f64) -> Psd {
+        Psd::new(
+            vec![low - 0.01, low, high, high + 0.01],
+            vec![0.0, 50.0, 50.0, 0.0],
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_gamma() {
+        assert_relative_eq!(gamma(5.0), 24.0, max_relative = 1e-12);
+        assert_relative_eq!(gamma(0.5), PI.sqrt(), max_relative = 1e-12);
+    }
+
+    #[test]
+    fn test_narrow_band_closed_form() {
+        let fatigue = single_slope(3);
+        let moments = band(10.0, 12.0).moments();
+        let damage = damage_rate(&moments, SpectralMethod::NarrowBand, &fatigue).unwrap();
+        // D = ν0 (2√2 σ)^m Γ(1 + m/2) / C with C = N_k S_k^m.
+        let c = 2000000.0 * 100.0_f64.powi(3);
+        let expected = moments.zero_crossing_rate()
+            * (2.0 * 2.0_f64.sqrt() * moments.std_dev()).powi(3)
+            * gamma(2.5)
+            / c;
+        assert_relative_eq!(damage, expected, max_relative = 1e-6);
+    }
+
+    #[test]
+    fn test_methods_converge_for_narrow_band() {
+        let fatigue = single_slope(4);
+        let moments = band(10.0, 10.5).moments();
+        let narrow = damage_rate(&moments, SpectralMethod::NarrowBand, &fatigue).unwrap();
+        for method in [
+            SpectralMethod::WirschingLight,
+            SpectralMethod::Dirlik,
+            SpectralMethod::TovoBenasciutti,
+            SpectralMethod::ZhaoBaker,
+        ] {
+            let damage = damage_rate(&moments, method, &fatigue).unwrap();
+            assert_relative_eq!(damage, narrow, max_relative = 0.05);
+        }
+    }
+
+    #[test]
+    fn test_wide_band_below_narrow_band() {
+        let fatigue = single_slope(3);
+        let moments = band(1.0, 50.0).moments();
+        let narrow = damage_rate(&moments, SpectralMethod::NarrowBand, &fatigue).unwrap();
+        for method in [
+            SpectralMethod::WirschingLight,
+            SpectralMethod::Dirlik,
+            SpectralMethod::TovoBenasciutti,
+            SpectralMethod::ZhaoBaker,
+        ] {
+            let damage = damage_rate(&moments, method, &fatigue).unwrap();
+            assert!(
+                damage < narrow,
+                "{:?} gave {} >= {}",
+                method,
+                damage,
+                narrow
+            );
+            assert!(damage > 0.5 * narrow, "{:?} gave {}", method, damage);
+        }
+    }
+
+    #[test]
+    fn test_invalid_input() {
+        let err = Psd::new(vec![1.0, 2.0], vec![1.0]).unwrap_err();
+        assert!(format!("{err:#}").contains("same length"));
+        let psd = Psd::new(vec![1.0, 2.0], vec![0.0, 0.0]).unwrap();
+        let err =
+            damage_rate(&psd.moments(), SpectralMethod::Dirlik, &single_slope(3)).unwrap_err();
+        assert!(format!("{err:#}").contains("spectral moments"));
+        assert!(SpectralMethod::from_name("RAYLEIGH").is_err());
+    }
+
+    #[test]
+    fn test_fft_matches_dft() {
+        let signal = [1.0, -2.0, 0.5, 3.0, 0.0, 1.5, -1.0, 2.0];
+        let mut buffer: Vec<Complex<f64>> = signal.iter().map(|&x| Complex::new(x, 0.0)).collect();
+        fft(&mut buffer);
+        for (k, value) in buffer.iter().enumerate() {
+            let expected: Complex<f64> = signal
+                .iter()
+                .enumerate()
+                .map(|(n, &x)| {
+                    let angle = -2.0 * PI * (k * n) as f64 / signal.len() as f64;
+                    Complex::new(angle.cos(), angle.sin()) * x
+                })
+                .sum();
+            assert_relative_eq!(value.re, expected.re, epsilon = 1e-12);
+            assert_relative_eq!(value.im, expected.im, epsilon = 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_welch_preserves_variance() {
+        let signal: Vec<f64> = (0..4096)
+            .map(|i| {
+                let t = i as f64 / 100.0;
+                3.0 * (2.0 * PI * 5.0 * t).sin() + (2.0 * PI * 17.0 * t).cos() + 10.0
+            })
+            .collect();
+        let psd = welch(&signal, 100.0, 512, 0.5, Window::Hann, Detrend::Linear).unwrap();
+        // Variance of the two harmonics: (3² + 1²) / 2.
+        assert_relative_eq!(psd.moment(0), 5.0, max_relative = 0.02);
+        assert_relative_eq!(*psd.frequencies.last().unwrap(), 50.0);
+    }
+
+    #[test]
+    fn test_welch_invalid_input() {
+        let err = welch(&[0.0; 10], 1.0, 16, 0.5, Window::Hann, Detrend::None).unwrap_err();
+        assert!(format!("{err:#}").contains("fewer than the segment length"));
+        let err = welch(&[0.0; 32], 1.0, 16, 1.0, Window::Hann, Detrend::None).unwrap_err();
+        assert!(format!("{err:#}").contains("overlap"));
+        assert!(Window::from_name("KAISER").is_err());
+    }
+}
@@ -1,9 +1,6 @@
 //! A module for stress tensor operations
 extern crate nalgebra as na;
 use na::{Const, Matrix3, SymmetricEigen, Vector6};
-use std::fs::File;
-use std::io::{BufRead, BufReader, Error};
-use std::path::Path;
 
 /// A struct representing a stress tensor where the stress components are stored in a 3x3 matrix and a 6x1 vector
 #[derive(Debug)]
@@ -62,6 +59,11 @@ impl StressTensor {
         self.vector = Self::matrix_to_vector(&matrix);
     }
 
+    /// Scales all stress components by `factor`, e.g. to convert them to another unit.
+    pub fn scale(&mut self, factor: f64) {
+        self.update_stress(self.matrix * factor);
+    }
+
     // Calculate principal stresses and their directions
     pub fn principal_stresses(&self) -> SymmetricEigen<f64, Const<3>> {
         self.matrix.symmetric_eigen()
@@ -79,15 +81,15 @@ impl StressTensor {
         rot.transpose()
     }
 
-    // Example method to get the maximum principal stress
+    /// Returns the maximum principal stress, `NaN` if a component is `NaN`.
     pub fn max_principal_stress(&self) -> f64 {
         let eigen = self.principal_stresses();
-        *eigen
+        eigen
             .eigenvalues
-            .as_slice()
             .iter()
-            .max_by(|a, b| a.partial_cmp(b).unwrap())
-            .unwrap()
+            .copied()
+            .max_by(f64::total_cmp)
+            .unwrap_or(f64::NAN)
     }
 
     pub fn von_mises_stress(&self) -> f64 {
@@ -97,39 +99,72 @@ impl StressTensor {
         let s3 = principal_stresses.eigenvalues[2];
         (((s1 - s2).powi(2) + (s2 - s3).powi(2) + (s3 - s1).powi(2)) / 2.0).sqrt()
     }
-}
 
-// Function to read stress tensors from a file and return them as a vector of tuples
-// Each tuple contains a node number and a `StressTensor` instance
-pub fn read_stress_tensors_from_file<P: AsRef<Path>>(
-    path: P,
-) -> Result<Vec<(usize, StressTensor)>, Error> {
-    let file = File::open(path)?;
-    let reader = BufReader::new(file);
-    let mut tensors = Vec::new();
+    /// Returns the Von Mises stress with the sign of the principal stress of largest magnitude,
+    /// so that tension and compression half cycles are told apart in a rainflow count.
+    pub fn signed_von_mises_stress(&self) -> f64 {
+        let eigen = self.principal_stresses();
+        let dominant =
+            eigen
+                .eigenvalues
+                .iter()
+                .copied()
+                .fold(0.0_f64, |a, b| if b.abs() > a.abs() { b } else { a });
+        self.von_mises_stress().copysign(dominant)
+    }
 
-    for line in reader.lines() {
-        let line = line?;
-        // Update this based on your file's format
-        let delimiter = ' '; // Assuming space or another character as delimiter
-        let values: Vec<f64> = line
-            .split(delimiter)
-            .filter_map(|s| s.trim().parse::<f64>().ok())
-            .collect();
+    /// Returns the normal stress on the plane whose normal lies in the xy-plane at `angle`
+    /// (in radians) from the x-axis.
+    pub fn normal_stress(&self, angle: f64) -> f64 {
+        let (sin, cos) = angle.sin_cos();
+        self.sxx() * cos * cos + self.syy() * sin * sin + 2.0 * self.sxy() * sin * cos
+    }
+}
 
-        // Ensure there are enough values for a node number and a matrix
-        if values.len() == 7 {
-            let node_number = values[0] as usize;
-            let matrix = Matrix3::new(
-                values[1], values[4], values[6], values[4], values[2], values[5], values[6],
-                values[5], values[3],
-            );
+/// A scalar stress criterion that reduces a stress tensor to the stress history counted
+/// by rainflow, built from `config::StressCriteria`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StressCriterion {
+    /// The Von Mises stress signed by the dominant principal stress.
+    VonMises,
+    /// The maximum principal stress.
+    MaximumPrincipal,
+    /// The normal stress on `n` planes with normals in the xy-plane, 180°/n apart.
+    CriticalPlane(usize),
+}
+
+impl StressCriterion {
+    /// Returns the number of planes the criterion is evaluated on.
+    pub fn planes(&self) -> usize {
+        match self {
+            StressCriterion::CriticalPlane(planes) => *planes,
+            _ => 1,
+        }
+    }
 
-            let tensor = StressTensor::new(matrix); // Assuming this constructor exists
-            tensors.push((node_number, tensor));
+    /// Returns the criterion stress of a tensor on the given plane.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fatigue::stress::{StressCriterion, StressTensor};
+    /// use nalgebra::Matrix3;
+    ///
+    /// let tensor = StressTensor::new(Matrix3::new(100.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0));
+    /// let planes = StressCriterion::CriticalPlane(4);
+    /// assert!((planes.stress(&tensor, 0) - 100.0).abs() < 1e-9);
+    /// assert!(planes.stress(&tensor, 2).abs() < 1e-9);
+    /// assert!((StressCriterion::VonMises.stress(&tensor, 0) - 100.0).abs() < 1e-9);
+    /// ```
+    pub fn stress(&self, tensor: &StressTensor, plane: usize) -> f64 {
+        match self {
+            StressCriterion::VonMises => tensor.signed_von_mises_stress(),
+            StressCriterion::MaximumPrincipal => tensor.max_principal_stress(),
+            StressCriterion::CriticalPlane(planes) => {
+                tensor.normal_stress(plane as f64 * std::f64::consts::PI / *planes as f64)
+            }
         }
     }
-    Ok(tensors)
 }
 
 #[cfg(test)]
@@ -137,7 +172,6 @@ mod tests {
     use super::*;
     use approx::assert_relative_eq;
     use na::Vector3;
-    use std::io;
 
     #[test]
     fn test_update_stress() {
@@ -165,6 +199,31 @@ mod tests {
         let stress_tensor = StressTensor::new(matrix);
         let max_stress = stress_tensor.max_principal_stress();
         assert_eq!(max_stress, 3.0);
+        for nan in [
+            Matrix3::new(f64::NAN, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 2.0),
+            Matrix3::new(3.0, f64::NAN, 0.0, f64::NAN, 1.0, 0.0, 0.0, 0.0, 2.0),
+        ] {
+            assert!(StressTensor::new(nan).max_principal_stress().is_nan());
+        }
+    }
+
+    #[test]
+    fn test_stress_criterion() {
+        // Uniaxial compression is negative for the signed Von Mises stress.
+        let tensor = StressTensor::new(Matrix3::new(-50.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0));
+        assert_relative_eq!(tensor.signed_von_mises_stress(), -50.0, epsilon = 1e-9);
+        // Pure shear has its largest normal stress on the 45° plane.
+        let shear = StressTensor::new(Matrix3::new(0.0, 30.0, 0.0, 30.0, 0.0, 0.0, 0.0, 0.0, 0.0));
+        let planes = StressCriterion::CriticalPlane(4);
+        assert_eq!(planes.planes(), 4);
+        let stresses: Vec<f64> = (0..4).map(|plane| planes.stress(&shear, plane)).collect();
+        assert_relative_eq!(stresses[1], 30.0, epsilon = 1e-9);
+        assert_relative_eq!(stresses[3], -30.0, epsilon = 1e-9);
+        assert_relative_eq!(
+            StressCriterion::MaximumPrincipal.stress(&shear, 0),
+            30.0,
+            epsilon = 1e-9
+        );
     }
 
     #[test]
@@ -223,47 +282,4 @@ mod tests {
         let expected_updated_vector = Vector6::new(9.0, 5.0, 1.0, 8.0, 4.0, 7.0);
         assert_eq!(stress_tensor.vector, expected_updated_vector);
     }
-
-    #[test]
-    fn test_read_stress_tensors_from_file() -> io::Result<()> {
-        use crate::timeseries::ParseConfig;
-        use crate::timeseries::{Interpolation, Point};
-        use std::path::PathBuf; // Ensure you import your Config and LoadCaseConfig
-
-        // Assuming LoadCaseConfig is structured something like this
-        let interp = Interpolation {
-            method: "LINEAR".to_string(),         // Assuming interpolation method
-            name: "StressTimeseries".to_string(), // Name of the time series
-            path: "tests/stressfile".to_string(), // Base path to your test files
-            scale: 1.0,                           // Scale factor
-            dimension: 3,                         // Dimension for interpolation
-            sensor: vec!["FX".into(), "FY".into(), "FZ".into()], // Sensors for interpolation
-            points: vec![Point {
-                file: Some("Fx.usf".to_string()), // File for interpolation point
-                coordinates: vec![0.0, 0.0, 0.0], // Assuming no specific value is provided; adjust if necessary
-            }],
-            parse_config: ParseConfig {
-                header: 1,             // Assuming the first line is a header
-                delimiter: " ".into(), // Assuming space-delimited values
-            },
-        };
-
-        for point in &interp.points {
-            // Check if `point.file` is `Some` and then construct the path
-            if let Some(ref file_name) = point.file {
-                let path = PathBuf::from(&interp.path).join(file_name); // Correctly constructs the path
-                let tensors = read_stress_tensors_from_file(&path)?; // Assuming the function accepts a `&Path`
-                                                                     // Assert that tensors are read correctly
-                                                                     // The exact assertions will depend on the expected content of your test file
-                                                                     // For example, if you know the specific tensors that should be parsed, assert those
-                assert!(!tensors.is_empty(), "Tensors should not be empty");
-                // Example assertion: check for a specific tensor value or node number
-                // assert_eq!(tensors[0].0, expected_node_number);
-                // assert_eq!(tensors[0].1.matrix, expected_matrix);
-                // Do something with `tensors` here
-            }
-        }
-
-        Ok(())
-    }
 }
@@ -1,13 +1,30 @@
-//! Contains the `TimeSeries` struct and related functionality for time series analysis.
+//! Contains the `TimeSeries` struct and related functionality for time series analysis,
+//! and the readers of the unit-load stress files of its interpolations: `.usf` files, also
+//! through a binary cache, Nastran punch files, Abaqus field reports and generic CSV files.
+use crate::expression::CompiledExpression;
 pub use crate::interpolate::Point;
+use crate::interpolate::{
+    affine_span, duplicate_groups, Delaunay, DuplicatePoints, Extrapolation, Grid, GridOrder,
+    InterpolationStrategyEnum, InverseDistance, Linear, NearestNeighbor, Outside, Rbf, RbfKernel,
+};
+use crate::spectral::{welch, Detrend, Psd, Window};
+use crate::stress::StressTensor;
+use crate::units::{Quantity, Unit, UnitSystem};
 use anyhow::{anyhow, Error, Result};
-use evalexpr::{eval_with_context, ContextWithMutableVariables, HashMapContext, Value};
+use evalexpr::{
+    build_operator_tree, eval_with_context, ContextWithMutableVariables, HashMapContext, Node,
+    Value,
+};
+use nalgebra::Matrix3;
 use regex::Regex;
 use serde::Deserialize;
 use serde_json::from_str;
-use std::collections::{HashMap, HashSet};
-use std::fs::read_to_string;
-use std::path::Path;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::fmt;
+use std::fs::{self, read_to_string, File};
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
 
 #[derive(Debug, Deserialize)]
 pub struct TimeSeries {
@@ -17,7 +34,14 @@ pub struct TimeSeries {
     pub loadcases: Vec<LoadCase>,
     pub parameters: HashMap<String, f64>,
     pub variables: HashMap<String, String>,
+    /// Optional evaluation order of the `variables`, sorted from their dependencies by default.
+    #[serde(default)]
     pub expressions: Expressions,
+    /// Layout of the load-case files, a comma-separated file with one header line by default.
+    #[serde(default)]
+    pub parse_config: ParseConfig,
+    /// Optional Welch PSD estimation of the load-case channels.
+    pub psd: Option<PsdConfig>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -56,12 +80,551 @@ impl LoadCase {
     }
 }
 
+/// Relative deviation from the nominal sample interval tolerated as timestamp jitter.
+const SAMPLING_TOLERANCE: f64 = 0.01;
+
+/// A missing stretch of samples in a time series.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Gap {
+    /// Index of the last sample before the gap.
+    pub index: usize,
+    /// Timestamp of the last sample before the gap.
+    pub start: f64,
+    /// Time between the samples on either side of the gap.
+    pub duration: f64,
+    /// Number of samples missing at the nominal sample rate.
+    pub missing: usize,
+}
+
+/// A named channel of a time series with its unit from `SensorFile.unit`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Channel {
+    /// The channel name.
+    pub name: String,
+    /// The unit of the channel values, empty if unknown.
+    pub unit: String,
+    /// The channel values, one per timestamp.
+    pub values: Vec<f64>,
+}
+
+impl Channel {
+    fn new(name: &str, unit: &str) -> Self {
+        Channel {
+            name: name.to_string(),
+            unit: unit.to_string(),
+            values: Vec::new(),
+        }
+    }
+
+    /// Applies a sensor's gain and offset correction and converts the values to the unit
+    /// system.
+    ///
+    /// Each value becomes `gain * value + correction` converted from the sensor's unit to
+    /// `UnitSystem::channel_unit`, where the correction is in the sensor's unit, and the
+    /// channel unit becomes the unit converted to.
+    ///
+    /// # Returns
+    ///
+    /// Returns an error if the sensor's unit or the unit system's unit is unknown.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fatigue::timeseries::{Channel, SensorFile};
+    /// use fatigue::units::UnitSystem;
+    ///
+    /// let sensor = SensorFile {
+    ///     no: 1,
+    ///     correction: 0.5,
+    ///     gain: 2.0,
+    ///     unit: "[kN]".into(),
+    ///     name: "Fx".into(),
+    ///     description: "Axial force".into(),
+    /// };
+    /// let mut channel = Channel { name: "Fx".into(), unit: String::new(), values: vec![1.0, -1.0] };
+    /// channel.calibrate(&sensor, &UnitSystem::default()).unwrap();
+    /// assert_eq!(channel.values, vec![2500.0, -1500.0]);
+    /// assert_eq!(channel.unit, "N");
+    /// ```
+    pub fn calibrate(&mut self, sensor: &SensorFile, units: &UnitSystem) -> Result<()> {
+        let unit =
+            Unit::parse(&sensor.unit).map_err(|e| anyhow!("sensor '{}': {}", sensor.name, e))?;
+        let to = units.channel_unit(unit.quantity)?;
+        for value in self.values.iter_mut() {
+            *value = unit.convert(sensor.gain * *value + sensor.correction, &to)?;
+        }
+        self.unit = to.symbol.to_string();
+        Ok(())
+    }
+}
+
+/// A set of channels sampled at a constant rate on a common time axis.
+///
+/// The sample rate is inferred from the median timestamp increment. Missing samples
+/// are recorded as `gaps` rather than rejected, so callers that need uniform sampling
+/// (PSD estimation) can refuse the series while duration-based quantities stay correct.
+#[derive(Debug, Clone)]
+pub struct SampledSeries {
+    /// The timestamps in seconds.
+    pub time: Vec<f64>,
+    /// The inferred sample rate in Hz.
+    pub sample_rate: f64,
+    /// The gaps detected in the time axis.
+    pub gaps: Vec<Gap>,
+    /// The data channels.
+    pub channels: Vec<Channel>,
+}
+
+impl SampledSeries {
+    /// Creates a series from timestamps and channels, validating the time axis.
+    ///
+    /// # Returns
+    ///
+    /// Returns an error if there are fewer than two timestamps, a timestamp is not
+    /// finite, the timestamps are not strictly increasing, an increment deviates from the
+    /// nominal interval without being a gap, or a channel length differs from the time axis.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fatigue::timeseries::{Channel, SampledSeries};
+    ///
+    /// let fx = Channel { name: "Fx".into(), unit: "N".into(), values: vec![1.0, 2.0, 3.0, 4.0] };
+    /// let series = SampledSeries::new(vec![0.0, 0.5, 1.0, 2.0], vec![fx]).unwrap();
+    /// assert_eq!(series.sample_rate, 2.0);
+    /// assert_eq!(series.gaps[0].missing, 1);
+    /// assert_eq!(series.duration(), 2.5);
+    ///
+    /// assert!(SampledSeries::new(vec![0.0, 1.0, 0.5], vec![]).is_err());
+    /// ```
+    pub fn new(time: Vec<f64>, channels: Vec<Channel>) -> Result<Self> {
+        if time.len() < 2 {
+            return Err(anyhow!(
+                "at least 2 timestamps are needed, got {}",
+                time.len()
+            ));
+        }
+        if let Some(index) = time.iter().position(|t| !t.is_finite()) {
+            return Err(anyhow!("timestamp at sample {} is not finite", index));
+        }
+        for channel in &channels {
+            if channel.values.len() != time.len() {
+                return Err(anyhow!(
+                    "channel '{}' has {} values for {} timestamps",
+                    channel.name,
+                    channel.values.len(),
+                    time.len()
+                ));
+            }
+        }
+        let increments: Vec<f64> = time.windows(2).map(|w| w[1] - w[0]).collect();
+        if let Some(index) = increments.iter().position(|dt| *dt <= 0.0) {
+            return Err(anyhow!(
+                "timestamps must be strictly increasing, got {} after {} at sample {}",
+                time[index + 1],
+                time[index],
+                index + 1
+            ));
+        }
+        let mut sorted = increments.clone();
+        sorted.sort_by(|a, b| a.total_cmp(b));
+        let interval = sorted[sorted.len() / 2];
+
+        let mut gaps = Vec::new();
+        for (index, &dt) in increments.iter().enumerate() {
+            let steps = (dt / interval).round();
+            if (dt - steps * interval).abs() > SAMPLING_TOLERANCE * steps * interval || steps < 1.0
+            {
+                return Err(anyhow!(
+                    "irregular sample interval {} at sample {}, expected a multiple of {}",
+                    dt,
+                    index + 1,
+                    interval
+                ));
+            }
+            if steps >= 2.0 {
+                gaps.push(Gap {
+                    index,
+                    start: time[index],
+                    duration: dt,
+                    missing: steps as usize - 1,
+                });
+            }
+        }
+        Ok(SampledSeries {
+            time,
+            sample_rate: 1.0 / interval,
+            gaps,
+            channels,
+        })
+    }
+
+    /// Reads a delimited load-case file with a time column and one column per sensor.
+    ///
+    /// The first `parse_config.header` lines are skipped; the last of them, if any, names
+    /// the columns. The time column is the one named `timestamp` (ignoring case), or the
+    /// first column. With a header, each sensor is mapped to the column with its name,
+    /// compared case-insensitively; without one, to the zero-based column number
+    /// `SensorFile.no`. Its channel takes the sensor's name and is
+    /// calibrated with `Channel::calibrate`. Without sensors every named column becomes an
+    /// uncalibrated channel.
+    ///
+    /// # Returns
+    ///
+    /// Returns the validated series, or an error if the file cannot be read, a sensor is
+    /// missing from the header or has no column, a row has the wrong number of fields or a value is not a finite number (as a
+    /// `ParseError` with file, line and column), or the time axis is invalid.
+    pub fn read<P: AsRef<Path>>(
+        path: P,
+        parse_config: &ParseConfig,
+        sensors: &[SensorFile],
+        units: &UnitSystem,
+    ) -> Result<Self> {
+        let path = path.as_ref();
+        let content = read_to_string(path)
+            .map_err(|e| anyhow!("Failed to read load case {}: {}", path.display(), e))?;
+        let mut lines = content.lines().enumerate();
+        let mut names: Vec<&str> = Vec::new();
+        for _ in 0..parse_config.header {
+            let (_, line) = lines.next().ok_or_else(|| {
+                anyhow!(
+                    "{} has fewer than {} header lines",
+                    path.display(),
+                    parse_config.header
+                )
+            })?;
+            names = parse_config.split(line).collect();
+        }
+        let time_column = names
+            .iter()
+            .position(|n| n.eq_ignore_ascii_case("timestamp"))
+            .unwrap_or(0);
+
+        let mut channels: Vec<(usize, Channel)> = Vec::new();
+        if sensors.is_empty() {
+            for (column, name) in names.iter().enumerate() {
+                if column != time_column {
+                    channels.push((column, Channel::new(name, "")));
+                }
+            }
+        } else {
+            for sensor in sensors {
+                let column = if names.is_empty() {
+                    sensor.no
+                } else {
+                    names
+                        .iter()
+                        .position(|n| n.eq_ignore_ascii_case(&sensor.name))
+                        .ok_or_else(|| {
+                            anyhow!(
+                                "sensor '{}' is not in the header of {}",
+                                sensor.name,
+                                path.display()
+                            )
+                        })?
+                };
+                if column == time_column {
+                    return Err(anyhow!(
+                        "sensor '{}' (no {}) maps to the time column of {}",
+                        sensor.name,
+                        sensor.no,
+                        path.display()
+                    ));
+                }
+                channels.push((column, Channel::new(&sensor.name, "")));
+            }
+        }
+
+        let mut width = (!names.is_empty()).then_some(names.len());
+        let mut slots: Vec<Option<usize>> = Vec::new();
+        let mut time = Vec::new();
+        for (index, line) in lines {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let expected = *width.get_or_insert_with(|| parse_config.split(line).count());
+            if slots.is_empty() {
+                slots = vec![None; expected];
+                for (slot, (column, channel)) in channels.iter().enumerate() {
+                    match slots.get_mut(*column) {
+                        Some(entry) => *entry = Some(slot),
+                        None => {
+                            return Err(anyhow!(
+                                "sensor '{}' column {} is beyond the {} columns of {}",
+                                channel.name,
+                                column,
+                                expected,
+                                path.display()
+                            ))
+                        }
+                    }
+                }
+            }
+
+            let mut found = 0;
+            for (column, field) in parse_config.split(line).enumerate() {
+                found += 1;
+                let slot = slots.get(column).copied().flatten();
+                if column != time_column && slot.is_none() {
+                    continue;
+                }
+                let value = field
+                    .parse::<f64>()
+                    .ok()
+                    .filter(|value| value.is_finite())
+                    .ok_or_else(|| ParseError {
+                        file: path.to_path_buf(),
+                        line: index + 1,
+                        column: column + 1,
+                        message: format!("'{}' is not a finite number", field),
+                    })?;
+                match slot {
+                    Some(slot) => channels[slot].1.values.push(value),
+                    None => time.push(value),
+                }
+            }
+            if found != expected {
+                return Err(ParseError {
+                    file: path.to_path_buf(),
+                    line: index + 1,
+                    column: found.min(expected) + 1,
+                    message: format!("expected {} fields, found {}", expected, found),
+                }
+                .into());
+            }
+        }
+        let mut channels: Vec<Channel> = channels.into_iter().map(|(_, channel)| channel).collect();
+        for (channel, sensor) in channels.iter_mut().zip(sensors) {
+            channel
+                .calibrate(sensor, units)
+                .map_err(|e| anyhow!("{}: {}", path.display(), e))?;
+        }
+        SampledSeries::new(time, channels)
+            .map_err(|e| anyhow!("Invalid time series in {}: {}", path.display(), e))
+    }
+
+    /// Returns the covered duration in seconds, including the last sample interval.
+    pub fn duration(&self) -> f64 {
+        self.time[self.time.len() - 1] - self.time[0] + 1.0 / self.sample_rate
+    }
+
+    /// Returns `true` if the time axis has no gaps.
+    pub fn is_uniform(&self) -> bool {
+        self.gaps.is_empty()
+    }
+
+    /// Returns the named channel.
+    pub fn channel(&self, name: &str) -> Option<&Channel> {
+        self.channels.iter().find(|c| c.name == name)
+    }
+
+    /// Appends the derived channels of the variables that depend on channels.
+    ///
+    /// Variables are evaluated in `order`. A variable referencing a channel or another
+    /// derived channel is compiled with `CompiledExpression` and evaluated over the whole
+    /// channels into a new channel with an unknown unit; the other variables are evaluated
+    /// once from `parameters` and are available as constants.
+    ///
+    /// # Returns
+    ///
+    /// Returns the names of the derived channels, or an error if an expression cannot be
+    /// parsed or evaluated, or a variable has the name of an existing channel.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fatigue::timeseries::{Channel, SampledSeries};
+    /// use std::collections::HashMap;
+    ///
+    /// let fx = Channel { name: "Fx".into(), unit: "N".into(), values: vec![3.0, 0.0] };
+    /// let fy = Channel { name: "Fy".into(), unit: "N".into(), values: vec![4.0, 2.0] };
+    /// let mut series = SampledSeries::new(vec![0.0, 1.0], vec![fx, fy]).unwrap();
+    /// let variables = HashMap::from([("Fres".to_string(), "math::sqrt(Fx^2 + Fy^2)".to_string())]);
+    /// series.derive(&variables, &["Fres".to_string()], &HashMap::new()).unwrap();
+    /// assert_eq!(series.channel("Fres").unwrap().values, vec![5.0, 2.0]);
+    /// ```
+    pub fn derive(
+        &mut self,
+        variables: &HashMap<String, String>,
+        order: &[String],
+        parameters: &HashMap<String, f64>,
+    ) -> Result<Vec<String>> {
+        let names: Vec<&str> = self.channels.iter().map(|c| c.name.as_str()).collect();
+        let derived = derived_variables(variables, order, &names)?;
+        if derived.is_empty() {
+            return Ok(Vec::new());
+        }
+        let mut context = HashMapContext::new();
+        let mut constants = HashMap::with_capacity(parameters.len() + order.len());
+        for (key, value) in parameters {
+            context
+                .set_value(key.clone(), Value::Float(*value))
+                .map_err(|e| anyhow!("Failed to insert parameter '{}': {}", key, e))?;
+            constants.insert(key.clone(), Value::Float(*value));
+        }
+        for key in order {
+            if derived.iter().any(|(name, _)| name == key) {
+                continue;
+            }
+            let Some(expression) = variables.get(key) else {
+                continue;
+            };
+            let value = eval_with_context(expression, &context)
+                .map_err(|e| anyhow!("Failed to evaluate variable '{}': {}", key, e))?;
+            // Scalar variables become constants of the compiled channel expressions.
+            constants.insert(key.clone(), value.clone());
+            context
+                .set_value(key.clone(), value)
+                .map_err(|e| anyhow!("Failed to insert variable '{}': {}", key, e))?;
+        }
+
+        let mut added = Vec::with_capacity(derived.len());
+        for (name, _) in derived {
+            let names: Vec<&str> = self.channels.iter().map(|c| c.name.as_str()).collect();
+            let columns: Vec<&[f64]> = self.channels.iter().map(|c| c.values.as_slice()).collect();
+            let expression = CompiledExpression::compile(&variables[&name], &names, &constants)
+                .map_err(|e| anyhow!("variable '{}': {}", name, e))?;
+            let mut channel = Channel::new(&name, "");
+            channel.values = expression
+                .evaluate(&columns)
+                .map_err(|e| anyhow!("variable '{}': {}", name, e))?;
+            self.channels.push(channel);
+            added.push(name);
+        }
+        Ok(added)
+    }
+}
+
+/// Returns the parsed expressions of the variables that depend on the given channels,
+/// directly or through other derived variables, in evaluation order.
+///
+/// # Returns
+///
+/// Returns an error if a variable in `order` has no expression, an expression cannot be
+/// parsed, or a derived variable has the name of a channel.
+pub fn derived_variables(
+    variables: &HashMap<String, String>,
+    order: &[String],
+    channels: &[&str],
+) -> Result<Vec<(String, Node)>> {
+    let mut derived: Vec<(String, Node)> = Vec::new();
+    for key in order {
+        let expression = variables
+            .get(key)
+            .ok_or_else(|| anyhow!("Variable '{}' not found in config", key))?;
+        let node = build_operator_tree(expression)
+            .map_err(|e| anyhow!("Failed to parse variable '{}': {}", key, e))?;
+        let depends = node.iter_read_variable_identifiers().any(|identifier| {
+            channels.contains(&identifier) || derived.iter().any(|(name, _)| name == identifier)
+        });
+        if !depends {
+            continue;
+        }
+        if channels.contains(&key.as_str()) {
+            return Err(anyhow!(
+                "derived variable '{}' has the name of a channel",
+                key
+            ));
+        }
+        derived.push((key.clone(), node));
+    }
+    Ok(derived)
+}
+
+/// The Welch PSD estimated for one channel of one load case.
+#[derive(Debug, Clone)]
+pub struct ChannelPsd {
+    /// The load-case file the channel was read from.
+    pub loadcase: String,
+    /// The channel name.
+    pub channel: String,
+    /// The estimated one-sided PSD.
+    pub psd: Psd,
+}
+
+/// Settings for Welch PSD estimation of load-case channels.
+#[derive(Debug, Deserialize)]
+pub struct PsdConfig {
+    /// Number of samples per segment.
+    pub segment: usize,
+    /// Fraction of overlap between consecutive segments, in `[0.0, 1.0)`.
+    pub overlap: f64,
+    /// Segment window. Valid values are "RECTANGULAR", "HANN" and "HAMMING".
+    pub window: String,
+    /// Segment detrending. Valid values are "NONE", "CONSTANT" and "LINEAR".
+    pub detrend: String,
+    /// Channels to estimate. All channels are estimated when empty.
+    #[serde(default)]
+    pub channels: Vec<String>,
+}
+
+impl PsdConfig {
+    /// Validates the `PsdConfig` struct's fields.
+    ///
+    /// Ensures that `segment` is at least 2, `overlap` is in `[0.0, 1.0)` and that
+    /// `window` and `detrend` name known methods.
+    pub fn validate(&self) -> Result<()> {
+        if self.segment < 2 {
+            return Err(anyhow!("segment must be at least 2, got {}", self.segment));
+        }
+        if !(0.0..1.0).contains(&self.overlap) {
+            return Err(anyhow!(
+                "overlap must be between 0.0 and 1.0 (exclusive), got {}",
+                self.overlap
+            ));
+        }
+        Window::from_name(&self.window)?;
+        Detrend::from_name(&self.detrend)?;
+        Ok(())
+    }
+}
+
+/// A malformed value in a delimited input file, located by file, line and column.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// The file being parsed.
+    pub file: PathBuf,
+    /// The one-based line number.
+    pub line: usize,
+    /// The one-based column (field) number.
+    pub column: usize,
+    /// What was wrong with the value.
+    pub message: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}:{}:{}: {}",
+            self.file.display(),
+            self.line,
+            self.column,
+            self.message
+        )
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Describes how a delimited text file is laid out.
 #[derive(Debug, Deserialize)]
 pub struct ParseConfig {
+    /// Number of header lines before the data; the last one names the columns.
     pub header: usize,
+    /// Field delimiter. A delimiter of only whitespace splits on runs of spaces and tabs.
     pub delimiter: String,
 }
 
+impl Default for ParseConfig {
+    fn default() -> Self {
+        ParseConfig {
+            header: 1,
+            delimiter: ",".to_string(),
+        }
+    }
+}
+
 impl ParseConfig {
     pub fn validate(&self) -> Result<()> {
         if self.delimiter.is_empty() {
@@ -69,6 +632,46 @@ impl ParseConfig {
         }
         Ok(())
     }
+
+    /// Splits a line into trimmed fields according to the delimiter.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fatigue::timeseries::ParseConfig;
+    ///
+    /// let whitespace = ParseConfig { header: 0, delimiter: " ".into() };
+    /// let fields: Vec<_> = whitespace.split("  1.0 \t 2.0  3.0 ").collect();
+    /// assert_eq!(fields, vec!["1.0", "2.0", "3.0"]);
+    ///
+    /// let semicolon = ParseConfig { header: 0, delimiter: ";".into() };
+    /// let fields: Vec<_> = semicolon.split("1.0; ;3.0").collect();
+    /// assert_eq!(fields, vec!["1.0", "", "3.0"]);
+    /// ```
+    pub fn split<'a>(&'a self, line: &'a str) -> Fields<'a> {
+        if self.delimiter.trim().is_empty() {
+            Fields::Whitespace(line.split_whitespace())
+        } else {
+            Fields::Delimited(line.split(self.delimiter.as_str()))
+        }
+    }
+}
+
+/// Iterator over the fields of a line, see `ParseConfig::split`.
+pub enum Fields<'a> {
+    Whitespace(std::str::SplitWhitespace<'a>),
+    Delimited(std::str::Split<'a, &'a str>),
+}
+
+impl<'a> Iterator for Fields<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<&'a str> {
+        match self {
+            Fields::Whitespace(fields) => fields.next(),
+            Fields::Delimited(fields) => fields.next().map(str::trim),
+        }
+    }
 }
 
 /// Represents the interpolation properties for a structural analysis application.
@@ -82,6 +685,48 @@ pub struct Interpolation {
     pub dimension: usize,
     pub sensor: Vec<String>,
     pub points: Vec<Point>,
+    /// Optional unit of the stresses in the unit-load stress files, e.g. `MPa`.
+    /// Defaults to the stress unit of the configuration's unit system; stresses in another
+    /// stress unit are converted to it when read.
+    pub unit: Option<String>,
+    /// Whether the stress files are read through binary caches next to them, `false` by default.
+    /// Only `.usf` stress files are cached.
+    #[serde(default)]
+    pub cache: bool,
+    /// The format of the stress files: "USF" (default), "PCH" for Nastran punch files,
+    /// "RPT" for Abaqus field reports or "CSV".
+    #[serde(default = "default_stress_format")]
+    pub format: String,
+    /// The columns of the node and the stress components of "CSV" stress files.
+    pub columns: Option<StressColumns>,
+    /// The ascending coordinates of each axis of the grid of methods "LINEAR_GRID" and
+    /// "CUBIC_GRID". Detected from the points if omitted.
+    pub grid: Option<Vec<Vec<f64>>>,
+    /// The shape parameter of methods "RBF_MULTIQUADRIC" and "RBF_GAUSSIAN", in inverse units
+    /// of the coordinates, 1.0 by default.
+    pub shape: Option<f64>,
+    /// Whether the "RBF_*" methods add a linear polynomial to the radial functions, `true` by
+    /// default.
+    pub polynomial: Option<bool>,
+    /// The power of the distance in the weights of method "IDW", 2.0 by default.
+    pub power: Option<f64>,
+    /// The number of nearest points weighted per target by method "IDW"; all points by
+    /// default.
+    pub neighbors: Option<usize>,
+    /// What happens to points sharing coordinates: "ERROR" (default), or "FIRST", "LAST" or
+    /// "AVERAGE" to keep the stresses of the first or last of them or their mean.
+    pub duplicates: Option<String>,
+    /// What happens to load-case samples outside the points: "ALLOW" (default) or "WARN" to
+    /// interpolate them as they are, "CLAMP" to move them onto the points or "ERROR". The
+    /// points span their convex hull for "LINEAR_DELAUNAY", where "ALLOW" extends the nearest
+    /// simplex and "CLAMP" moves samples to the nearest point of the hull, the grid for
+    /// "LINEAR_GRID" and "CUBIC_GRID", and their bounding box for the other methods. Samples
+    /// outside the bounding box are counted per load case either way.
+    pub outside: Option<String>,
+}
+
+fn default_stress_format() -> String {
+    "USF".to_string()
 }
 
 /// Interpolation configuration for a structural analysis application.
@@ -89,12 +734,64 @@ impl Interpolation {
     pub fn validate(&self) -> Result<()> {
         self.parse_config.validate()?;
         match self.method.as_str() {
-            "LINEAR" | "NEAREST" | "NONE" => Ok(()),
+            "LINEAR" | "LINEAR_DELAUNAY" | "LINEAR_GRID" | "CUBIC_GRID" | "RBF_THIN_PLATE"
+            | "RBF_MULTIQUADRIC" | "RBF_GAUSSIAN" | "IDW" | "NEAREST" | "NONE" => Ok(()),
             _ => Err(anyhow!(
-                "method must be LINEAR, NEAREST, or NONE, got {}",
+                "method must be LINEAR, LINEAR_DELAUNAY, LINEAR_GRID, CUBIC_GRID, RBF_THIN_PLATE, RBF_MULTIQUADRIC, RBF_GAUSSIAN, IDW, NEAREST, or NONE, got {}",
                 self.method
             )),
         }?;
+        match (self.method.as_str(), self.shape) {
+            ("RBF_MULTIQUADRIC" | "RBF_GAUSSIAN", Some(shape))
+                if !shape.is_finite() || shape <= 0.0 =>
+            {
+                return Err(anyhow!("shape must be greater than 0.0, got {}", shape))
+            }
+            ("RBF_MULTIQUADRIC" | "RBF_GAUSSIAN", _) | (_, None) => (),
+            (_, Some(_)) => {
+                return Err(anyhow!(
+                    "shape is only used by methods RBF_MULTIQUADRIC and RBF_GAUSSIAN"
+                ))
+            }
+        }
+        if self.polynomial.is_some() && !self.method.starts_with("RBF_") {
+            return Err(anyhow!("polynomial is only used by RBF_* methods"));
+        }
+        match (self.method.as_str(), self.power) {
+            ("IDW", Some(power)) if !power.is_finite() || power <= 0.0 => {
+                return Err(anyhow!("power must be greater than 0.0, got {}", power))
+            }
+            ("IDW", _) | (_, None) => (),
+            (_, Some(_)) => return Err(anyhow!("power is only used by method IDW")),
+        }
+        match (self.method.as_str(), self.neighbors) {
+            ("IDW", Some(0)) => return Err(anyhow!("neighbors must be greater than 0")),
+            ("IDW", _) | (_, None) => (),
+            (_, Some(_)) => return Err(anyhow!("neighbors are only used by method IDW")),
+        }
+        if let Some(grid) = &self.grid {
+            if !self.method.ends_with("_GRID") {
+                return Err(anyhow!(
+                    "grid is only used by methods LINEAR_GRID and CUBIC_GRID"
+                ));
+            }
+            if grid.len() != self.dimension {
+                return Err(anyhow!(
+                    "grid must have {} axes, got {}",
+                    self.dimension,
+                    grid.len()
+                ));
+            }
+            if let Some(axis) = grid
+                .iter()
+                .find(|axis| axis.len() < 2 || axis.windows(2).any(|pair| pair[1] <= pair[0]))
+            {
+                return Err(anyhow!(
+                    "grid axes must have at least 2 ascending values, got {:?}",
+                    axis
+                ));
+            }
+        }
         if self.name.trim().is_empty() {
             return Err(anyhow!("name must not be empty"));
         }
@@ -102,36 +799,971 @@ impl Interpolation {
         if self.path.trim().is_empty() {
             return Err(anyhow!("path must not be empty"));
         }
-        if self.scale < 0.0 {
-            return Err(anyhow!(
-                "scale must be greater than 0.0, got {}",
-                self.scale
+        if self.scale < 0.0 {
+            return Err(anyhow!(
+                "scale must be greater than 0.0, got {}",
+                self.scale
+            ));
+        }
+        // Validate the dimension and sensor vector length condition
+        if self.sensor.len() != self.dimension {
+            return Err(anyhow!("When dimension is {}, the sensor vector must also have a length of 3. Found length: {}", self.dimension, self.sensor.len()));
+        }
+        if self.sensor.is_empty() {
+            return Err(anyhow!("sensor must not be empty"));
+        }
+
+        if self.points.is_empty() {
+            return Err(anyhow!("points must not be empty"));
+        }
+        match self.format.as_str() {
+            "USF" | "PCH" | "RPT" | "CSV" => Ok(()),
+            _ => Err(anyhow!(
+                "format must be USF, PCH, RPT, or CSV, got {}",
+                self.format
+            )),
+        }?;
+        match (self.format.as_str(), &self.columns) {
+            ("CSV", None) => return Err(anyhow!("format CSV requires columns")),
+            ("CSV", Some(columns)) => columns.validate()?,
+            (_, Some(_)) => return Err(anyhow!("columns are only used by format CSV")),
+            (_, None) => (),
+        }
+        if self.cache && self.format != "USF" {
+            return Err(anyhow!("cache is only supported for format USF"));
+        }
+        if let Some(unit) = &self.unit {
+            let quantity = Unit::parse(unit)?.quantity;
+            if quantity != Quantity::Stress {
+                return Err(anyhow!(
+                    "unit of interpolation '{}' must be a stress unit, got {} ({})",
+                    self.name,
+                    unit,
+                    quantity
+                ));
+            }
+        }
+        for point in &self.points {
+            if point.file.as_ref().unwrap().trim().is_empty() {
+                return Err(anyhow!("file must not be empty"));
+            }
+            if point.coordinates.len() != self.dimension {
+                return Err(anyhow!("When dimension is {}, the values per point must also have a length of 3. Found length: {}", self.dimension, point.coordinates.len()));
+            }
+            if point.coordinates.is_empty() {
+                return Err(anyhow!("value must not be empty"));
+            }
+        }
+        match self.duplicates.as_deref() {
+            None | Some("ERROR" | "FIRST" | "LAST" | "AVERAGE") => (),
+            Some(duplicates) => {
+                return Err(anyhow!(
+                    "duplicates must be ERROR, FIRST, LAST, or AVERAGE, got {}",
+                    duplicates
+                ))
+            }
+        }
+        match self.outside.as_deref() {
+            None | Some("ALLOW" | "CLAMP" | "ERROR" | "WARN") => (),
+            Some(outside) => {
+                return Err(anyhow!(
+                    "outside must be ALLOW, CLAMP, ERROR, or WARN, got {}",
+                    outside
+                ))
+            }
+        }
+        let groups = duplicate_groups(&self.points);
+        let unique: Vec<Point> = self
+            .points
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| groups[*index] == *index)
+            .map(|(_, point)| point.clone())
+            .collect();
+        if let (DuplicatePoints::Error, Some(index)) = (
+            self.duplicate_points(),
+            (0..groups.len()).find(|index| groups[*index] != *index),
+        ) {
+            return Err(anyhow!(
+                "points {} and {} of interpolation '{}' share coordinates {:?}, set duplicates to FIRST, LAST, or AVERAGE to merge them",
+                self.points[groups[index]].file.as_deref().unwrap_or_default(),
+                self.points[index].file.as_deref().unwrap_or_default(),
+                self.name,
+                self.points[index].coordinates
+            ));
+        }
+        // LINEAR is a least-squares regression and stays defined on fewer dimensions.
+        let spanning = match self.method.as_str() {
+            "LINEAR_DELAUNAY" | "LINEAR_GRID" | "CUBIC_GRID" => true,
+            method => method.starts_with("RBF_") && self.polynomial.unwrap_or(true),
+        };
+        let span = affine_span(&unique);
+        if spanning && span < self.dimension {
+            return Err(anyhow!(
+                "points of interpolation '{}' span {} of {} dimensions, method {} needs all of them",
+                self.name,
+                span,
+                self.dimension,
+                self.method
+            ));
+        }
+        Ok(())
+    }
+
+    /// Returns what happens to samples outside the points, `Outside::Allow` by default.
+    pub fn outside_policy(&self) -> Outside {
+        match self.outside.as_deref() {
+            Some("CLAMP") => Outside::Clamp,
+            Some("ERROR") => Outside::Error,
+            Some("WARN") => Outside::Warn,
+            _ => Outside::Allow,
+        }
+    }
+
+    /// Returns whether the method applies `outside_policy` to the points itself, through the
+    /// `Extrapolation` of its strategy, rather than to their bounding box.
+    pub fn extrapolates(&self) -> bool {
+        matches!(
+            self.method.as_str(),
+            "LINEAR_DELAUNAY" | "LINEAR_GRID" | "CUBIC_GRID"
+        )
+    }
+
+    /// Returns what happens to points sharing coordinates, `DuplicatePoints::Error` by
+    /// default.
+    pub fn duplicate_points(&self) -> DuplicatePoints {
+        match self.duplicates.as_deref() {
+            Some("FIRST") => DuplicatePoints::First,
+            Some("LAST") => DuplicatePoints::Last,
+            Some("AVERAGE") => DuplicatePoints::Average,
+            _ => DuplicatePoints::Error,
+        }
+    }
+
+    /// Returns the interpolation strategy of the method.
+    ///
+    /// # Returns
+    ///
+    /// Returns an error if the method is "NONE", which does not interpolate.
+    pub fn strategy(&self) -> Result<InterpolationStrategyEnum> {
+        let extrapolation = match self.outside_policy() {
+            Outside::Allow | Outside::Warn => Extrapolation::Linear,
+            Outside::Clamp => Extrapolation::Clamp,
+            Outside::Error => Extrapolation::Error,
+        };
+        match self.method.as_str() {
+            "LINEAR" => Ok(InterpolationStrategyEnum::Linear(Linear)),
+            "LINEAR_DELAUNAY" => Ok(InterpolationStrategyEnum::Delaunay(Delaunay {
+                extrapolation,
+            })),
+            "LINEAR_GRID" | "CUBIC_GRID" => Ok(InterpolationStrategyEnum::Grid(Grid {
+                order: match self.method.as_str() {
+                    "CUBIC_GRID" => GridOrder::Cubic,
+                    _ => GridOrder::Linear,
+                },
+                extrapolation,
+                axes: self.grid.clone(),
+            })),
+            "RBF_THIN_PLATE" | "RBF_MULTIQUADRIC" | "RBF_GAUSSIAN" => {
+                Ok(InterpolationStrategyEnum::Rbf(Rbf {
+                    kernel: match self.method.as_str() {
+                        "RBF_THIN_PLATE" => RbfKernel::ThinPlate,
+                        "RBF_MULTIQUADRIC" => RbfKernel::Multiquadric,
+                        _ => RbfKernel::Gaussian,
+                    },
+                    shape: self.shape.unwrap_or(1.0),
+                    polynomial: self.polynomial.unwrap_or(true),
+                }))
+            }
+            "IDW" => Ok(InterpolationStrategyEnum::InverseDistance(
+                InverseDistance {
+                    power: self.power.unwrap_or(2.0),
+                    neighbors: self.neighbors,
+                },
+            )),
+            "NEAREST" => Ok(InterpolationStrategyEnum::NearestNeighbor(NearestNeighbor)),
+            method => Err(anyhow!(
+                "interpolation '{}' with method {} does not interpolate",
+                self.name,
+                method
+            )),
+        }
+    }
+
+    /// Reads the stress tensors of the nodes accepted by `select` from a stress file of the
+    /// interpolation in its `format`, through its binary cache if `cache` is set.
+    ///
+    /// Stresses in a `unit` other than the stress unit of `units` are converted to it.
+    pub fn read_stress_tensors<F>(
+        &self,
+        file: &str,
+        units: &UnitSystem,
+        select: F,
+    ) -> Result<Vec<(usize, StressTensor)>>
+    where
+        F: Fn(usize) -> bool,
+    {
+        let path = Path::new(&self.path).join(file);
+        let mut tensors = match (self.format.as_str(), &self.columns) {
+            ("USF", _) if self.cache => {
+                read_cached_stress_tensors(&path, &self.parse_config, select)
+            }
+            ("USF", _) => read_selected_stress_tensors(&path, &self.parse_config, select),
+            ("PCH", _) => read_punch_stress_tensors(&path, select),
+            ("RPT", _) => read_report_stress_tensors(&path, select),
+            ("CSV", Some(columns)) => {
+                read_csv_stress_tensors(&path, &self.parse_config, columns, select)
+            }
+            (format, _) => Err(anyhow!(
+                "interpolation '{}' cannot read stress files of format {}",
+                self.name,
+                format
+            )),
+        }?;
+        if let Some(unit) = &self.unit {
+            let factor = Unit::parse(unit)?
+                .convert(1.0, &units.unit(Quantity::Stress)?)
+                .map_err(|e| anyhow!("interpolation '{}': {}", self.name, e))?;
+            for (_, tensor) in tensors.iter_mut() {
+                tensor.scale(factor);
+            }
+        }
+        Ok(tensors)
+    }
+}
+
+/// The number of fields of a `.usf` row: the node number and six stress components.
+const USF_FIELDS: usize = 7;
+
+/// The width of a field in the fixed-width `.usf` layout.
+const USF_FIELD_WIDTH: usize = 17;
+
+/// Reads the unit-load stress tensors of a `.usf` stress file.
+///
+/// Each row holds a node number and the stress components `sxx syy szz sxy syz szx`.
+/// The first `parse_config.header` lines are skipped and blank lines are ignored. With a
+/// whitespace delimiter, rows of exactly seven 17-character fields are read by position,
+/// so fields without separating blanks are accepted; other rows are split on whitespace
+/// runs. Numbers may use `E` or Fortran `D` exponents, e.g. `-0.630434E-01` or `0.5D+02`.
+///
+/// # Returns
+///
+/// Returns the node numbers with their stress tensors in file order, or an error if the
+/// file cannot be read, has fewer lines than the header, or has a malformed row. Malformed
+/// rows are reported as a `ParseError` with file, line and field.
+pub fn read_stress_tensors_from_file<P: AsRef<Path>>(
+    path: P,
+    parse_config: &ParseConfig,
+) -> Result<Vec<(usize, StressTensor)>> {
+    read_selected_stress_tensors(path, parse_config, |_| true)
+}
+
+/// Reads the stress tensors of the nodes accepted by `select` from a `.usf` stress file.
+///
+/// Rows are parsed and validated as in `read_stress_tensors_from_file`, but tensors are
+/// only built and kept for selected nodes, e.g. `|node| selection.contains(node)` with the
+/// `config::NodeSelection` of `Solution.node`.
+///
+/// # Returns
+///
+/// Returns the selected node numbers with their stress tensors in file order, or the
+/// errors of `read_stress_tensors_from_file`.
+pub fn read_selected_stress_tensors<P, F>(
+    path: P,
+    parse_config: &ParseConfig,
+    select: F,
+) -> Result<Vec<(usize, StressTensor)>>
+where
+    P: AsRef<Path>,
+    F: Fn(usize) -> bool,
+{
+    let path = path.as_ref();
+    let file = File::open(path)
+        .map_err(|e| anyhow!("Failed to open stress file {}: {}", path.display(), e))?;
+    parse_usf(BufReader::new(file), path, parse_config, select)
+}
+
+/// Parses the rows of a `.usf` stress file, keeping the nodes accepted by `select`.
+fn parse_usf<R, F>(
+    mut reader: R,
+    path: &Path,
+    parse_config: &ParseConfig,
+    select: F,
+) -> Result<Vec<(usize, StressTensor)>>
+where
+    R: BufRead,
+    F: Fn(usize) -> bool,
+{
+    let mut tensors = Vec::new();
+    let mut line = String::new();
+    let mut number = 0;
+    loop {
+        line.clear();
+        let read = reader
+            .read_line(&mut line)
+            .map_err(|e| anyhow!("Failed to read stress file {}: {}", path.display(), e))?;
+        if read == 0 {
+            break;
+        }
+        number += 1;
+        if number <= parse_config.header {
+            continue;
+        }
+        let row = line.trim_end_matches(['\n', '\r']);
+        if row.trim().is_empty() {
+            continue;
+        }
+        let values = parse_usf_row(row, parse_config).map_err(|(column, message)| {
+            anyhow::Error::new(ParseError {
+                file: path.to_path_buf(),
+                line: number,
+                column,
+                message,
+            })
+        })?;
+        let node = values[0];
+        if node < 1.0 || node.fract() != 0.0 || node > u32::MAX as f64 {
+            return Err(anyhow::Error::new(ParseError {
+                file: path.to_path_buf(),
+                line: number,
+                column: 1,
+                message: format!("node number must be a positive integer, got {}", node),
+            }));
+        }
+        if !select(node as usize) {
+            continue;
+        }
+        let matrix = Matrix3::new(
+            values[1], values[4], values[6], values[4], values[2], values[5], values[6], values[5],
+            values[3],
+        );
+        tensors.push((node as usize, StressTensor::new(matrix)));
+    }
+    if number < parse_config.header {
+        return Err(anyhow!(
+            "{} has fewer than {} header lines",
+            path.display(),
+            parse_config.header
+        ));
+    }
+    Ok(tensors)
+}
+
+/// Parses the seven numbers of a `.usf` row, or returns the one-based field and a message.
+fn parse_usf_row(
+    row: &str,
+    parse_config: &ParseConfig,
+) -> Result<[f64; USF_FIELDS], (usize, String)> {
+    let fixed = parse_config.delimiter.trim().is_empty()
+        && row.len() == USF_FIELDS * USF_FIELD_WIDTH
+        && row.is_ascii();
+    let mut values = [0.0; USF_FIELDS];
+    let mut count = 0;
+    let mut store = |field: &str| {
+        count += 1;
+        if count > USF_FIELDS {
+            return Ok(());
+        }
+        values[count - 1] = parse_fortran_float(field.trim())
+            .ok_or_else(|| (count, format!("invalid number '{}'", field.trim())))?;
+        Ok(())
+    };
+    if fixed {
+        for start in (0..row.len()).step_by(USF_FIELD_WIDTH) {
+            store(&row[start..start + USF_FIELD_WIDTH])?;
+        }
+    } else {
+        for field in parse_config.split(row) {
+            store(field)?;
+        }
+    }
+    if count != USF_FIELDS {
+        return Err((
+            count.min(USF_FIELDS) + 1,
+            format!("expected {} fields, got {}", USF_FIELDS, count),
+        ));
+    }
+    Ok(values)
+}
+
+/// Parses a finite number that may use a Fortran `D` exponent; `NaN` and infinities are
+/// refused like any other invalid number.
+pub(crate) fn parse_fortran_float(field: &str) -> Option<f64> {
+    let value = match field.parse::<f64>() {
+        Ok(value) => Some(value),
+        // Only the rare Fortran double precision form pays for a copy.
+        Err(_) if field.contains(['D', 'd']) => field.replace(['D', 'd'], "E").parse().ok(),
+        Err(_) => None,
+    };
+    value.filter(|value: &f64| value.is_finite())
+}
+
+/// Magic bytes and format version at the start of a binary stress-tensor cache.
+const CACHE_MAGIC: &[u8; 8] = b"FATUSF\x00\x02";
+
+/// The component order of the cached tensors, recorded in the cache header.
+const CACHE_COMPONENTS: &[u8; 12] = b"XXYYZZXYYZZX";
+
+/// The size of the cache header: magic, parse configuration hash, source size, source
+/// modification time, source hash, node count and component order.
+const CACHE_HEADER: usize = 8 + 5 * 8 + 12;
+
+/// The size of a cached node: the node number and six components, all little-endian.
+const CACHE_RECORD: usize = 8 + 6 * 8;
+
+/// The FNV-1a offset basis of the cache hashes.
+const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+
+/// Returns the path of the binary cache of a stress file, the file name with `.cache` appended.
+pub fn cache_path(path: &Path) -> PathBuf {
+    let mut name = path.as_os_str().to_os_string();
+    name.push(".cache");
+    PathBuf::from(name)
+}
+
+/// Reads the stress tensors of the selected nodes through the binary cache of a `.usf` file.
+///
+/// The cache next to the source (see `cache_path`) holds every node of the file as
+/// little-endian numbers behind a header with a hash of the `parse_config`, the size,
+/// modification time and FNV-1a hash of the source, the node count and the component
+/// order `sxx syy szz sxy syz szx`. When size and modification time match, the cache is
+/// used without reading the source; otherwise the source is hashed, and the cache is used
+/// and its header refreshed if the hash still matches, or regenerated if not. The cache
+/// is read in one piece rather than memory-mapped, which would need a new dependency or
+/// `unsafe`.
+/// Failing to write the cache only costs the speed-up of the next run and is reported as
+/// a warning on standard error.
+///
+/// # Returns
+///
+/// Returns the selected node numbers with their stress tensors in file order, or the
+/// errors of `read_stress_tensors_from_file` when the source has to be parsed.
+pub fn read_cached_stress_tensors<P, F>(
+    path: P,
+    parse_config: &ParseConfig,
+    select: F,
+) -> Result<Vec<(usize, StressTensor)>>
+where
+    P: AsRef<Path>,
+    F: Fn(usize) -> bool,
+{
+    let path = path.as_ref();
+    let metadata = fs::metadata(path)
+        .map_err(|e| anyhow!("Failed to read stress file {}: {}", path.display(), e))?;
+    // A zero modification time is never trusted, so the source is hashed instead.
+    let modified = metadata
+        .modified()
+        .ok()
+        .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+        .map_or(0, |time| u64::try_from(time.as_nanos()).unwrap_or(0));
+    let mut header = CacheHeader {
+        config: config_hash(parse_config),
+        size: metadata.len(),
+        modified,
+        source: None,
+        count: 0,
+    };
+    let cache = cache_path(path);
+    let mut bytes = fs::read(&cache).unwrap_or_default();
+    let cached = CacheHeader::decode(&bytes).filter(|cached| cached.config == header.config);
+    if let Some(cached) = cached {
+        if modified != 0 && (cached.size, cached.modified) == (header.size, modified) {
+            if let Some(tensors) = decode_cache(&bytes, &cached, &select) {
+                return Ok(tensors);
+            }
+        } else {
+            let hash = source_hash(path)?;
+            header.source = Some(hash);
+            if cached.source == Some(hash) {
+                if let Some(tensors) = decode_cache(&bytes, &cached, &select) {
+                    // Record the new stamp so the next run skips hashing.
+                    header.count = cached.count;
+                    bytes[..CACHE_HEADER].copy_from_slice(&header.encode());
+                    write_cache(&cache, &bytes);
+                    return Ok(tensors);
+                }
+            }
+        }
+    }
+    let tensors = read_stress_tensors_from_file(path, parse_config)?;
+    header.source = match header.source {
+        Some(hash) => Some(hash),
+        None => Some(source_hash(path)?),
+    };
+    header.count = tensors.len() as u64;
+    write_cache(&cache, &encode_cache(&header, &tensors));
+    Ok(tensors
+        .into_iter()
+        .filter(|(node, _)| select(*node))
+        .collect())
+}
+
+/// The header of a binary stress-tensor cache.
+struct CacheHeader {
+    /// The hash of the parse configuration the source was read with.
+    config: u64,
+    /// The size of the source in bytes.
+    size: u64,
+    /// The modification time of the source in nanoseconds since the Unix epoch, 0 if unknown.
+    modified: u64,
+    /// The FNV-1a hash of the source, `None` until it is computed.
+    source: Option<u64>,
+    /// The number of cached nodes.
+    count: u64,
+}
+
+impl CacheHeader {
+    /// Encodes the header; an unknown source hash is written as 0.
+    fn encode(&self) -> [u8; CACHE_HEADER] {
+        let mut bytes = [0; CACHE_HEADER];
+        bytes[..8].copy_from_slice(CACHE_MAGIC);
+        let words = [
+            self.config,
+            self.size,
+            self.modified,
+            self.source.unwrap_or(0),
+            self.count,
+        ];
+        for (chunk, word) in bytes[8..48].chunks_exact_mut(8).zip(words) {
+            chunk.copy_from_slice(&word.to_le_bytes());
+        }
+        bytes[48..].copy_from_slice(CACHE_COMPONENTS);
+        bytes
+    }
+
+    /// Decodes the header of a cache, or returns `None` if it is of another format.
+    fn decode(bytes: &[u8]) -> Option<Self> {
+        let header = bytes.get(..CACHE_HEADER)?;
+        if &header[..8] != CACHE_MAGIC || &header[48..] != CACHE_COMPONENTS {
+            return None;
+        }
+        let word = |index: usize| -> Option<u64> {
+            let offset = 8 + 8 * index;
+            Some(u64::from_le_bytes(
+                header.get(offset..offset + 8)?.try_into().ok()?,
+            ))
+        };
+        Some(CacheHeader {
+            config: word(0)?,
+            size: word(1)?,
+            modified: word(2)?,
+            source: Some(word(3)?),
+            count: word(4)?,
+        })
+    }
+}
+
+/// Writes a cache, warning on standard error if it cannot be written.
+fn write_cache(cache: &Path, bytes: &[u8]) {
+    if let Err(e) = fs::write(cache, bytes) {
+        eprintln!(
+            "Warning: failed to write stress cache {}: {}",
+            cache.display(),
+            e
+        );
+    }
+}
+
+/// Folds words into an FNV-1a hash.
+fn fnv(hash: u64, word: u64) -> u64 {
+    (hash ^ word).wrapping_mul(0x0100_0000_01b3)
+}
+
+/// Returns the FNV-1a hash of the parse configuration a stress file is read with.
+fn config_hash(parse_config: &ParseConfig) -> u64 {
+    let hash = parse_config
+        .delimiter
+        .bytes()
+        .fold(FNV_OFFSET, |hash, byte| fnv(hash, byte as u64));
+    fnv(hash, parse_config.header as u64)
+}
+
+/// Returns the FNV-1a hash of a stress file.
+///
+/// The source is hashed in little-endian 64-bit words rather than bytes, which keeps the
+/// hash well below the cost of parsing the source.
+fn source_hash(path: &Path) -> Result<u64> {
+    let source = fs::read(path)
+        .map_err(|e| anyhow!("Failed to read stress file {}: {}", path.display(), e))?;
+    let mut words = source.chunks_exact(8);
+    let hash = words
+        .by_ref()
+        .filter_map(|chunk| chunk.try_into().ok().map(u64::from_le_bytes))
+        .fold(FNV_OFFSET, fnv);
+    Ok(words
+        .remainder()
+        .iter()
+        .fold(hash, |hash, byte| fnv(hash, *byte as u64)))
+}
+
+/// Encodes stress tensors behind a header into the binary cache format.
+fn encode_cache(header: &CacheHeader, tensors: &[(usize, StressTensor)]) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(CACHE_HEADER + tensors.len() * CACHE_RECORD);
+    bytes.extend_from_slice(&header.encode());
+    for (node, tensor) in tensors {
+        bytes.extend_from_slice(&(*node as u64).to_le_bytes());
+        let components = [
+            tensor.sxx(),
+            tensor.syy(),
+            tensor.szz(),
+            tensor.sxy(),
+            tensor.syz(),
+            tensor.szx(),
+        ];
+        for component in components {
+            bytes.extend_from_slice(&component.to_le_bytes());
+        }
+    }
+    bytes
+}
+
+/// Decodes the selected nodes of a binary cache, or returns `None` if it is truncated.
+fn decode_cache<F>(
+    bytes: &[u8],
+    header: &CacheHeader,
+    select: &F,
+) -> Option<Vec<(usize, StressTensor)>>
+where
+    F: Fn(usize) -> bool,
+{
+    let records = bytes.get(CACHE_HEADER..)?;
+    let count = usize::try_from(header.count).ok()?;
+    if records.len() != count.checked_mul(CACHE_RECORD)? {
+        return None;
+    }
+    let mut tensors = Vec::new();
+    for record in records.chunks_exact(CACHE_RECORD) {
+        let mut values = record
+            .chunks_exact(8)
+            .filter_map(|chunk| chunk.try_into().ok().map(u64::from_le_bytes));
+        let node = usize::try_from(values.next()?).ok()?;
+        if !select(node) {
+            continue;
+        }
+        let mut components = [0.0; 6];
+        for component in components.iter_mut() {
+            *component = f64::from_bits(values.next()?);
+        }
+        let [sxx, syy, szz, sxy, syz, szx] = components;
+        let matrix = Matrix3::new(sxx, sxy, szx, sxy, syy, syz, szx, syz, szz);
+        tensors.push((node, StressTensor::new(matrix)));
+    }
+    Some(tensors)
+}
+
+/// The width of the ID field and of the data fields of a punch record.
+const PUNCH_FIELD_WIDTH: usize = 18;
+
+/// The last column of the data fields of a punch record, followed by the line number.
+const PUNCH_DATA_END: usize = 72;
+
+/// The Abaqus stress components in the order `sxx syy szz sxy syz szx`.
+const REPORT_COMPONENTS: [&str; 6] = ["S11", "S22", "S33", "S12", "S23", "S13"];
+
+/// The one-based columns of the node number and the stress components of a CSV stress file.
+///
+/// The columns map any Voigt ordering of the file to the components of a `StressTensor`.
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct StressColumns {
+    /// The column of the node number.
+    pub node: usize,
+    /// The column of the normal stress in x.
+    pub sxx: usize,
+    /// The column of the normal stress in y.
+    pub syy: usize,
+    /// The column of the normal stress in z.
+    pub szz: usize,
+    /// The column of the shear stress in the xy-plane.
+    pub sxy: usize,
+    /// The column of the shear stress in the yz-plane.
+    pub syz: usize,
+    /// The column of the shear stress in the zx-plane.
+    pub szx: usize,
+}
+
+impl StressColumns {
+    /// Validates that the columns are greater than 0 and distinct.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fatigue::timeseries::StressColumns;
+    ///
+    /// let columns = StressColumns { node: 1, sxx: 2, syy: 3, szz: 4, sxy: 5, syz: 6, szx: 7 };
+    /// assert!(columns.validate().is_ok());
+    ///
+    /// let shared = StressColumns { szx: 2, ..columns };
+    /// assert!(shared.validate().is_err());
+    /// ```
+    pub fn validate(&self) -> Result<()> {
+        let columns = self.all();
+        if columns.contains(&0) {
+            return Err(anyhow!("columns must be greater than 0, got {:?}", columns));
+        }
+        for (index, column) in columns.iter().enumerate() {
+            if columns[..index].contains(column) {
+                return Err(anyhow!("column {} is used twice", column));
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the columns of the node and of `sxx syy szz sxy syz szx`.
+    fn all(&self) -> [usize; 7] {
+        [
+            self.node, self.sxx, self.syy, self.szz, self.sxy, self.syz, self.szx,
+        ]
+    }
+}
+
+/// Reads the stress tensors of the nodes accepted by `select` from a Nastran punch file.
+///
+/// Punch records hold an ID in columns 1-18 and up to three 18-character data fields in
+/// columns 19-72, continued on `-CONT-` lines; columns 73-80 hold the line number and `$`
+/// lines are headers. Each record holds the grid ID and the grid point stresses
+/// `NORMAL-X NORMAL-Y NORMAL-Z SHEAR-XY SHEAR-YZ SHEAR-ZX`, followed by fields such as the
+/// mean pressure and the Von Mises stress, which are ignored.
+///
+/// # Returns
+///
+/// Returns the selected grid IDs with their stress tensors in file order, or an error if
+/// the file cannot be read or a record is malformed, reported as a `ParseError`.
+pub fn read_punch_stress_tensors<P, F>(path: P, select: F) -> Result<Vec<(usize, StressTensor)>>
+where
+    P: AsRef<Path>,
+    F: Fn(usize) -> bool,
+{
+    let path = path.as_ref();
+    let content = read_stress_file(path)?;
+    let error = |line: usize, column: usize, message: String| {
+        anyhow::Error::new(ParseError {
+            file: path.to_path_buf(),
+            line,
+            column,
+            message,
+        })
+    };
+    // The records as the line of their ID, the grid ID and the data fields read so far.
+    let mut records: Vec<(usize, usize, Vec<f64>)> = Vec::new();
+    for (index, line) in content.lines().enumerate() {
+        let number = index + 1;
+        if line.starts_with('$') || line.trim().is_empty() {
+            continue;
+        }
+        let data = line.get(..PUNCH_DATA_END).unwrap_or(line);
+        if !data.is_ascii() {
+            return Err(error(number, 1, "punch records must be ASCII".to_string()));
+        }
+        let id = data.get(..PUNCH_FIELD_WIDTH).unwrap_or(data);
+        if id.starts_with("-CONT-") {
+            if records.is_empty() {
+                return Err(error(
+                    number,
+                    1,
+                    "continuation without a record".to_string(),
+                ));
+            }
+        } else {
+            let grid = id.trim().parse::<usize>().ok().filter(|grid| *grid > 0);
+            let grid = grid.ok_or_else(|| {
+                error(
+                    number,
+                    1,
+                    format!("expected a grid ID, got '{}'", id.trim()),
+                )
+            })?;
+            records.push((number, grid, Vec::new()));
+        }
+        let Some((_, _, values)) = records.last_mut() else {
+            continue;
+        };
+        let fields = data.get(PUNCH_FIELD_WIDTH..).unwrap_or_default();
+        for (field, start) in (0..fields.len()).step_by(PUNCH_FIELD_WIDTH).enumerate() {
+            let text = fields[start..(start + PUNCH_FIELD_WIDTH).min(fields.len())].trim();
+            if text.is_empty() {
+                continue;
+            }
+            let value = parse_fortran_float(text)
+                .ok_or_else(|| error(number, field + 2, format!("invalid number '{}'", text)))?;
+            values.push(value);
+        }
+    }
+    let mut tensors = Vec::new();
+    for (number, grid, values) in records {
+        let Some(components) = values.first_chunk::<6>() else {
+            return Err(error(
+                number,
+                1,
+                format!("expected 6 stress components, got {}", values.len()),
+            ));
+        };
+        if select(grid) {
+            tensors.push((grid, tensor(components)));
+        }
+    }
+    Ok(tensors)
+}
+
+/// Reads the stress tensors of the nodes accepted by `select` from an Abaqus field report.
+///
+/// Every table of the report whose header line starts with `Node` and labels the stress
+/// components `S11 S22 S33 S12 S13 S23` (e.g. `S.S11`, in any order) is read; the rows of a
+/// table start with an integer node label. Other lines, such as the `Minimum` and `At Node`
+/// summaries, are ignored.
+///
+/// # Returns
+///
+/// Returns the selected node labels with their stress tensors in file order, or an error if
+/// the file cannot be read, holds no stress table or has a malformed row, reported as a
+/// `ParseError`.
+pub fn read_report_stress_tensors<P, F>(path: P, select: F) -> Result<Vec<(usize, StressTensor)>>
+where
+    P: AsRef<Path>,
+    F: Fn(usize) -> bool,
+{
+    let path = path.as_ref();
+    let content = read_stress_file(path)?;
+    // The number of fields of the current table and the fields of the components.
+    let mut table: Option<(usize, [usize; 6])> = None;
+    let mut tables = 0;
+    let mut tensors = Vec::new();
+    for (index, line) in content.lines().enumerate() {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        match fields.first() {
+            Some(&"Node") => {
+                table = report_columns(&fields);
+                tables += table.is_some() as usize;
+                continue;
+            }
+            Some(first) if first.parse::<usize>().is_ok() => (),
+            // Blank lines and summaries such as `Minimum` are not rows.
+            _ => continue,
+        }
+        let Some((width, columns)) = table else {
+            continue;
+        };
+        let error = |column: usize, message: String| {
+            anyhow::Error::new(ParseError {
+                file: path.to_path_buf(),
+                line: index + 1,
+                column,
+                message,
+            })
+        };
+        if fields.len() != width {
+            return Err(error(
+                fields.len().min(width) + 1,
+                format!("expected {} fields, got {}", width, fields.len()),
             ));
         }
-        // Validate the dimension and sensor vector length condition
-        if self.sensor.len() != self.dimension {
-            return Err(anyhow!("When dimension is {}, the sensor vector must also have a length of 3. Found length: {}", self.dimension, self.sensor.len()));
+        let node = fields[0].parse::<usize>().unwrap_or_default();
+        if node == 0 {
+            return Err(error(1, "node label must be greater than 0".to_string()));
         }
-        if self.sensor.is_empty() {
-            return Err(anyhow!("sensor must not be empty"));
+        if !select(node) {
+            continue;
         }
+        let mut components = [0.0; 6];
+        for (component, column) in components.iter_mut().zip(columns) {
+            *component = parse_fortran_float(fields[column])
+                .ok_or_else(|| error(column + 1, format!("invalid number '{}'", fields[column])))?;
+        }
+        tensors.push((node, tensor(&components)));
+    }
+    if tables == 0 {
+        return Err(anyhow!(
+            "{} has no table of nodal stresses {}",
+            path.display(),
+            REPORT_COMPONENTS.join(" ")
+        ));
+    }
+    Ok(tensors)
+}
 
-        if self.points.is_empty() {
-            return Err(anyhow!("points must not be empty"));
+/// Returns the number of fields and the fields of the components of a report table header,
+/// or `None` if the header does not label all six stress components.
+fn report_columns(header: &[&str]) -> Option<(usize, [usize; 6])> {
+    let mut columns = [0; 6];
+    for (column, name) in columns.iter_mut().zip(REPORT_COMPONENTS) {
+        *column = header
+            .iter()
+            .position(|label| label.rsplit(['.', '-', ':']).next() == Some(name))?;
+    }
+    Some((header.len(), columns))
+}
+
+/// Reads the stress tensors of the nodes accepted by `select` from a generic CSV stress file.
+///
+/// The first `parse_config.header` lines are skipped and rows are split per `parse_config`;
+/// the node number and the components are taken from the one-based `columns`, so the
+/// components may be in any Voigt ordering and other columns are ignored.
+///
+/// # Returns
+///
+/// Returns the selected node numbers with their stress tensors in file order, or an error if
+/// the file cannot be read or a row is malformed, reported as a `ParseError`.
+pub fn read_csv_stress_tensors<P, F>(
+    path: P,
+    parse_config: &ParseConfig,
+    columns: &StressColumns,
+    select: F,
+) -> Result<Vec<(usize, StressTensor)>>
+where
+    P: AsRef<Path>,
+    F: Fn(usize) -> bool,
+{
+    let path = path.as_ref();
+    let content = read_stress_file(path)?;
+    let columns = columns.all();
+    let mut tensors = Vec::new();
+    for (index, line) in content.lines().enumerate().skip(parse_config.header) {
+        if line.trim().is_empty() {
+            continue;
         }
-        for point in &self.points {
-            if point.file.as_ref().unwrap().trim().is_empty() {
-                return Err(anyhow!("file must not be empty"));
-            }
-            if point.coordinates.len() != self.dimension {
-                return Err(anyhow!("When dimension is {}, the values per point must also have a length of 3. Found length: {}", self.dimension, point.coordinates.len()));
-            }
-            if point.coordinates.is_empty() {
-                return Err(anyhow!("value must not be empty"));
-            }
+        let error = |column: usize, message: String| {
+            anyhow::Error::new(ParseError {
+                file: path.to_path_buf(),
+                line: index + 1,
+                column,
+                message,
+            })
+        };
+        let fields: Vec<&str> = parse_config.split(line).collect();
+        let mut values = [0.0; 7];
+        for (value, column) in values.iter_mut().zip(columns) {
+            let field = fields.get(column - 1).ok_or_else(|| {
+                error(
+                    column,
+                    format!("expected {} fields, got {}", column, fields.len()),
+                )
+            })?;
+            *value = parse_fortran_float(field)
+                .ok_or_else(|| error(column, format!("invalid number '{}'", field)))?;
+        }
+        let [node, components @ ..] = values;
+        if node < 1.0 || node.fract() != 0.0 || node > u32::MAX as f64 {
+            return Err(error(
+                columns[0],
+                format!("node number must be a positive integer, got {}", node),
+            ));
+        }
+        if select(node as usize) {
+            tensors.push((node as usize, tensor(&components)));
         }
-        Ok(())
     }
+    Ok(tensors)
+}
+
+/// Reads a stress file to a string.
+fn read_stress_file(path: &Path) -> Result<String> {
+    fs::read_to_string(path)
+        .map_err(|e| anyhow!("Failed to read stress file {}: {}", path.display(), e))
+}
+
+/// Returns the stress tensor of the components `sxx syy szz sxy syz szx`.
+fn tensor(components: &[f64; 6]) -> StressTensor {
+    let [sxx, syy, szz, sxy, syz, szx] = *components;
+    StressTensor::new(Matrix3::new(sxx, sxy, szx, sxy, syy, syz, szx, syz, szz))
 }
 
 /// Represents a series of time-dependent data used for structural analysis.
@@ -159,10 +1791,10 @@ impl TimeSeries {
         // Detailed validation of each component is performed to ensure data integrity.
 
         self.expressions.validate()?;
-        self.validate_variables_and_values()?;
         if !Path::new(&self.sensorfile).exists() {
             return Err(anyhow!("sensorfile does not exist"));
         }
+        self.validate_variables_and_values()?;
         if self.path.trim().is_empty() {
             return Err(anyhow!("path must not be empty"));
         }
@@ -178,6 +1810,10 @@ impl TimeSeries {
         if self.loadcases.is_empty() {
             return Err(anyhow!("loadcases must not be empty"));
         }
+        self.parse_config.validate()?;
+        if let Some(psd) = &self.psd {
+            psd.validate()?;
+        }
         for lc in &self.loadcases {
             // Construct the full path for the loadcase file
             lc.validate()?;
@@ -209,23 +1845,6 @@ impl TimeSeries {
         Ok(sensors)
     }
 
-    /// Validates the expressions defined within the `TimeSeries` configuration.
-    ///
-    /// This method checks if any of the defined expressions contain valid variable names
-    /// as per the predefined rules and available data.
-    ///
-    /// # Arguments
-    ///
-    /// * `expression` - The expression string to validate.
-    /// * `valid_names` - A set of valid variable names to check against.
-    ///
-    /// # Returns
-    ///
-    /// Returns `true` if the expression is valid, otherwise returns `false`.
-    fn expression_valid(&self, expression: &str, valid_names: &HashSet<String>) -> bool {
-        // Simplified logic to check if the expression contains any valid variable names.
-        valid_names.iter().any(|name| expression.contains(name))
-    }
     /// Validates variables and values against predefined rules and configurations.
     ///
     /// This method checks the validity of variable names and values, ensuring they
@@ -237,21 +1856,7 @@ impl TimeSeries {
     /// returns a `ValidationError` with details on the specific validation failure.
     fn validate_variables_and_values(&self) -> Result<()> {
         // Validates variable names and values, ensuring compliance with rules.
-        let mut valid_names = HashSet::<String>::new();
         let re = Regex::new(r"^[a-zA-Z_][a-zA-Z0-9_]*$").unwrap();
-        // Add parameters and variables to valid names
-        self.parameters.iter().for_each(|(key, _)| {
-            valid_names.insert(key.clone());
-        });
-        self.variables.iter().for_each(|(key, _)| {
-            valid_names.insert(key.clone());
-        });
-        for interp in self.interpolations.iter() {
-            valid_names.insert(interp.name.clone());
-            interp.sensor.iter().for_each(|sensor| {
-                valid_names.insert(sensor.clone());
-            });
-        }
         for (key, value) in &self.variables {
             if !re.is_match(key) {
                 return Err(anyhow!("Invalid variable name: {}", key));
@@ -268,17 +1873,96 @@ impl TimeSeries {
                 return Err(anyhow!("parameter value must be a number, got {}", value));
             }
         }
-        // Validate variables expressions
-        for (name, expression) in &self.variables {
-            if !self.expression_valid(expression, &valid_names) {
+        // Validate variable expressions against parameters, channels and each other
+        let channels = self.channel_names()?;
+        let channels: Vec<&str> = channels.iter().map(String::as_str).collect();
+        self.evaluation_order(&channels)?;
+        Ok(())
+    }
+
+    /// Returns the names of the sensor channels defined in the sensor file.
+    fn channel_names(&self) -> Result<Vec<String>> {
+        let sensors = self
+            .read_sensorfile()
+            .map_err(|e| anyhow!("Failed to read sensor file: {}", e))?;
+        Ok(sensors.into_iter().map(|s| s.name).collect())
+    }
+
+    /// Returns the evaluation order of the `variables`, see `dependency_order`.
+    ///
+    /// Expressions may use the `parameters`, the given channel names and other variables.
+    pub fn evaluation_order(&self, channels: &[&str]) -> Result<Vec<String>> {
+        let mut known: Vec<&str> = self.parameters.keys().map(String::as_str).collect();
+        known.extend_from_slice(channels);
+        dependency_order(&self.variables, &self.expressions.order, &known)
+    }
+
+    /// Reads the time series of a load case, converting channels from the units of the
+    /// sensor file to `units`, and appends the channels derived from `variables`.
+    pub fn read_loadcase(&self, lc: &LoadCase, units: &UnitSystem) -> Result<SampledSeries> {
+        let sensors = self
+            .read_sensorfile()
+            .map_err(|e| anyhow!("Failed to read sensor file: {}", e))?;
+        let path = Path::new(self.path.trim()).join(lc.file.trim());
+        let mut series = SampledSeries::read(&path, &self.parse_config, &sensors, units)?;
+        let channels: Vec<&str> = sensors.iter().map(|s| s.name.as_str()).collect();
+        let order = self.evaluation_order(&channels)?;
+        series
+            .derive(&self.variables, &order, &self.parameters)
+            .map_err(|e| anyhow!("Derived channels of {}: {}", path.display(), e))?;
+        Ok(series)
+    }
+
+    /// Estimates the Welch PSD of the configured channels of every load case.
+    ///
+    /// # Returns
+    ///
+    /// Returns one `ChannelPsd` per load case and channel, an empty vector if no `psd`
+    /// section is configured, or an error if a load case cannot be read or a channel is missing.
+    pub fn loadcase_psds(&self, units: &UnitSystem) -> Result<Vec<ChannelPsd>> {
+        let config = match &self.psd {
+            Some(config) => config,
+            None => return Ok(Vec::new()),
+        };
+        let window = Window::from_name(&config.window)?;
+        let detrend = Detrend::from_name(&config.detrend)?;
+        let mut psds = Vec::new();
+        for lc in &self.loadcases {
+            let series = self.read_loadcase(lc, units)?;
+            let path = Path::new(self.path.trim()).join(lc.file.trim());
+            if !series.is_uniform() {
                 return Err(anyhow!(
-                    "Invalid expression for variable '{}': {}",
-                    name,
-                    expression
+                    "{} has {} gaps in its time axis, PSD estimation requires uniform sampling",
+                    path.display(),
+                    series.gaps.len()
                 ));
             }
+            let names = if config.channels.is_empty() {
+                series.channels.iter().map(|c| c.name.clone()).collect()
+            } else {
+                config.channels.clone()
+            };
+            for name in names {
+                let channel = series
+                    .channel(&name)
+                    .ok_or_else(|| anyhow!("channel '{}' not found in {}", name, path.display()))?;
+                let psd = welch(
+                    &channel.values,
+                    series.sample_rate,
+                    config.segment,
+                    config.overlap,
+                    window,
+                    detrend,
+                )
+                .map_err(|e| anyhow!("PSD of '{}' in {}: {}", name, path.display(), e))?;
+                psds.push(ChannelPsd {
+                    loadcase: lc.file.clone(),
+                    channel: name,
+                    psd,
+                });
+            }
         }
-        Ok(())
+        Ok(psds)
     }
 
     pub fn parse_input(&self) -> Result<HashMap<String, Value>, Error> {
@@ -297,8 +1981,18 @@ impl TimeSeries {
             }
         }
 
+        // Variables that depend on channels are evaluated per sample by `SampledSeries::derive`.
+        let channels = self.channel_names()?;
+        let channels: Vec<&str> = channels.iter().map(String::as_str).collect();
+        let order = self.evaluation_order(&channels)?;
+        let derived = derived_variables(&self.variables, &order, &channels)?;
+        let order: Vec<String> = order
+            .into_iter()
+            .filter(|key| !derived.iter().any(|(name, _)| name == key))
+            .collect();
+
         // Insert variables into context with actual values
-        for key in &self.expressions.order {
+        for key in &order {
             let expression = self
                 .variables
                 .get(key)
@@ -325,7 +2019,7 @@ impl TimeSeries {
 
         let mut results = HashMap::new();
         // Evaluate expressions based on the specified order
-        for key in &self.expressions.order {
+        for key in &order {
             if let Some(expression) = self.variables.get(key) {
                 match eval_with_context(expression, &context) {
                     Ok(result) => {
@@ -350,26 +2044,23 @@ impl TimeSeries {
 
 /// Represents the order in which expressions should be evaluated in a structural analysis context.
 ///
-/// This struct holds an ordered list of expression names, defining the sequence in which
-/// calculations or operations should be executed. The order is critical for ensuring that
-/// dependencies between expressions are correctly managed, and results are accurate.
-#[derive(Debug, Deserialize)]
+/// The evaluation order of the `variables` is derived from their dependencies. An explicit
+/// `order` is optional; if given, it must list every variable after the variables it uses.
+#[derive(Debug, Default, Deserialize)]
 pub struct Expressions {
-    /// A list of expression names indicating the sequence of evaluation.
-    /// The list should not be empty to ensure a valid sequence of operations.
+    /// An optional list of variable names indicating the sequence of evaluation.
+    /// If empty, the order is sorted from the dependencies automatically.
+    #[serde(default)]
     pub order: Vec<String>,
 }
 
 impl Expressions {
-    /// Validates the `Expressions` configuration to ensure that the order of expressions is specified.
-    ///
-    /// Validation checks include verifying that the `order` vector is not empty,
-    /// indicating that there is at least one expression to evaluate.
+    /// Validates the `Expressions` configuration to ensure no variable is listed twice.
     ///
     /// # Returns
     ///
-    /// Returns `Ok(())` if the order of expressions is properly specified (i.e., the list is not empty).
-    /// Otherwise, returns a `ValidationError` with a message indicating that the order must not be empty.
+    /// Returns `Ok(())` if the order is empty or free of duplicates. Otherwise, returns a
+    /// `ValidationError` naming the duplicated variable.
     ///
     /// # Examples
     ///
@@ -381,32 +2072,226 @@ impl Expressions {
     /// };
     /// assert!(expressions.validate().is_ok());
     ///
-    /// let empty_expressions = Expressions { order: vec![] };
-    /// assert!(empty_expressions.validate().is_err());
-    /// ```
+    /// let automatic = Expressions { order: vec![] };
+    /// assert!(automatic.validate().is_ok());
     ///
-    /// This method ensures that the application has a clear, non-empty sequence of expressions to evaluate,
-    /// maintaining the integrity of the computational process.    
+    /// let duplicated = Expressions { order: vec![String::from("a"), String::from("a")] };
+    /// assert!(duplicated.validate().is_err());
+    /// ```
     pub fn validate(&self) -> Result<()> {
-        if self.order.is_empty() {
-            return Err(anyhow!("order must not be empty"));
+        let mut seen = HashSet::new();
+        for name in &self.order {
+            if !seen.insert(name) {
+                return Err(anyhow!("order lists variable '{}' twice", name));
+            }
         }
         Ok(())
     }
 }
 
+/// Returns the evaluation order of variable expressions from their dependencies.
+///
+/// Identifiers in an expression must be other variables or one of the `known` names
+/// (parameters and channels). With an empty `order` the variables are sorted topologically,
+/// alphabetically among independent variables; otherwise `order` is checked to list every
+/// variable after its dependencies.
+///
+/// # Returns
+///
+/// Returns the variable names in evaluation order, or an error naming the variable for an
+/// unparsable expression, an undefined identifier, a cyclic dependency or an invalid `order`.
+///
+/// # Examples
+///
+/// ```
+/// use fatigue::timeseries::dependency_order;
+/// use std::collections::HashMap;
+///
+/// let variables = HashMap::from([
+///     ("total".to_string(), "sum + a".to_string()),
+///     ("sum".to_string(), "a + b".to_string()),
+/// ]);
+/// let order = dependency_order(&variables, &[], &["a", "b"]).unwrap();
+/// assert_eq!(order, vec!["sum", "total"]);
+///
+/// let err = dependency_order(&variables, &[], &["a"]).unwrap_err();
+/// assert!(err.to_string().contains("undefined identifier 'b'"));
+/// ```
+pub fn dependency_order(
+    variables: &HashMap<String, String>,
+    order: &[String],
+    known: &[&str],
+) -> Result<Vec<String>> {
+    let mut dependencies: BTreeMap<&str, BTreeSet<&str>> = BTreeMap::new();
+    for (name, expression) in variables {
+        if known.contains(&name.as_str()) {
+            return Err(anyhow!(
+                "variable '{}' has the name of a parameter or channel",
+                name
+            ));
+        }
+        let node = build_operator_tree(expression)
+            .map_err(|e| anyhow!("Failed to parse variable '{}': {}", name, e))?;
+        let mut uses = BTreeSet::new();
+        for identifier in node.iter_read_variable_identifiers() {
+            match variables.get_key_value(identifier) {
+                Some((variable, _)) => {
+                    uses.insert(variable.as_str());
+                }
+                None if known.contains(&identifier) => (),
+                None => {
+                    return Err(anyhow!(
+                        "variable '{}' uses undefined identifier '{}'",
+                        name,
+                        identifier
+                    ))
+                }
+            }
+        }
+        dependencies.insert(name, uses);
+    }
+
+    if !order.is_empty() {
+        for (position, name) in order.iter().enumerate() {
+            let uses = dependencies
+                .get(name.as_str())
+                .ok_or_else(|| anyhow!("order lists variable '{}' which is not defined", name))?;
+            if let Some(later) = uses
+                .iter()
+                .find(|u| !order[..position].iter().any(|o| o == *u))
+            {
+                return Err(anyhow!(
+                    "order evaluates variable '{}' before its dependency '{}'",
+                    name,
+                    later
+                ));
+            }
+        }
+        if let Some(missing) = dependencies.keys().find(|n| !order.iter().any(|o| o == *n)) {
+            return Err(anyhow!("order does not list variable '{}'", missing));
+        }
+        return Ok(order.to_vec());
+    }
+
+    let mut sorted: Vec<String> = Vec::with_capacity(dependencies.len());
+    let mut remaining = dependencies;
+    while !remaining.is_empty() {
+        let ready: Vec<&str> = remaining
+            .iter()
+            .filter(|(_, uses)| uses.iter().all(|u| !remaining.contains_key(u)))
+            .map(|(name, _)| *name)
+            .collect();
+        if ready.is_empty() {
+            return Err(anyhow!("cyclic dependency: {}", find_cycle(&remaining)));
+        }
+        for name in ready {
+            remaining.remove(name);
+            sorted.push(name.to_string());
+        }
+    }
+    Ok(sorted)
+}
+
+/// Returns a dependency cycle among the unresolved variables as `a -> b -> a`.
+fn find_cycle(remaining: &BTreeMap<&str, BTreeSet<&str>>) -> String {
+    // Every unresolved variable uses another unresolved one, so following them must revisit a name.
+    let mut path: Vec<&str> = Vec::new();
+    let mut current = remaining.keys().next().copied();
+    while let Some(name) = current {
+        if let Some(start) = path.iter().position(|p| *p == name) {
+            path.push(name);
+            return path[start..].join(" -> ");
+        }
+        path.push(name);
+        current = remaining
+            .get(name)
+            .and_then(|uses| uses.iter().find(|u| remaining.contains_key(*u)).copied());
+    }
+    path.join(" -> ")
+}
+
+/// Reads a one-sided stress PSD from a two-column text file.
+///
+/// Each non-empty line holds a frequency in Hz and a density in stress squared per Hz,
+/// separated by a comma or whitespace. A non-numeric first line is treated as a header.
+///
+/// # Returns
+///
+/// Returns the `Psd`, or an error naming the offending line if a row is malformed.
+pub fn read_psd_file<P: AsRef<Path>>(path: P) -> Result<Psd> {
+    let path = path.as_ref();
+    let content = read_to_string(path)
+        .map_err(|e| anyhow!("Failed to read PSD file {}: {}", path.display(), e))?;
+    let mut frequencies = Vec::new();
+    let mut density = Vec::new();
+    for (index, line) in content.lines().enumerate() {
+        let fields: Vec<&str> = line
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|f| !f.is_empty())
+            .collect();
+        if fields.is_empty() {
+            continue;
+        }
+        let values: Result<Vec<f64>, _> = fields.iter().map(|f| f.parse::<f64>()).collect();
+        match values {
+            Ok(values) if values.len() == 2 => {
+                frequencies.push(values[0]);
+                density.push(values[1]);
+            }
+            Err(_) if index == 0 => continue,
+            _ => {
+                return Err(anyhow!(
+                    "{}:{}: expected frequency and density, got '{}'",
+                    path.display(),
+                    index + 1,
+                    line.trim()
+                ))
+            }
+        }
+    }
+    Psd::new(frequencies, density).map_err(|e| anyhow!("Invalid PSD in {}: {}", path.display(), e))
+}
+
+/// A sensor definition from the sensor file.
 #[derive(Debug, Deserialize)]
 pub struct SensorFile {
+    /// Zero-based column of the sensor in the load-case files, used when no header names it.
     pub no: usize,
+    /// Offset added to the raw values, in the sensor's unit.
     pub correction: f64,
+    /// Gain applied to the raw values before the offset, 1.0 by default.
+    #[serde(default = "default_gain")]
+    pub gain: f64,
+    /// The sensor's unit, e.g. `[kN]`; see `units::Unit::parse`.
     pub unit: String,
+    /// The sensor name referenced by `Interpolation.sensor`.
     pub name: String,
+    /// A description of the sensor.
     pub description: String,
 }
 
+fn default_gain() -> f64 {
+    1.0
+}
+
 #[cfg(test)]
 mod tests {
+    use super::{
+        cache_path, dependency_order, parse_fortran_float, read_cached_stress_tensors,
+        read_csv_stress_tensors, read_psd_file, read_punch_stress_tensors,
+        read_report_stress_tensors, read_selected_stress_tensors, read_stress_tensors_from_file,
+        CacheHeader, Channel, Interpolation, ParseConfig, ParseError, Point, SampledSeries,
+        SensorFile, StressColumns, CACHE_HEADER, CACHE_RECORD,
+    };
     use crate::config::load_config; // Ensure this is correctly imported
+    use crate::stress::StressTensor;
+    use crate::units::UnitSystem;
+    use anyhow::Result;
+    use approx::assert_relative_eq;
+    use std::collections::HashMap;
+    use std::fs;
+    use std::path::PathBuf;
+    use std::time::UNIX_EPOCH;
 
     #[test]
     fn test_parse_input() {
@@ -447,4 +2332,619 @@ mod tests {
             "Should match 22.0510832"
         );
     }
+
+    #[test]
+    fn test_read_psd_file() {
+        let path: PathBuf = ["tests", "spectral", "stress_psd.csv"].iter().collect();
+        let psd = read_psd_file(&path).expect("Failed to read PSD file");
+        assert_eq!(psd.frequencies.len(), psd.density.len());
+        assert!(psd.moments().m0 > 0.0);
+
+        let path: PathBuf = ["tests", "spectral", "stress_psd_malformed.csv"]
+            .iter()
+            .collect();
+        let err = read_psd_file(&path).unwrap_err();
+        assert!(format!("{err:#}").contains("stress_psd_malformed.csv:4"));
+    }
+
+    #[test]
+    fn test_loadcase_psds() {
+        let config = load_config("tests/config.yaml").expect("Failed to load config");
+        let psds = config
+            .timeseries
+            .loadcase_psds(&config.units)
+            .expect("Failed to estimate PSDs");
+        assert_eq!(psds.len(), config.timeseries.loadcases.len());
+        let first = &psds[0];
+        assert_eq!(first.channel, "Fx");
+        // The example load cases are sampled once per second.
+        assert!((first.psd.frequencies.last().unwrap() - 0.5).abs() < 1e-12);
+
+        let series = config
+            .timeseries
+            .read_loadcase(&config.timeseries.loadcases[0], &config.units)
+            .expect("Failed to read load case");
+        let names: Vec<_> = series.channels.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, vec!["Fx", "Fy", "Fz"]);
+        assert!((series.sample_rate - 1.0).abs() < 1e-12);
+        assert!((series.duration() - 600.0).abs() < 1e-9);
+        let variance = {
+            let fx = &series.channel("Fx").unwrap().values;
+            let mean = fx.iter().sum::<f64>() / fx.len() as f64;
+            fx.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / fx.len() as f64
+        };
+        assert!((first.psd.moment(0) - variance).abs() / variance < 0.25);
+    }
+
+    #[test]
+    fn test_sampled_series_time_axis() {
+        let channel = |values: Vec<f64>| Channel {
+            name: "Fx".to_string(),
+            unit: "N".to_string(),
+            values,
+        };
+        let series = SampledSeries::new(
+            vec![0.0, 0.01, 0.02, 0.05, 0.06],
+            vec![channel(vec![0.0; 5])],
+        )
+        .unwrap();
+        assert!((series.sample_rate - 100.0).abs() < 1e-9);
+        assert_eq!(series.gaps.len(), 1);
+        assert_eq!(series.gaps[0].index, 2);
+        assert_eq!(series.gaps[0].missing, 2);
+        assert!(!series.is_uniform());
+
+        let err = SampledSeries::new(vec![0.0, 1.0, 1.0, 2.0], vec![]).unwrap_err();
+        assert!(format!("{err:#}").contains("strictly increasing"));
+        let err = SampledSeries::new(vec![0.0, 1.0, 2.0, 2.5, 3.5], vec![]).unwrap_err();
+        assert!(format!("{err:#}").contains("irregular sample interval"));
+        let err = SampledSeries::new(vec![0.0, 1.0], vec![channel(vec![0.0])]).unwrap_err();
+        assert!(format!("{err:#}").contains("1 values for 2 timestamps"));
+    }
+
+    fn sensor(no: usize, name: &str) -> SensorFile {
+        SensorFile {
+            no,
+            correction: 0.0,
+            gain: 1.0,
+            unit: "[kN]".to_string(),
+            name: name.to_string(),
+            description: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_read_whitespace_loadcase() {
+        let path: PathBuf = ["tests", "timeseries", "loadcase_whitespace.txt"]
+            .iter()
+            .collect();
+        let parse_config = ParseConfig {
+            header: 2,
+            delimiter: " ".to_string(),
+        };
+        // Names are matched case-insensitively, the column numbers are ignored.
+        let sensors = vec![sensor(7, "Fx"), sensor(9, "Fy")];
+        let series =
+            SampledSeries::read(&path, &parse_config, &sensors, &UnitSystem::default()).unwrap();
+        assert_eq!(series.time, vec![0.0, 0.5, 1.0, 1.5]);
+        assert_eq!(
+            series.channel("Fx").unwrap().values,
+            vec![1000.0, 2000.0, 3000.0, 4000.0]
+        );
+        assert_eq!(
+            series.channel("Fy").unwrap().values,
+            vec![-1000.0, -2000.0, -3000.0, -4000.0]
+        );
+        assert_eq!(series.channel("Fy").unwrap().unit, "N");
+
+        let mut unknown = sensor(1, "Fx");
+        unknown.unit = "[lbf]".to_string();
+        let err = SampledSeries::read(&path, &parse_config, &[unknown], &UnitSystem::default())
+            .unwrap_err();
+        assert!(format!("{err:#}").contains("sensor 'Fx': unknown unit '[lbf]'"));
+
+        let err = SampledSeries::read(
+            &path,
+            &parse_config,
+            &[sensor(2, "Mx")],
+            &UnitSystem::default(),
+        )
+        .unwrap_err();
+        assert!(format!("{err:#}").contains("sensor 'Mx' is not in the header"));
+    }
+
+    #[test]
+    fn test_read_loadcase_without_header() {
+        let path: PathBuf = ["tests", "timeseries", "loadcase_no_header.txt"]
+            .iter()
+            .collect();
+        let parse_config = ParseConfig {
+            header: 0,
+            delimiter: " ".to_string(),
+        };
+        // Without a header, sensors are mapped by their column number.
+        let series = SampledSeries::read(
+            &path,
+            &parse_config,
+            &[sensor(2, "Fy")],
+            &UnitSystem::default(),
+        )
+        .unwrap();
+        assert_eq!(series.channel("Fy").unwrap().values, vec![-1000.0, -2000.0]);
+
+        let err = SampledSeries::read(
+            &path,
+            &parse_config,
+            &[sensor(7, "Mx")],
+            &UnitSystem::default(),
+        )
+        .unwrap_err();
+        assert!(format!("{err:#}").contains("beyond the 4 columns"));
+    }
+
+    #[test]
+    fn test_read_malformed_loadcase() {
+        let path: PathBuf = ["tests", "timeseries", "loadcase_malformed.csv"]
+            .iter()
+            .collect();
+        let err = SampledSeries::read(&path, &ParseConfig::default(), &[], &UnitSystem::default())
+            .unwrap_err();
+        let parse_error = err
+            .downcast_ref::<ParseError>()
+            .expect("expected a ParseError");
+        assert_eq!((parse_error.line, parse_error.column), (3, 3));
+        assert!(parse_error.message.contains("'1.2.3'"));
+
+        let parse_config = ParseConfig {
+            header: 1,
+            delimiter: ";".to_string(),
+        };
+        let err =
+            SampledSeries::read(&path, &parse_config, &[], &UnitSystem::default()).unwrap_err();
+        let parse_error = err
+            .downcast_ref::<ParseError>()
+            .expect("expected a ParseError");
+        assert_eq!(parse_error.line, 2);
+
+        let path: PathBuf = ["tests", "timeseries", "loadcase_nan.csv"].iter().collect();
+        let err = SampledSeries::read(&path, &ParseConfig::default(), &[], &UnitSystem::default())
+            .unwrap_err();
+        let parse_error = err
+            .downcast_ref::<ParseError>()
+            .expect("expected a ParseError");
+        assert_eq!(parse_error.file, path);
+        assert_eq!((parse_error.line, parse_error.column), (3, 2));
+        assert!(parse_error.message.contains("'NaN' is not a finite number"));
+    }
+
+    #[test]
+    fn test_derived_channels() {
+        let channel = |name: &str, values: Vec<f64>| Channel {
+            name: name.to_string(),
+            unit: "N".to_string(),
+            values,
+        };
+        let mut series = SampledSeries::new(
+            vec![0.0, 1.0, 2.0],
+            vec![
+                channel("Fx", vec![1.0, 0.0, 2.0]),
+                channel("Fy", vec![0.0, 1.0, 2.0]),
+            ],
+        )
+        .unwrap();
+        let variables = HashMap::from([
+            ("angle".to_string(), "math::atan2(1, 0) * scale".to_string()),
+            (
+                "Fu".to_string(),
+                "Fx * math::cos(angle) + Fy * math::sin(angle)".to_string(),
+            ),
+            ("Fu2".to_string(), "2 * Fu".to_string()),
+        ]);
+        let order = vec!["angle".to_string(), "Fu".to_string(), "Fu2".to_string()];
+        let parameters = HashMap::from([("scale".to_string(), 1.0)]);
+        let added = series.derive(&variables, &order, &parameters).unwrap();
+        assert_eq!(added, vec!["Fu", "Fu2"]);
+        // Rotated by 90 degrees, the Fu axis points along Fy.
+        let fu = &series.channel("Fu").unwrap().values;
+        for (value, expected) in fu.iter().zip([0.0, 1.0, 2.0]) {
+            assert_relative_eq!(*value, expected, epsilon = 1e-12);
+        }
+        assert_relative_eq!(
+            series.channel("Fu2").unwrap().values[2],
+            4.0,
+            epsilon = 1e-12
+        );
+        assert_eq!(series.channel("Fu").unwrap().unit, "");
+        assert!(series.channel("angle").is_none());
+
+        let clash = HashMap::from([("Fx".to_string(), "Fy + 1".to_string())]);
+        let err = series
+            .derive(&clash, &["Fx".to_string()], &parameters)
+            .unwrap_err();
+        assert!(format!("{err:#}").contains("derived variable 'Fx' has the name of a channel"));
+
+        let config = load_config("tests/config.yaml").expect("Failed to load config");
+        let mut timeseries = config.timeseries;
+        timeseries
+            .variables
+            .insert("Fres".to_string(), "math::sqrt(Fx^2 + Fy^2)".to_string());
+        timeseries.expressions.order.push("Fres".to_string());
+        let series = timeseries
+            .read_loadcase(&timeseries.loadcases[0], &config.units)
+            .expect("Failed to read load case");
+        let fx = &series.channel("Fx").unwrap().values;
+        let fy = &series.channel("Fy").unwrap().values;
+        let fres = &series.channel("Fres").unwrap().values;
+        assert_eq!(fres.len(), fx.len());
+        assert_relative_eq!(fres[10], fx[10].hypot(fy[10]), max_relative = 1e-12);
+    }
+
+    #[test]
+    fn test_dependency_order() {
+        let variables: HashMap<String, String> = [
+            ("c", "a + b"),
+            ("b", "math::sqrt(a) * Fx"),
+            ("a", "p + 1"),
+            ("d", "max(p, 2)"),
+        ]
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+        let order = dependency_order(&variables, &[], &["p", "Fx"]).unwrap();
+        assert_eq!(order, vec!["a", "d", "b", "c"]);
+
+        let explicit: Vec<String> = ["d", "a", "b", "c"].iter().map(|s| s.to_string()).collect();
+        assert_eq!(
+            dependency_order(&variables, &explicit, &["p", "Fx"]).unwrap(),
+            explicit
+        );
+        let early: Vec<String> = ["d", "b", "a", "c"].iter().map(|s| s.to_string()).collect();
+        let err = dependency_order(&variables, &early, &["p", "Fx"]).unwrap_err();
+        assert!(format!("{err:#}").contains("evaluates variable 'b' before its dependency 'a'"));
+        let err = dependency_order(&variables, &explicit[..3], &["p", "Fx"]).unwrap_err();
+        assert!(format!("{err:#}").contains("does not list variable 'c'"));
+
+        let err = dependency_order(&variables, &[], &["p"]).unwrap_err();
+        assert!(format!("{err:#}").contains("variable 'b' uses undefined identifier 'Fx'"));
+        let err = dependency_order(&variables, &[], &["p", "Fx", "a"]).unwrap_err();
+        assert!(format!("{err:#}").contains("variable 'a' has the name of a parameter"));
+
+        let cyclic: HashMap<String, String> = [("x", "z + 1"), ("y", "x * 2"), ("z", "y - p")]
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        let err = dependency_order(&cyclic, &[], &["p"]).unwrap_err();
+        assert!(format!("{err:#}").contains("cyclic dependency: x -> z -> y -> x"));
+    }
+
+    #[test]
+    fn test_parse_input_without_order() {
+        let mut config = load_config("tests/config.yaml").expect("Failed to load config");
+        let ordered = config.timeseries.parse_input().unwrap();
+        config.timeseries.expressions.order.clear();
+        assert!(config.timeseries.validate().is_ok());
+        let sorted = config.timeseries.parse_input().unwrap();
+        assert_eq!(ordered, sorted);
+        assert_eq!(sorted.len(), config.timeseries.variables.len());
+    }
+
+    /// The first three nodes of the `.usf` file the export fixtures were written from.
+    fn expected() -> Vec<(usize, StressTensor)> {
+        let parse_config = ParseConfig {
+            header: 0,
+            delimiter: " ".into(),
+        };
+        read_selected_stress_tensors("tests/stressfile/FX0FY0FZ0.usf", &parse_config, |node| {
+            node <= 3
+        })
+        .unwrap()
+    }
+
+    fn assert_tensors_eq(left: &[(usize, StressTensor)], right: &[(usize, StressTensor)]) {
+        assert_eq!(left.len(), right.len());
+        for ((left_node, left), (right_node, right)) in left.iter().zip(right) {
+            assert_eq!(left_node, right_node);
+            let components =
+                |t: &StressTensor| [t.sxx(), t.syy(), t.szz(), t.sxy(), t.syz(), t.szx()];
+            for (a, b) in components(left).iter().zip(components(right)) {
+                assert_relative_eq!(*a, b, max_relative = 1e-6);
+            }
+        }
+    }
+
+    #[test]
+    fn test_read_stress_tensors_from_file() -> Result<()> {
+        // Assuming LoadCaseConfig is structured something like this
+        let interp = Interpolation {
+            method: "LINEAR".to_string(),         // Assuming interpolation method
+            name: "StressTimeseries".to_string(), // Name of the time series
+            path: "tests/stressfile".to_string(), // Base path to your test files
+            scale: 1.0,                           // Scale factor
+            dimension: 3,                         // Dimension for interpolation
+            sensor: vec!["FX".into(), "FY".into(), "FZ".into()], // Sensors for interpolation
+            points: vec![Point {
+                file: Some("Fx.usf".to_string()), // File for interpolation point
+                coordinates: vec![0.0, 0.0, 0.0], // Assuming no specific value is provided; adjust if necessary
+            }],
+            parse_config: ParseConfig {
+                header: 0,             // The unit-load files have no header
+                delimiter: " ".into(), // Fixed-width, space-padded values
+            },
+            unit: None,                // Stresses in the configuration's stress unit
+            cache: false,              // Parse the stress files on every read
+            format: "USF".to_string(), // Unit-load stress files
+            columns: None,             // Only used by CSV stress files
+            grid: None,                // Only used by grid methods
+            shape: None,               // Only used by RBF methods
+            polynomial: None,          // Only used by RBF methods
+            power: None,               // Only used by method IDW
+            neighbors: None,           // Only used by method IDW
+            duplicates: None,          // Points sharing coordinates are an error
+            outside: None,             // Samples outside the points are interpolated
+        };
+
+        for point in &interp.points {
+            // Check if `point.file` is `Some` and then construct the path
+            if let Some(ref file_name) = point.file {
+                let path = PathBuf::from(&interp.path).join(file_name); // Correctly constructs the path
+                let tensors = read_stress_tensors_from_file(&path, &interp.parse_config)?;
+                assert!(!tensors.is_empty(), "Tensors should not be empty");
+                assert_eq!(tensors.len(), 19382);
+                assert_eq!(tensors[0].0, 1);
+                assert_relative_eq!(tensors[0].1.sxx(), -0.0630434);
+                assert_relative_eq!(tensors[0].1.szx(), 1.83482);
+                assert_eq!(tensors[19381].0, 19382);
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_fortran_fixed_width_usf() {
+        let parse_config = ParseConfig {
+            header: 2,
+            delimiter: " ".into(),
+        };
+        let path: PathBuf = ["tests", "stressfile", "fortran_header.usf"]
+            .iter()
+            .collect();
+        let tensors = read_stress_tensors_from_file(&path, &parse_config).unwrap();
+        let nodes: Vec<usize> = tensors.iter().map(|(node, _)| *node).collect();
+        assert_eq!(nodes, vec![1, 2, 3]);
+        assert_relative_eq!(tensors[0].1.sxx(), -0.0630434);
+        assert_relative_eq!(tensors[0].1.syy(), 29.8352);
+        assert_relative_eq!(tensors[1].1.szz(), -1.0e-3);
+        // Node 3 has fields without separating blanks.
+        assert_relative_eq!(tensors[2].1.sxx(), -1.20316e3);
+        assert_relative_eq!(tensors[2].1.syy(), -0.339651);
+        assert_relative_eq!(tensors[2].1.sxy(), -0.150302);
+
+        let err = read_stress_tensors_from_file(
+            &path,
+            &ParseConfig {
+                header: 9,
+                ..parse_config
+            },
+        )
+        .unwrap_err();
+        assert!(format!("{err:#}").contains("fewer than 9 header lines"));
+    }
+
+    #[test]
+    fn test_read_malformed_usf() {
+        let parse_config = ParseConfig {
+            header: 0,
+            delimiter: " ".into(),
+        };
+        let path: PathBuf = ["tests", "stressfile", "malformed.usf"].iter().collect();
+        let err = read_stress_tensors_from_file(&path, &parse_config).unwrap_err();
+        let parse_error = err.downcast_ref::<ParseError>().unwrap();
+        assert_eq!((parse_error.line, parse_error.column), (3, 4));
+        assert!(format!("{err:#}").contains("invalid number '52.88x06'"));
+
+        let nan: PathBuf = ["tests", "stressfile", "nan.usf"].iter().collect();
+        let err = read_stress_tensors_from_file(&nan, &parse_config).unwrap_err();
+        let parse_error = err.downcast_ref::<ParseError>().unwrap();
+        assert_eq!(parse_error.file, nan);
+        assert_eq!((parse_error.line, parse_error.column), (2, 4));
+        assert!(format!("{err:#}").contains("invalid number 'NaN'"));
+        assert!(parse_fortran_float("inf").is_none());
+        assert!(parse_fortran_float("-Infinity").is_none());
+        assert_relative_eq!(parse_fortran_float("1.5D3").unwrap(), 1500.0);
+
+        let truncated: PathBuf = ["tests", "stressfile", "truncated.usf"].iter().collect();
+        let err = read_stress_tensors_from_file(&truncated, &parse_config).unwrap_err();
+        let parse_error = err.downcast_ref::<ParseError>().unwrap();
+        assert_eq!((parse_error.line, parse_error.column), (2, 7));
+        assert!(format!("{err:#}").contains("expected 7 fields, got 6"));
+
+        let fractional: PathBuf = ["tests", "stressfile", "fractional_node.usf"]
+            .iter()
+            .collect();
+        let err = read_stress_tensors_from_file(&fractional, &parse_config).unwrap_err();
+        assert!(format!("{err:#}").contains("node number must be a positive integer, got 1.5"));
+    }
+
+    #[test]
+    fn test_read_selected_stress_tensors() {
+        let parse_config = ParseConfig {
+            header: 0,
+            delimiter: " ".into(),
+        };
+        let path: PathBuf = ["tests", "stressfile", "Fx.usf"].iter().collect();
+        let tensors =
+            read_selected_stress_tensors(&path, &parse_config, |node| (3..=5).contains(&node))
+                .unwrap();
+        let nodes: Vec<usize> = tensors.iter().map(|(node, _)| *node).collect();
+        assert_eq!(nodes, vec![3, 4, 5]);
+        assert_relative_eq!(tensors[0].1.sxx(), 1.20316);
+
+        // Rows of unselected nodes are still validated.
+        let path: PathBuf = ["tests", "stressfile", "malformed.usf"].iter().collect();
+        let err = read_selected_stress_tensors(&path, &parse_config, |node| node == 1).unwrap_err();
+        assert!(err.downcast_ref::<ParseError>().is_some());
+    }
+
+    #[test]
+    fn test_read_cached_stress_tensors() {
+        let dir: PathBuf = [
+            "tests",
+            "stressfile",
+            &format!("cache-{}", std::process::id()),
+        ]
+        .iter()
+        .collect();
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("fortran_header.usf");
+        fs::copy(
+            ["tests", "stressfile", "fortran_header.usf"]
+                .iter()
+                .collect::<PathBuf>(),
+            &path,
+        )
+        .unwrap();
+        let stamp = |seconds: u64| {
+            let time = UNIX_EPOCH + std::time::Duration::from_secs(seconds);
+            fs::File::options()
+                .write(true)
+                .open(&path)
+                .unwrap()
+                .set_modified(time)
+                .unwrap();
+        };
+        stamp(1_000_000);
+        let parse_config = ParseConfig {
+            header: 2,
+            delimiter: " ".into(),
+        };
+        let parsed = read_stress_tensors_from_file(&path, &parse_config).unwrap();
+
+        // The first read parses the source and writes the cache, the second decodes it.
+        let written = read_cached_stress_tensors(&path, &parse_config, |_| true).unwrap();
+        let cache = cache_path(&path);
+        assert_eq!(
+            fs::metadata(&cache).unwrap().len() as usize,
+            CACHE_HEADER + parsed.len() * CACHE_RECORD
+        );
+        let cached = read_cached_stress_tensors(&path, &parse_config, |node| node != 1).unwrap();
+        assert_tensors_eq(&written, &parsed);
+        assert_tensors_eq(&cached, &parsed[1..]);
+
+        // With matching size and modification time the source is not read: a marked
+        // cache value is returned as is.
+        let mut bytes = fs::read(&cache).unwrap();
+        let sxx_offset = CACHE_HEADER + 8;
+        bytes[sxx_offset..sxx_offset + 8].copy_from_slice(&7.0_f64.to_le_bytes());
+        fs::write(&cache, &bytes).unwrap();
+        let marked = read_cached_stress_tensors(&path, &parse_config, |_| true).unwrap();
+        assert_relative_eq!(marked[0].1.sxx(), 7.0);
+
+        // A touched but unchanged source is hashed, and the cache kept with a new stamp.
+        stamp(2_000_000);
+        let touched = read_cached_stress_tensors(&path, &parse_config, |_| true).unwrap();
+        assert_relative_eq!(touched[0].1.sxx(), 7.0);
+        let header = CacheHeader::decode(&fs::read(&cache).unwrap()).unwrap();
+        assert_eq!(header.modified, 2_000_000 * 1_000_000_000);
+
+        // A changed source or parse configuration invalidates the cache.
+        let source = fs::read_to_string(&path).unwrap();
+        fs::write(&path, source.replacen("1.00000", "4.00000", 1)).unwrap();
+        stamp(3_000_000);
+        let changed = read_cached_stress_tensors(&path, &parse_config, |_| true).unwrap();
+        assert_eq!(changed[0].0, 4);
+        assert_tensors_eq(&changed[1..], &parsed[1..]);
+        let other = ParseConfig {
+            header: 3,
+            delimiter: " ".into(),
+        };
+        let skipped = read_cached_stress_tensors(&path, &other, |_| true).unwrap();
+        assert_eq!(skipped.len(), changed.len() - 1);
+
+        // A truncated cache is regenerated.
+        let bytes = fs::read(&cache).unwrap();
+        fs::write(&cache, &bytes[..bytes.len() - 1]).unwrap();
+        let regenerated = read_cached_stress_tensors(&path, &other, |_| true).unwrap();
+        assert_tensors_eq(&regenerated, &skipped);
+        assert_eq!(fs::read(&cache).unwrap(), bytes);
+
+        // A cache that cannot be written only costs the speed-up.
+        fs::remove_file(&cache).unwrap();
+        fs::create_dir(&cache).unwrap();
+        let unwritten = read_cached_stress_tensors(&path, &other, |_| true).unwrap();
+        assert_tensors_eq(&unwritten, &skipped);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_read_punch_stress_tensors() {
+        let tensors = read_punch_stress_tensors("tests/stressfile/unit_fx.pch", |_| true).unwrap();
+        assert_tensors_eq(&tensors, &expected());
+        let selected =
+            read_punch_stress_tensors("tests/stressfile/unit_fx.pch", |node| node == 2).unwrap();
+        assert_eq!(selected.len(), 1);
+        assert_eq!(selected[0].0, 2);
+    }
+
+    #[test]
+    fn test_read_malformed_punch_stress_tensors() {
+        let parse_error = |name: &str| {
+            let path = format!("tests/stressfile/{name}");
+            let err = read_punch_stress_tensors(path, |_| true).unwrap_err();
+            let parse_error = err.downcast_ref::<ParseError>().unwrap();
+            (
+                parse_error.line,
+                parse_error.column,
+                parse_error.message.clone(),
+            )
+        };
+        assert_eq!(
+            parse_error("invalid_number.pch"),
+            (4, 3, "invalid number 'abc'".to_string())
+        );
+        assert_eq!(
+            parse_error("orphan_continuation.pch"),
+            (3, 1, "continuation without a record".to_string())
+        );
+        assert_eq!(
+            parse_error("short_record.pch"),
+            (3, 1, "expected 6 stress components, got 5".to_string())
+        );
+    }
+
+    #[test]
+    fn test_read_report_stress_tensors() {
+        let tensors = read_report_stress_tensors("tests/stressfile/unit_fx.rpt", |_| true).unwrap();
+        assert_tensors_eq(&tensors, &expected());
+
+        let err = read_report_stress_tensors("tests/stressfile/unit_fx.pch", |_| true).unwrap_err();
+        assert!(format!("{err:#}").contains("has no table of nodal stresses"));
+    }
+
+    #[test]
+    fn test_read_csv_stress_tensors() {
+        let parse_config = ParseConfig {
+            header: 1,
+            delimiter: ";".into(),
+        };
+        let columns = StressColumns {
+            node: 1,
+            szz: 3,
+            sxx: 4,
+            syy: 5,
+            syz: 6,
+            szx: 7,
+            sxy: 8,
+        };
+        let path = "tests/stressfile/unit_fx.csv";
+        let tensors = read_csv_stress_tensors(path, &parse_config, &columns, |_| true).unwrap();
+        assert_tensors_eq(&tensors, &expected());
+
+        // The label column is text, so it cannot hold a component.
+        let label = StressColumns { sxy: 2, ..columns };
+        let err = read_csv_stress_tensors(path, &parse_config, &label, |_| true).unwrap_err();
+        let parse_error = err.downcast_ref::<ParseError>().unwrap();
+        assert_eq!((parse_error.line, parse_error.column), (2, 2));
+        assert!(parse_error.message.contains("invalid number 'hotspot'"));
+    }
 }
@@ -0,0 +1,308 @@
+//! A module for the units of measure of sensor channels.
+//!
+//! Sensor channels are converted on load to the declared `UnitSystem` the `.usf` unit-load
+//! stresses are expressed per, by default newton for forces, newton metre for moments and
+//! megapascal for stresses; strains are converted to plain strain (m/m).
+use anyhow::{anyhow, Result};
+use serde::Deserialize;
+use std::fmt;
+
+/// The physical quantity measured in a unit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Quantity {
+    /// Force, base unit N.
+    Force,
+    /// Moment, base unit Nm.
+    Moment,
+    /// Stress, base unit MPa.
+    Stress,
+    /// Strain, base unit m/m.
+    Strain,
+}
+
+impl Quantity {
+    /// Returns `true` for loads (forces and moments) that unit-load stress files can be scaled by.
+    pub fn is_load(&self) -> bool {
+        matches!(self, Quantity::Force | Quantity::Moment)
+    }
+}
+
+impl fmt::Display for Quantity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Quantity::Force => "force",
+            Quantity::Moment => "moment",
+            Quantity::Stress => "stress",
+            Quantity::Strain => "strain",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Known units as symbol, quantity and factor to the base unit of the quantity.
+const UNITS: &[(&str, Quantity, f64)] = &[
+    ("N", Quantity::Force, 1.0),
+    ("kN", Quantity::Force, 1.0e3),
+    ("MN", Quantity::Force, 1.0e6),
+    ("Nm", Quantity::Moment, 1.0),
+    ("kNm", Quantity::Moment, 1.0e3),
+    ("MNm", Quantity::Moment, 1.0e6),
+    ("Pa", Quantity::Stress, 1.0e-6),
+    ("kPa", Quantity::Stress, 1.0e-3),
+    ("MPa", Quantity::Stress, 1.0),
+    ("GPa", Quantity::Stress, 1.0e3),
+    ("με", Quantity::Strain, 1.0e-6),
+    ("µε", Quantity::Strain, 1.0e-6),
+    ("ue", Quantity::Strain, 1.0e-6),
+    ("m/m", Quantity::Strain, 1.0),
+];
+
+/// A unit of measure with its quantity and conversion factor to the base unit.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Unit {
+    /// The unit symbol as listed in the unit table.
+    pub symbol: &'static str,
+    /// The quantity measured.
+    pub quantity: Quantity,
+    /// The factor converting a value in this unit to the base unit.
+    pub factor: f64,
+}
+
+impl Unit {
+    /// Parses a unit symbol such as `N`, `[kNm]` or `kN·m`.
+    ///
+    /// Surrounding brackets and whitespace are ignored, and `·`, `*` and `.` between
+    /// the parts of a moment unit are accepted.
+    ///
+    /// # Returns
+    ///
+    /// Returns the `Unit`, or an error if the symbol is not a known unit.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fatigue::units::{Quantity, Unit};
+    ///
+    /// let unit = Unit::parse("[kN·m]").unwrap();
+    /// assert_eq!(unit.quantity, Quantity::Moment);
+    /// assert_eq!(unit.to_base(2.5), 2500.0);
+    /// assert!(Unit::parse("lbf").is_err());
+    /// ```
+    pub fn parse(symbol: &str) -> Result<Self> {
+        let trimmed = symbol.trim().trim_start_matches('[').trim_end_matches(']');
+        let normalized: String = trimmed
+            .trim()
+            .chars()
+            .filter(|c| !matches!(c, '·' | '*' | '.' | ' '))
+            .collect();
+        UNITS
+            .iter()
+            .find(|(s, _, _)| *s == normalized)
+            .map(|&(symbol, quantity, factor)| Unit {
+                symbol,
+                quantity,
+                factor,
+            })
+            .ok_or_else(|| {
+                anyhow!(
+                    "unknown unit '{}', expected one of {}",
+                    symbol,
+                    UNITS.iter().map(|u| u.0).collect::<Vec<_>>().join(", ")
+                )
+            })
+    }
+
+    /// Returns the base unit of this unit's quantity.
+    pub fn base(&self) -> Unit {
+        let symbol = match self.quantity {
+            Quantity::Force => "N",
+            Quantity::Moment => "Nm",
+            Quantity::Stress => "MPa",
+            Quantity::Strain => "m/m",
+        };
+        Unit {
+            symbol,
+            quantity: self.quantity,
+            factor: 1.0,
+        }
+    }
+
+    /// Converts a value in this unit to the base unit.
+    pub fn to_base(&self, value: f64) -> f64 {
+        value * self.factor
+    }
+
+    /// Converts a value in this unit to another unit of the same quantity.
+    ///
+    /// # Returns
+    ///
+    /// Returns the converted value, or an error if the units measure different quantities.
+    pub fn convert(&self, value: f64, to: &Unit) -> Result<f64> {
+        if self.quantity != to.quantity {
+            return Err(anyhow!(
+                "cannot convert {} ({}) to {} ({})",
+                self.symbol,
+                self.quantity,
+                to.symbol,
+                to.quantity
+            ));
+        }
+        Ok(value * self.factor / to.factor)
+    }
+}
+
+/// Parses a unit-annotated value such as `"210 GPa"` or `"355MPa"`.
+///
+/// # Returns
+///
+/// Returns the number and its unit, or an error if either part is missing or invalid.
+///
+/// # Examples
+///
+/// ```
+/// use fatigue::units::{parse_measure, Unit};
+///
+/// let (value, unit) = parse_measure("210 GPa").unwrap();
+/// let mpa = Unit::parse("MPa").unwrap();
+/// assert_eq!(unit.convert(value, &mpa).unwrap(), 210000.0);
+/// assert!(parse_measure("210").is_err());
+/// ```
+pub fn parse_measure(text: &str) -> Result<(f64, Unit)> {
+    let text = text.trim();
+    let split = text
+        .find(|c: char| !(c.is_ascii_digit() || matches!(c, '+' | '-' | '.' | 'e' | 'E')))
+        .ok_or_else(|| anyhow!("missing unit in '{}'", text))?;
+    let (number, symbol) = text.split_at(split);
+    let value: f64 = number
+        .trim()
+        .parse()
+        .map_err(|_| anyhow!("invalid number '{}' in '{}'", number.trim(), text))?;
+    let unit = Unit::parse(symbol).map_err(|e| anyhow!("'{}': {}", text, e))?;
+    Ok((value, unit))
+}
+
+/// Represents the unit system of the configuration.
+///
+/// Bare material values are taken to be in these units, unit-annotated values such as
+/// `"210 GPa"` are normalized to them on load, sensor channels are converted to them on
+/// load, and the unit-load stress files give stresses in `stress` per unit `force` or
+/// `moment`.
+#[derive(Debug, Deserialize)]
+pub struct UnitSystem {
+    /// The force unit, `N` by default.
+    #[serde(default = "default_force_unit")]
+    pub force: String,
+    /// The moment unit, `Nm` by default.
+    #[serde(default = "default_moment_unit")]
+    pub moment: String,
+    /// The stress unit, `MPa` by default.
+    #[serde(default = "default_stress_unit")]
+    pub stress: String,
+}
+
+fn default_force_unit() -> String {
+    "N".to_string()
+}
+
+fn default_moment_unit() -> String {
+    "Nm".to_string()
+}
+
+fn default_stress_unit() -> String {
+    "MPa".to_string()
+}
+
+impl Default for UnitSystem {
+    fn default() -> Self {
+        UnitSystem {
+            force: default_force_unit(),
+            moment: default_moment_unit(),
+            stress: default_stress_unit(),
+        }
+    }
+}
+
+impl UnitSystem {
+    /// Validates that each unit is known and measures the quantity it is declared for.
+    pub fn validate(&self) -> Result<()> {
+        for quantity in [Quantity::Force, Quantity::Moment, Quantity::Stress] {
+            self.unit(quantity)?;
+        }
+        Ok(())
+    }
+
+    /// Returns the unit sensor channels of a quantity are converted to: the declared unit
+    /// for forces, moments and stresses, and the base unit for strains.
+    pub fn channel_unit(&self, quantity: Quantity) -> Result<Unit> {
+        match quantity {
+            Quantity::Strain => Unit::parse("m/m"),
+            quantity => self.unit(quantity),
+        }
+    }
+
+    /// Returns the declared unit of a quantity.
+    ///
+    /// # Returns
+    ///
+    /// Returns the `Unit`, or an error if the declared unit is unknown, measures a
+    /// different quantity or if no unit is declared for the quantity.
+    pub fn unit(&self, quantity: Quantity) -> Result<Unit> {
+        let symbol = match quantity {
+            Quantity::Force => &self.force,
+            Quantity::Moment => &self.moment,
+            Quantity::Stress => &self.stress,
+            Quantity::Strain => return Err(anyhow!("units do not declare a strain unit")),
+        };
+        let unit = Unit::parse(symbol).map_err(|e| anyhow!("units.{}: {}", quantity, e))?;
+        if unit.quantity != quantity {
+            return Err(anyhow!(
+                "units.{} must be a {} unit, got {} ({})",
+                quantity,
+                quantity,
+                symbol,
+                unit.quantity
+            ));
+        }
+        Ok(unit)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn test_parse_units() {
+        assert_eq!(Unit::parse("[N]").unwrap().quantity, Quantity::Force);
+        assert_eq!(Unit::parse(" MN ").unwrap().factor, 1.0e6);
+        assert_eq!(Unit::parse("kN*m").unwrap().symbol, "kNm");
+        assert_eq!(Unit::parse("Pa").unwrap().base().symbol, "MPa");
+        assert_relative_eq!(Unit::parse("με").unwrap().to_base(250.0), 2.5e-4);
+        assert!(!Unit::parse("MPa").unwrap().quantity.is_load());
+    }
+
+    #[test]
+    fn test_parse_measure() {
+        let mpa = Unit::parse("MPa").unwrap();
+        let (value, unit) = parse_measure(" 210 GPa").unwrap();
+        assert_relative_eq!(unit.convert(value, &mpa).unwrap(), 210000.0);
+        let (value, unit) = parse_measure("355000kPa").unwrap();
+        assert_relative_eq!(unit.convert(value, &mpa).unwrap(), 355.0);
+        let (value, unit) = parse_measure("1.5e3 N").unwrap();
+        assert_relative_eq!(unit.to_base(value), 1500.0);
+
+        assert!(format!("{:#}", parse_measure("210").unwrap_err()).contains("missing unit"));
+        assert!(format!("{:#}", parse_measure("GPa").unwrap_err()).contains("invalid number"));
+        let err = unit.convert(1.0, &mpa).unwrap_err();
+        assert!(format!("{err:#}").contains("cannot convert N (force) to MPa (stress)"));
+    }
+
+    #[test]
+    fn test_unknown_unit() {
+        let err = Unit::parse("[lbf]").unwrap_err();
+        let message = format!("{err:#}");
+        assert!(message.contains("unknown unit '[lbf]'"));
+        assert!(message.contains("kNm"));
+    }
+}